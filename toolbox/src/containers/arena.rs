@@ -0,0 +1,420 @@
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::marker::PhantomData;
+use std::ops::Index;
+use std::ops::IndexMut;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* HANDLE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Handle<T> {
+    index: u32,
+    generation: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn build(index: u32, generation: u32) -> Self {
+        Self { index, generation, marker: PhantomData }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.index, self.generation).hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({}v{})", self.index, self.generation)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* ARENA */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    generation: u32,
+    dense: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Arena<T> {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    values: Vec<T>,
+    owners: Vec<u32>,
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            values: Vec::with_capacity(capacity),
+            owners: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        let dense = Some(self.values.len() as u32);
+        let index = match self.free.pop() {
+            | Some(index) => {
+                self.slots[index as usize].dense = dense;
+                index
+            }
+            | None => {
+                self.slots.push(Slot { generation: 0, dense });
+                (self.slots.len() - 1) as u32
+            }
+        };
+
+        self.values.push(value);
+        self.owners.push(index);
+        Handle::build(index, self.slots[index as usize].generation)
+    }
+
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let dense = self.dense(handle)?;
+        let slot = &mut self.slots[handle.index()];
+        slot.dense = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+
+        let value = self.values.swap_remove(dense);
+        self.owners.swap_remove(dense);
+        if let Some(&moved) = self.owners.get(dense) {
+            self.slots[moved as usize].dense = Some(dense as u32);
+        }
+        Some(value)
+    }
+
+    pub fn contains(&self, handle: Handle<T>) -> bool {
+        self.dense(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        self.dense(handle).map(|dense| &self.values[dense])
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        self.dense(handle).map(|dense| &mut self.values[dense])
+    }
+
+    pub fn get_pair_mut(&mut self, a: Handle<T>, b: Handle<T>) -> Option<(&mut T, &mut T)> {
+        let (first, second) = (self.dense(a)?, self.dense(b)?);
+        match first.cmp(&second) {
+            | std::cmp::Ordering::Equal => None,
+            | std::cmp::Ordering::Less => {
+                let (low, high) = self.values.split_at_mut(second);
+                Some((&mut low[first], &mut high[0]))
+            }
+            | std::cmp::Ordering::Greater => {
+                let (low, high) = self.values.split_at_mut(first);
+                Some((&mut high[0], &mut low[second]))
+            }
+        }
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    pub fn handles(&self) -> impl ExactSizeIterator<Item = Handle<T>> + '_ {
+        self.owners.iter().map(|&index| Handle::build(index, self.slots[index as usize].generation))
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Handle<T>, &T)> {
+        self.handles().zip(&self.values)
+    }
+
+    pub fn iter_mut(&mut self) -> impl ExactSizeIterator<Item = (Handle<T>, &mut T)> {
+        let slots = &self.slots;
+        let handles = self.owners.iter().map(|&index| Handle::build(index, slots[index as usize].generation));
+        handles.zip(&mut self.values)
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(Handle<T>, &mut T) -> bool) {
+        let mut dense = 0;
+        while dense < self.values.len() {
+            let index = self.owners[dense];
+            let handle = Handle::build(index, self.slots[index as usize].generation);
+            match keep(handle, &mut self.values[dense]) {
+                | true => dense += 1,
+                | false => _ = self.remove(handle),
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.owners.drain(..).for_each(|index| {
+            let slot = &mut self.slots[index as usize];
+            slot.dense = None;
+            slot.generation = slot.generation.wrapping_add(1);
+            self.free.push(index);
+        });
+        self.values.clear();
+    }
+
+    fn dense(&self, handle: Handle<T>) -> Option<usize> {
+        match self.slots.get(handle.index())? {
+            | Slot { generation, dense: Some(dense) } if *generation == handle.generation => Some(*dense as usize),
+            | _ => None,
+        }
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Handle<T>> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle<T>) -> &Self::Output {
+        self.get(handle).expect("stale arena handle")
+    }
+}
+
+impl<T> IndexMut<Handle<T>> for Arena<T> {
+    fn index_mut(&mut self, handle: Handle<T>) -> &mut Self::Output {
+        self.get_mut(handle).expect("stale arena handle")
+    }
+}
+
+impl<T> Extend<T> for Arena<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| _ = self.insert(value));
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SECONDARY MAP */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone)]
+pub struct SecondaryMap<K, V> {
+    slots: Vec<Option<(u32, V)>>,
+    len: usize,
+    marker: PhantomData<fn() -> K>,
+}
+
+impl<K, V> SecondaryMap<K, V> {
+    pub fn new() -> Self {
+        Self { slots: Vec::new(), len: 0, marker: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // a handle older than the stored entry is stale, the value is handed back instead of being dropped
+    pub fn insert(&mut self, handle: Handle<K>, value: V) -> Result<Option<V>, V> {
+        if handle.index() >= self.slots.len() {
+            self.slots.resize_with(handle.index() + 1, || None);
+        }
+
+        let slot = &mut self.slots[handle.index()];
+        match slot {
+            | Some((generation, _)) if (handle.generation.wrapping_sub(*generation) as i32) < 0 => return Err(value),
+            | Some(_) => {}
+            | None => self.len += 1,
+        }
+
+        match slot.replace((handle.generation, value)) {
+            | Some((generation, previous)) if generation == handle.generation => Ok(Some(previous)),
+            | _ => Ok(None),
+        }
+    }
+
+    pub fn remove(&mut self, handle: Handle<K>) -> Option<V> {
+        let slot = self.slots.get_mut(handle.index())?;
+        match slot {
+            | Some((generation, _)) if *generation == handle.generation => {
+                self.len -= 1;
+                slot.take().map(|(_, value)| value)
+            }
+            | _ => None,
+        }
+    }
+
+    pub fn contains_key(&self, handle: Handle<K>) -> bool {
+        self.get(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle<K>) -> Option<&V> {
+        match self.slots.get(handle.index())? {
+            | Some((generation, value)) if *generation == handle.generation => Some(value),
+            | _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<K>) -> Option<&mut V> {
+        match self.slots.get_mut(handle.index())? {
+            | Some((generation, value)) if *generation == handle.generation => Some(value),
+            | _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<K>, &V)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.as_ref().map(|(generation, value)| (Handle::build(index as u32, *generation), value))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<K>, &mut V)> {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            slot.as_mut().map(|(generation, value)| (Handle::build(index as u32, *generation), value))
+        })
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(Handle<K>, &mut V) -> bool) {
+        self.slots.iter_mut().enumerate().for_each(|(index, slot)| {
+            if let Some((generation, value)) = slot
+                && !keep(Handle::build(index as u32, *generation), value)
+            {
+                *slot = None;
+                self.len -= 1;
+            }
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.len = 0;
+    }
+}
+
+impl<K, V> Default for SecondaryMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Index<Handle<K>> for SecondaryMap<K, V> {
+    type Output = V;
+
+    fn index(&self, handle: Handle<K>) -> &Self::Output {
+        self.get(handle).expect("missing secondary map entry")
+    }
+}
+
+impl<K, V> IndexMut<Handle<K>> for SecondaryMap<K, V> {
+    fn index_mut(&mut self, handle: Handle<K>) -> &mut Self::Output {
+        self.get_mut(handle).expect("missing secondary map entry")
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::random::Rng;
+    use crate::math::random::Xoshiro256;
+
+    #[test]
+    fn stale_handles() {
+        let mut arena = Arena::new();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+        assert!(arena.remove(a) == Some("a") && arena.remove(a).is_none() && arena.get(a).is_none());
+
+        let c = arena.insert("c");
+        assert!(c.index() == a.index() && c != a && !arena.contains(a) && arena[c] == "c");
+        assert!(arena.get_pair_mut(b, c).is_some() && arena.get_pair_mut(b, b).is_none());
+
+        arena.clear();
+        assert!(arena.is_empty() && arena.get(b).is_none() && arena.get(c).is_none());
+    }
+
+    #[test]
+    fn dense_iteration() {
+        let mut rng = Xoshiro256::build(5);
+        let mut arena = Arena::new();
+        let mut live: Vec<(Handle<u32>, u32)> = Vec::new();
+
+        (0..2000u32).for_each(|i| match live.is_empty() || rng.uniform(0., 1.) < 0.6 {
+            | true => live.push((arena.insert(i), i)),
+            | false => {
+                let (handle, value) = live.swap_remove(rng.uniform(0., live.len() as f64) as usize);
+                assert!(arena.remove(handle) == Some(value));
+            }
+        });
+
+        assert!(arena.len() == live.len() && arena.values().len() == live.len());
+        assert!(live.iter().all(|&(handle, value)| arena[handle] == value));
+        assert!(arena.iter().all(|(handle, &value)| live.contains(&(handle, value))));
+
+        arena.iter_mut().for_each(|(_, value)| *value *= 2);
+        arena.retain(|_, value| value.is_multiple_of(4));
+        assert!(live.iter().all(|&(handle, value)| arena.get(handle) == (value % 2 == 0).then_some(&(value * 2))));
+    }
+
+    #[test]
+    fn secondary_map() {
+        let mut arena = Arena::new();
+        let handles: Vec<_> = (0..5).map(|i| arena.insert(i)).collect();
+        let mut names = SecondaryMap::new();
+        handles.iter().for_each(|&handle| _ = names.insert(handle, format!("#{}", arena[handle])));
+
+        arena.remove(handles[1]);
+        let reused = arena.insert(9);
+        assert!(names.get(reused).is_none() && names.insert(reused, "#9".into()) == Ok(None));
+        assert!(names.insert(handles[1], "stale".into()) == Err("stale".into()) && names[reused] == "#9");
+        assert!(names.insert(reused, "#9".into()) == Ok(Some("#9".into())));
+
+        names.retain(|handle, _| arena.contains(handle));
+        assert!(names.len() == 5 && names.remove(handles[0]) == Some("#0".into()) && names.len() == 4);
+        assert!(names.iter().all(|(handle, name)| *name == format!("#{}", arena[handle])));
+    }
+}
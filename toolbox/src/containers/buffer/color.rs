@@ -0,0 +1,401 @@
+use super::Buffer2;
+use super::Color;
+use super::PackedColor;
+use super::pack_color;
+use crate::math::matrix::Matrix3;
+use crate::math::vector::Vector3;
+use crate::math::vector::Vector4;
+use crate::vec3;
+use crate::vec4;
+
+pub type Rgba = Vector4<f32>;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PACKING */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn to_byte(value: f32) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
+}
+
+pub fn pack_rgba(color: Rgba) -> PackedColor {
+    let [r, g, b, a] = color.array().map(to_byte);
+    u32::from_be_bytes([a, r, g, b])
+}
+
+pub fn unpack_rgba(color: PackedColor) -> Rgba {
+    let [a, r, g, b] = color.to_be_bytes();
+    vec4!(r, g, b, a) / 255.
+}
+
+pub fn premultiply(color: Rgba) -> Rgba {
+    vec4!(color.x * color.w, color.y * color.w, color.z * color.w, color.w)
+}
+
+pub fn unpremultiply(color: Rgba) -> Rgba {
+    match color.w > 0. {
+        | true => vec4!(color.x / color.w, color.y / color.w, color.z / color.w, color.w),
+        | false => Rgba::zeros(),
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* COLOR SPACES */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+const LINEAR_TO_XYZ: Matrix3<f32> = Matrix3::build([
+    [0.412_456_4, 0.357_576_1, 0.180_437_5],
+    [0.212_672_9, 0.715_152_2, 0.072_175],
+    [0.019_333_9, 0.119_192, 0.950_304_1],
+]);
+
+const XYZ_TO_LINEAR: Matrix3<f32> = Matrix3::build([
+    [3.240_454_2, -1.537_138_5, -0.498_531_4],
+    [-0.969_266, 1.876_010_8, 0.041_556],
+    [0.055_643_4, -0.204_025_9, 1.057_225_2],
+]);
+
+const LINEAR_TO_LMS: Matrix3<f32> = Matrix3::build([
+    [0.412_221_46, 0.536_332_55, 0.051_445_995],
+    [0.211_903_5, 0.680_699_5, 0.107_396_96],
+    [0.088_302_46, 0.281_718_85, 0.629_978_7],
+]);
+
+const LMS_TO_OKLAB: Matrix3<f32> = Matrix3::build([
+    [0.210_454_26, 0.793_617_8, -0.004_072_047],
+    [1.977_998_5, -2.428_592_2, 0.450_593_7],
+    [0.025_904_037, 0.782_771_77, -0.808_675_77],
+]);
+
+const OKLAB_TO_LMS: Matrix3<f32> = Matrix3::build([
+    [1., 0.396_337_78, 0.215_803_76],
+    [1., -0.105_561_346, -0.063_854_17],
+    [1., -0.089_484_18, -1.291_485_5],
+]);
+
+const LMS_TO_LINEAR: Matrix3<f32> = Matrix3::build([
+    [4.076_741_7, -3.307_711_6, 0.230_969_94],
+    [-1.268_438, 2.609_757_4, -0.341_319_4],
+    [-0.004_196_086_3, -0.703_418_6, 1.707_614_7],
+]);
+
+fn map(color: Color, f: impl Fn(f32) -> f32) -> Color {
+    vec3!(f(color.x), f(color.y), f(color.z))
+}
+
+pub fn srgb_decode(value: f32) -> f32 {
+    match value <= 0.040_45 {
+        | true => value / 12.92,
+        | false => ((value + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+pub fn srgb_encode(value: f32) -> f32 {
+    match value <= 0.003_130_8 {
+        | true => value * 12.92,
+        | false => 1.055 * value.powf(1. / 2.4) - 0.055,
+    }
+}
+
+pub fn srgb_to_linear(color: Color) -> Color {
+    map(color, srgb_decode)
+}
+
+pub fn linear_to_srgb(color: Color) -> Color {
+    map(color, srgb_encode)
+}
+
+pub fn linear_to_xyz(color: Color) -> Color {
+    LINEAR_TO_XYZ * color
+}
+
+pub fn xyz_to_linear(color: Color) -> Color {
+    XYZ_TO_LINEAR * color
+}
+
+pub fn linear_to_oklab(color: Color) -> Color {
+    LMS_TO_OKLAB * map(LINEAR_TO_LMS * color, f32::cbrt)
+}
+
+pub fn oklab_to_linear(color: Color) -> Color {
+    LMS_TO_LINEAR * map(OKLAB_TO_LMS * color, |value| value * value * value)
+}
+
+fn hue(color: Color, max: f32, delta: f32) -> f32 {
+    let [r, g, b] = color.array();
+    let sector = match delta == 0. {
+        | true => 0.,
+        | false if max == r => (g - b) / delta,
+        | false if max == g => (b - r) / delta + 2.,
+        | false => (r - g) / delta + 4.,
+    };
+    (sector * 60.).rem_euclid(360.)
+}
+
+pub fn rgb_to_hsv(color: Color) -> Color {
+    let max = color.x.max(color.y).max(color.z);
+    let delta = max - color.x.min(color.y).min(color.z);
+    let saturation = match max > 0. {
+        | true => delta / max,
+        | false => 0.,
+    };
+    vec3!(hue(color, max, delta), saturation, max)
+}
+
+pub fn hsv_to_rgb(color: Color) -> Color {
+    let [h, s, v] = color.array();
+    let channel = |n: f32| {
+        let k = (n + h / 60.).rem_euclid(6.);
+        v - v * s * k.min(4. - k).clamp(0., 1.)
+    };
+    vec3!(channel(5.), channel(3.), channel(1.))
+}
+
+pub fn rgb_to_hsl(color: Color) -> Color {
+    let (max, min) = (color.x.max(color.y).max(color.z), color.x.min(color.y).min(color.z));
+    let (delta, lightness) = (max - min, (max + min) / 2.);
+    let saturation = match lightness > 0. && lightness < 1. {
+        | true => delta / (1. - (2. * lightness - 1.).abs()),
+        | false => 0.,
+    };
+    vec3!(hue(color, max, delta), saturation, lightness)
+}
+
+pub fn hsl_to_rgb(color: Color) -> Color {
+    let [h, s, l] = color.array();
+    let a = s * l.min(1. - l);
+    let channel = |n: f32| {
+        let k = (n + h / 30.).rem_euclid(12.);
+        l - a * (k - 3.).min(9. - k).clamp(-1., 1.)
+    };
+    vec3!(channel(0.), channel(8.), channel(4.))
+}
+
+pub fn luminance(linear: Color) -> f32 {
+    LINEAR_TO_XYZ.inner[1][0] * linear.x + LINEAR_TO_XYZ.inner[1][1] * linear.y + LINEAR_TO_XYZ.inner[1][2] * linear.z
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* COMPOSITING */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blend {
+    Clear,
+    Source,
+    Destination,
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+    Plus,
+}
+
+impl Blend {
+    pub fn factors(self, source_alpha: f32, destination_alpha: f32) -> (f32, f32) {
+        let (sa, da) = (source_alpha, destination_alpha);
+        match self {
+            | Self::Clear => (0., 0.),
+            | Self::Source => (1., 0.),
+            | Self::Destination => (0., 1.),
+            | Self::SourceOver => (1., 1. - sa),
+            | Self::DestinationOver => (1. - da, 1.),
+            | Self::SourceIn => (da, 0.),
+            | Self::DestinationIn => (0., sa),
+            | Self::SourceOut => (1. - da, 0.),
+            | Self::DestinationOut => (0., 1. - sa),
+            | Self::SourceAtop => (da, 1. - sa),
+            | Self::DestinationAtop => (1. - da, sa),
+            | Self::Xor => (1. - da, 1. - sa),
+            | Self::Plus => (1., 1.),
+        }
+    }
+
+    pub fn apply(self, source: Rgba, destination: Rgba) -> Rgba {
+        let (fs, fd) = self.factors(source.w, destination.w);
+        let out = source * fs + destination * fd;
+        vec4!(out.x.min(1.), out.y.min(1.), out.z.min(1.), out.w.min(1.))
+    }
+}
+
+pub fn blend(source: PackedColor, destination: PackedColor, mode: Blend) -> PackedColor {
+    let out = mode.apply(premultiply(unpack_rgba(source)), premultiply(unpack_rgba(destination)));
+    pack_rgba(unpremultiply(out))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TONE MAPPING */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    Clamp,
+    Reinhard,
+    ReinhardExtended(f32),
+    AcesFilmic,
+}
+
+impl ToneMap {
+    pub fn apply(self, hdr: Color) -> Color {
+        let hdr = map(hdr, |value| value.max(0.));
+        match self {
+            | Self::Clamp => map(hdr, |value| value.min(1.)),
+            | Self::Reinhard => map(hdr, |value| value / (1. + value)),
+            | Self::ReinhardExtended(white) => {
+                map(hdr, |value| (value * (1. + value / (white * white)) / (1. + value)).min(1.))
+            }
+            | Self::AcesFilmic => map(hdr, |value| {
+                ((value * (2.51 * value + 0.03)) / (value * (2.43 * value + 0.59) + 0.14)).clamp(0., 1.)
+            }),
+        }
+    }
+}
+
+pub fn tone_map(image: &Buffer2<Color>, operator: ToneMap, exposure: f32) -> Buffer2<Color> {
+    let mut out = Buffer2::new(image.width, image.height, operator.apply(image.clear_value * exposure));
+    out.data.iter_mut().zip(&image.data).for_each(|(out, &hdr)| *out = operator.apply(hdr * exposure));
+    out
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* DITHERING */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+const BAYER: [[f32; 4]; 4] = [
+    [0., 8., 2., 10.],
+    [12., 4., 14., 6.],
+    [3., 11., 1., 9.],
+    [15., 7., 13., 5.],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dither {
+    None,
+    Ordered,
+    FloydSteinberg,
+}
+
+pub fn pack_dithered(image: &Buffer2<Color>, dither: Dither, levels: u32) -> Buffer2<PackedColor> {
+    let steps = levels.clamp(2, 256) as f32 - 1.;
+    let quantize = |value: f32, threshold: f32| (value.clamp(0., 1.) * steps + threshold).floor().min(steps) / steps;
+
+    let mut out = Buffer2::new(image.width, image.height, pack_color(image.clear_value));
+    match dither {
+        | Dither::None => out.data.iter_mut().zip(&image.data).for_each(|(out, &color)| {
+            *out = pack_color(map(color, |value| quantize(value, 0.5)));
+        }),
+        | Dither::Ordered => out.data.iter_mut().zip(&image.data).enumerate().for_each(|(i, (out, &color))| {
+            let threshold = (BAYER[i / image.width % 4][i % image.width % 4] + 0.5) / 16.;
+            *out = pack_color(map(color, |value| quantize(value, threshold)));
+        }),
+        | Dither::FloydSteinberg => {
+            let mut work = image.data.clone();
+            let (width, height) = (image.width, image.height);
+            (0..height).for_each(|y| {
+                (0..width).for_each(|x| {
+                    let color = work[y * width + x];
+                    let quantized = map(color, |value| quantize(value, 0.5));
+                    out.data[y * width + x] = pack_color(quantized);
+
+                    let error = color - quantized;
+                    let mut spread = |dx: isize, dy: usize, weight: f32| {
+                        let nx = x as isize + dx;
+                        if nx >= 0 && (nx as usize) < width && y + dy < height {
+                            work[(y + dy) * width + nx as usize] += error * weight;
+                        }
+                    };
+                    spread(1, 0, 7. / 16.);
+                    spread(-1, 1, 3. / 16.);
+                    spread(0, 1, 5. / 16.);
+                    spread(1, 1, 1. / 16.);
+                });
+            });
+        }
+    }
+    out
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::super::unpack_color;
+    use super::*;
+
+    fn close(a: Color, b: Color, epsilon: f32) -> bool {
+        (a - b).array().iter().all(|delta| delta.abs() < epsilon)
+    }
+
+    #[test]
+    fn packing() {
+        assert!(pack_color(vec3!(1.5, 0.5, -0.2)) == 0xffff_8000);
+        assert!(pack_color(vec3!(0.999, 0.002, 0.5)) == 0xffff_0180);
+        assert!((0..=255u32).all(|v| pack_color(unpack_color(0xff00_0000 | v << 8)) == 0xff00_0000 | v << 8));
+
+        let rgba = unpack_rgba(0x80ff_4020);
+        assert!(pack_rgba(rgba) == 0x80ff_4020 && (rgba.w - 128. / 255.).abs() < 1e-6);
+        assert!(pack_rgba(unpremultiply(premultiply(rgba))) == 0x80ff_4020);
+        assert!(unpremultiply(vec4!(0.3, 0.3, 0.3, 0.)) == Rgba::zeros());
+    }
+
+    #[test]
+    fn color_spaces() {
+        assert!((srgb_decode(0.5) - 0.214_041).abs() < 1e-5 && (srgb_encode(srgb_decode(0.73)) - 0.73).abs() < 1e-5);
+
+        let white = linear_to_xyz(Vector3::splat(1.));
+        assert!(close(white, vec3!(0.950_47, 1., 1.088_83), 1e-4) && (luminance(Vector3::splat(1.)) - 1.).abs() < 1e-5);
+        assert!(close(linear_to_oklab(Vector3::splat(1.)), vec3!(1., 0., 0.), 1e-4));
+        assert!(close(linear_to_oklab(vec3!(1., 0., 0.)), vec3!(0.627_955, 0.224_863, 0.125_846), 1e-4));
+
+        assert!(
+            rgb_to_hsv(vec3!(1., 0., 0.)) == vec3!(0., 1., 1.) && rgb_to_hsl(vec3!(0., 1., 0.)) == vec3!(120., 1., 0.5)
+        );
+        assert!(close(rgb_to_hsv(vec3!(0.2, 0.4, 0.8)), vec3!(220., 0.75, 0.8), 1e-5));
+
+        (0..64).map(|i| vec3!(i % 4, i / 4 % 4, i / 16) / 3.).for_each(|color| {
+            assert!(close(hsv_to_rgb(rgb_to_hsv(color)), color, 1e-5));
+            assert!(close(hsl_to_rgb(rgb_to_hsl(color)), color, 1e-5));
+            assert!(close(xyz_to_linear(linear_to_xyz(color)), color, 1e-4));
+            assert!(close(oklab_to_linear(linear_to_oklab(color)), color, 1e-3));
+        });
+    }
+
+    #[test]
+    fn compositing() {
+        let (red, blue) = (0x80ff_0000, 0xff00_00ff);
+        assert!(blend(red, blue, Blend::SourceOver) == 0xff80_007f);
+        assert!(blend(red, blue, Blend::DestinationOver) == blue);
+        assert!(blend(red, blue, Blend::SourceIn) == red && blend(red, blue, Blend::SourceOut) == 0);
+        assert!(blend(red, blue, Blend::DestinationOut) == 0x7f00_00ff);
+        assert!(blend(red, blue, Blend::Xor) == 0x7f00_00ff && blend(red, blue, Blend::Clear) == 0);
+        assert!(blend(red, 0x8000_ff00, Blend::Plus) == 0xff80_8000);
+
+        let (source, destination) = (premultiply(vec4!(1., 1., 0., 0.5)), premultiply(vec4!(0., 0., 1., 0.25)));
+        let atop = Blend::SourceAtop.apply(source, destination);
+        assert!((atop.w - 0.25).abs() < 1e-6 && (atop.x - 0.125).abs() < 1e-6 && (atop.z - 0.125).abs() < 1e-6);
+    }
+
+    #[test]
+    fn tone_mapping_and_dithering() {
+        let hdr = vec3!(0., 1., 9.);
+        assert!(ToneMap::Clamp.apply(hdr) == vec3!(0., 1., 1.) && ToneMap::Reinhard.apply(hdr) == vec3!(0., 0.5, 0.9));
+        assert!(ToneMap::ReinhardExtended(9.).apply(hdr).z == 1.);
+        let aces = ToneMap::AcesFilmic.apply(vec3!(0.18, 1., 100.));
+        assert!((aces.x - 0.266_9).abs() < 1e-4 && (aces.y - 0.803_8).abs() < 1e-3 && aces.z == 1.);
+
+        let image = Buffer2::new(16, 16, Vector3::splat(0.25));
+        let tone = tone_map(&image, ToneMap::Reinhard, 3.);
+        assert!(tone.data.iter().all(|&color| color == Vector3::splat(0.75 / 1.75)));
+
+        let white = |packed: &Buffer2<PackedColor>| packed.data.iter().filter(|&&color| color == 0xffff_ffff).count();
+        assert!(white(&pack_dithered(&image, Dither::None, 2)) == 0);
+        assert!(white(&pack_dithered(&image, Dither::Ordered, 2)) == 64);
+        assert!((56..=72).contains(&white(&pack_dithered(&image, Dither::FloydSteinberg, 2))));
+        let fine = pack_dithered(&image, Dither::FloydSteinberg, 256);
+        let mean = fine.data.iter().map(|&color| (color & 0xff) as f32).sum::<f32>() / 256.;
+        assert!(
+            fine.data.iter().all(|&color| matches!(color, 0xff3f_3f3f | 0xff40_4040)) && (mean - 63.75).abs() < 0.1
+        );
+    }
+}
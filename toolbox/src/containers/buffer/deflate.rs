@@ -0,0 +1,460 @@
+use std::fmt;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const WINDOW: usize = 1 << 15;
+const HASH_BITS: usize = 15;
+const MAX_CHAIN: usize = 64;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InflateError {
+    Truncated,
+    Header,
+    InvalidBlock,
+    InvalidCode,
+    InvalidDistance,
+    Checksum,
+    TooLarge,
+}
+
+impl fmt::Display for InflateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            | Self::Truncated => "compressed stream ended early",
+            | Self::Header => "invalid zlib header",
+            | Self::InvalidBlock => "invalid deflate block",
+            | Self::InvalidCode => "invalid huffman code",
+            | Self::InvalidDistance => "back reference before start of output",
+            | Self::Checksum => "adler32 checksum mismatch",
+            | Self::TooLarge => "decompressed output exceeds the limit",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for InflateError {}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* ZLIB */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    data.chunks(5552).for_each(|chunk| {
+        chunk.iter().for_each(|&byte| {
+            a += byte as u32;
+            b += a;
+        });
+        (a, b) = (a % 65521, b % 65521);
+    });
+    (b << 16) | a
+}
+
+pub fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x9c];
+    out.extend(deflate(data));
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+pub fn zlib_decompress(data: &[u8]) -> Result<Vec<u8>, InflateError> {
+    zlib_decompress_limited(data, usize::MAX)
+}
+
+pub fn zlib_decompress_limited(data: &[u8], max_output: usize) -> Result<Vec<u8>, InflateError> {
+    let [cmf, flg, ..] = *data else {
+        return Err(InflateError::Truncated);
+    };
+    if cmf & 0x0f != 8 || !(cmf as u16 * 256 + flg as u16).is_multiple_of(31) || flg & 0x20 != 0 {
+        return Err(InflateError::Header);
+    }
+
+    let body = data.get(2..data.len().saturating_sub(4)).ok_or(InflateError::Truncated)?;
+    let out = inflate_limited(body, max_output)?;
+    let checksum = data.get(data.len() - 4..).ok_or(InflateError::Truncated)?;
+    match adler32(&out).to_be_bytes() == checksum {
+        | true => Ok(out),
+        | false => Err(InflateError::Checksum),
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* DEFLATE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+struct BitWriter {
+    out: Vec<u8>,
+    bits: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        self.bits |= (value as u64) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn write_code(&mut self, code: u32, length: u32) {
+        self.write(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.bits as u8);
+        }
+        self.out
+    }
+}
+
+fn write_literal(writer: &mut BitWriter, symbol: u32) {
+    match symbol {
+        | 0..=143 => writer.write_code(0x30 + symbol, 8),
+        | 144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+        | 256..=279 => writer.write_code(symbol - 256, 7),
+        | _ => writer.write_code(0xc0 + symbol - 280, 8),
+    }
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let code = LENGTH_BASE.iter().rposition(|&base| base as usize <= length).unwrap_or(0);
+    write_literal(writer, 257 + code as u32);
+    writer.write((length - LENGTH_BASE[code] as usize) as u32, LENGTH_EXTRA[code] as u32);
+
+    let code = DISTANCE_BASE.iter().rposition(|&base| base as usize <= distance).unwrap_or(0);
+    writer.write_code(code as u32, 5);
+    writer.write((distance - DISTANCE_BASE[code] as usize) as u32, DISTANCE_EXTRA[code] as u32);
+}
+
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter {
+        out: Vec::with_capacity(data.len() / 2 + 16),
+        bits: 0,
+        count: 0,
+    };
+    writer.write(1, 1);
+    writer.write(1, 2);
+
+    let hash = |at: usize| {
+        let key = (data[at] as usize) << 16 | (data[at + 1] as usize) << 8 | data[at + 2] as usize;
+        (key.wrapping_mul(2_654_435_761) >> 8) & ((1 << HASH_BITS) - 1)
+    };
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut previous = vec![usize::MAX; WINDOW];
+    let insert = |at: usize, head: &mut [usize], previous: &mut [usize]| {
+        if at + MIN_MATCH <= data.len() {
+            let key = hash(at);
+            previous[at % WINDOW] = head[key];
+            head[key] = at;
+        }
+    };
+
+    let mut position = 0;
+    while position < data.len() {
+        let (mut best_length, mut best_distance) = (0, 0);
+        if position + MIN_MATCH <= data.len() {
+            let limit = (data.len() - position).min(MAX_MATCH);
+            let mut candidate = head[hash(position)];
+            for _ in 0..MAX_CHAIN {
+                if candidate == usize::MAX || position - candidate > WINDOW - 1 {
+                    break;
+                }
+
+                let length = (0..limit).take_while(|&i| data[candidate + i] == data[position + i]).count();
+                if length > best_length {
+                    (best_length, best_distance) = (length, position - candidate);
+                    if length == limit {
+                        break;
+                    }
+                }
+
+                let next = previous[candidate % WINDOW];
+                if next == usize::MAX || next >= candidate {
+                    break;
+                }
+                candidate = next;
+            }
+        }
+
+        match best_length >= MIN_MATCH {
+            | true => {
+                write_match(&mut writer, best_length, best_distance);
+                (position..position + best_length).for_each(|at| insert(at, &mut head, &mut previous));
+                position += best_length;
+            }
+            | false => {
+                write_literal(&mut writer, data[position] as u32);
+                insert(position, &mut head, &mut previous);
+                position += 1;
+            }
+        }
+    }
+
+    write_literal(&mut writer, 256);
+    writer.finish()
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* INFLATE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+struct BitReader<'d> {
+    data: &'d [u8],
+    position: usize,
+    bits: u32,
+    count: u32,
+}
+
+impl BitReader<'_> {
+    fn bit(&mut self) -> Result<u32, InflateError> {
+        if self.count == 0 {
+            self.bits = *self.data.get(self.position).ok_or(InflateError::Truncated)? as u32;
+            self.position += 1;
+            self.count = 8;
+        }
+
+        let bit = self.bits & 1;
+        self.bits >>= 1;
+        self.count -= 1;
+        Ok(bit)
+    }
+
+    fn read(&mut self, count: u32) -> Result<u32, InflateError> {
+        (0..count).try_fold(0, |value, i| Ok(value | self.bit()? << i))
+    }
+
+    fn align(&mut self) {
+        self.count = 0;
+    }
+}
+
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn build(lengths: &[u8]) -> Result<Self, InflateError> {
+        let mut counts = [0u16; 16];
+        lengths.iter().for_each(|&length| counts[length as usize] += 1);
+        counts[0] = 0;
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(InflateError::InvalidCode);
+            }
+        }
+
+        let mut offsets = [0u16; 16];
+        (1..15).for_each(|length| offsets[length + 1] = offsets[length] + counts[length]);
+        let mut symbols = vec![0; lengths.len()];
+        lengths.iter().enumerate().filter(|&(_, &length)| length > 0).for_each(|(symbol, &length)| {
+            symbols[offsets[length as usize] as usize] = symbol as u16;
+            offsets[length as usize] += 1;
+        });
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, InflateError> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= reader.bit()? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(InflateError::InvalidCode)
+    }
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    let literals = Huffman::build(&lengths).unwrap_or(Huffman { counts: [0; 16], symbols: Vec::new() });
+    let distances = Huffman::build(&[5; 30]).unwrap_or(Huffman { counts: [0; 16], symbols: Vec::new() });
+    (literals, distances)
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman), InflateError> {
+    let literal_count = reader.read(5)? as usize + 257;
+    let distance_count = reader.read(5)? as usize + 1;
+    let code_count = reader.read(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_count] {
+        code_lengths[index] = reader.read(3)? as u8;
+    }
+    let code_table = Huffman::build(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match code_table.decode(reader)? {
+            | symbol @ 0..=15 => (symbol as u8, 1),
+            | 16 => (*lengths.last().ok_or(InflateError::InvalidBlock)?, 3 + reader.read(2)?),
+            | 17 => (0, 3 + reader.read(3)?),
+            | 18 => (0, 11 + reader.read(7)?),
+            | _ => return Err(InflateError::InvalidCode),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+
+    if lengths.len() > literal_count + distance_count || lengths[256] == 0 {
+        return Err(InflateError::InvalidBlock);
+    }
+    Ok((Huffman::build(&lengths[..literal_count])?, Huffman::build(&lengths[literal_count..])?))
+}
+
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>, InflateError> {
+    inflate_limited(data, usize::MAX)
+}
+
+pub fn inflate_limited(data: &[u8], max_output: usize) -> Result<Vec<u8>, InflateError> {
+    let mut reader = BitReader { data, position: 0, bits: 0, count: 0 };
+    let mut out = Vec::with_capacity(data.len().saturating_mul(4).min(max_output));
+
+    loop {
+        let last = reader.bit()? == 1;
+        match reader.read(2)? {
+            | 0 => {
+                reader.align();
+                let header = data.get(reader.position..reader.position + 4).ok_or(InflateError::Truncated)?;
+                let length = u16::from_le_bytes([header[0], header[1]]);
+                if length != !u16::from_le_bytes([header[2], header[3]]) {
+                    return Err(InflateError::InvalidBlock);
+                }
+
+                reader.position += 4;
+                if out.len() + length as usize > max_output {
+                    return Err(InflateError::TooLarge);
+                }
+                let end = reader.position + length as usize;
+                out.extend_from_slice(data.get(reader.position..end).ok_or(InflateError::Truncated)?);
+                reader.position = end;
+            }
+            | kind @ (1 | 2) => {
+                let (literals, distances) = match kind {
+                    | 1 => fixed_tables(),
+                    | _ => dynamic_tables(&mut reader)?,
+                };
+                inflate_block(&mut reader, &literals, &distances, &mut out, max_output)?;
+            }
+            | _ => return Err(InflateError::InvalidBlock),
+        }
+
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+fn inflate_block(
+    reader: &mut BitReader,
+    literals: &Huffman,
+    distances: &Huffman,
+    out: &mut Vec<u8>,
+    max_output: usize,
+) -> Result<(), InflateError> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        match symbol {
+            | 0..=255 if out.len() >= max_output => return Err(InflateError::TooLarge),
+            | 0..=255 => out.push(symbol as u8),
+            | 256 => return Ok(()),
+            | 257..=285 => {
+                let code = symbol - 257;
+                let length = LENGTH_BASE[code] as usize + reader.read(LENGTH_EXTRA[code] as u32)? as usize;
+                let code = distances.decode(reader)? as usize;
+                if code >= DISTANCE_BASE.len() {
+                    return Err(InflateError::InvalidCode);
+                }
+
+                let distance = DISTANCE_BASE[code] as usize + reader.read(DISTANCE_EXTRA[code] as u32)? as usize;
+                if distance > out.len() {
+                    return Err(InflateError::InvalidDistance);
+                }
+                if out.len() + length > max_output {
+                    return Err(InflateError::TooLarge);
+                }
+                let start = out.len() - distance;
+                (0..length).for_each(|i| out.push(out[start + i]));
+            }
+            | _ => return Err(InflateError::InvalidCode),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = b"the quick brown fox jumps over the lazy dog, the quick brown fox jumps again".repeat(40);
+        let mut state = 1u32;
+        let noise: Vec<u8> = (0..5000)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as u8
+            })
+            .collect();
+
+        for data in [&b""[..], b"a", &[0; 70_000][..], &text, &noise] {
+            let packed = zlib_compress(data);
+            assert!(zlib_decompress(&packed).unwrap() == data);
+        }
+        assert!(zlib_compress(&text).len() < text.len() / 10);
+
+        let bomb = zlib_compress(&[0; 1 << 20]);
+        assert!(bomb.len() < 1 << 14 && zlib_decompress_limited(&bomb, 1 << 20).unwrap().len() == 1 << 20);
+        assert!(zlib_decompress_limited(&bomb, (1 << 20) - 1) == Err(InflateError::TooLarge));
+        assert!(zlib_decompress_limited(&zlib_compress(&noise), 4999) == Err(InflateError::TooLarge));
+    }
+
+    #[test]
+    fn reference_streams() {
+        // produced by zlib at level 9 (a single dynamic block) and level 0 (a stored block)
+        let dynamic = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/squares.zlib"));
+        let expected: Vec<u8> = (0..40).flat_map(|i| format!("{i} squared is {}; ", i * i).into_bytes()).collect();
+        assert!(zlib_decompress(dynamic).unwrap() == expected);
+
+        let stored = [
+            0x78, 0x01, 0x01, 0x03, 0x00, 0xfc, 0xff, 0x61, 0x62, 0x63, 0x02, 0x4d, 0x01, 0x27,
+        ];
+        assert!(zlib_decompress(&stored).unwrap() == b"abc");
+
+        assert!(zlib_decompress(&stored[..10]) == Err(InflateError::Truncated));
+        let mut corrupted = stored;
+        corrupted[13] ^= 1;
+        assert!(zlib_decompress(&corrupted) == Err(InflateError::Checksum));
+        assert!(adler32(b"Wikipedia") == 0x11e6_0398);
+    }
+}
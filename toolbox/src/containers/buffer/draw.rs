@@ -0,0 +1,543 @@
+use super::Buffer2;
+use crate::math::vector::Vector2;
+use crate::vec2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    EvenOdd,
+    NonZero,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PRIMITIVES */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+impl<T> Buffer2<T>
+where
+    T: Copy,
+{
+    pub fn plot(&mut self, x: isize, y: isize, value: T) -> Option<()> {
+        match x >= 0 && y >= 0 {
+            | true => self.set(x as usize, y as usize, value),
+            | false => None,
+        }
+    }
+
+    pub fn hline(&mut self, x0: isize, x1: isize, y: isize, value: T) {
+        if y < 0 || y >= self.height as isize {
+            return;
+        }
+
+        let (start, end) = (x0.min(x1).max(0), x0.max(x1).min(self.width as isize - 1));
+        if start <= end {
+            let row = y as usize * self.width;
+            self.data[row + start as usize..=row + end as usize].fill(value);
+        }
+    }
+
+    pub fn draw_line(&mut self, from: Vector2<isize>, to: Vector2<isize>, value: T) {
+        let (origin, target) = ([from.x as i128, from.y as i128], [to.x as i128, to.y as i128]);
+        let delta = [target[0] - origin[0], target[1] - origin[1]];
+        let size = [self.width as i128, self.height as i128];
+        let axis = (delta[1].abs() > delta[0].abs()) as usize;
+        let (major, minor, step) = (delta[axis].abs(), delta[1 - axis].abs(), delta.map(i128::signum));
+
+        // bresenham takes its minor step after k major steps at (2k * minor + major) / (2 * major), so the
+        // visible span can be solved for directly instead of walking the off-screen part of the line
+        let (mut first, mut last) = span(origin[axis], step[axis], size[axis]).unwrap_or((1, 0));
+        let (low, high) = span(origin[1 - axis], step[1 - axis], size[1 - axis])
+            .map_or((1, 0), |(low, high)| (low.max(0), high.min(minor)));
+        match minor {
+            | 0 if low > 0 || high < 0 => return,
+            | 0 => {}
+            | _ => {
+                first = first.max(ceil_div((2 * low - 1) * major, 2 * minor));
+                last = last.min(((2 * high + 1) * major - 1).div_euclid(2 * minor));
+            }
+        }
+
+        let (first, last) = (first.max(0), last.min(major));
+        let mut offset = match major {
+            | 0 => 0,
+            | _ => (2 * first * minor + major).div_euclid(2 * major),
+        };
+        (first..=last).for_each(|k| {
+            while 2 * k * minor + major >= 2 * major * (offset + 1) {
+                offset += 1;
+            }
+            let mut point = [0; 2];
+            point[axis] = origin[axis] + step[axis] * k;
+            point[1 - axis] = origin[1 - axis] + step[1 - axis] * offset;
+            self.set_unchecked(point[0] as usize, point[1] as usize, value);
+        });
+    }
+
+    pub fn draw_line_aa(&mut self, from: Vector2<f32>, to: Vector2<f32>, value: T, blend: impl Fn(T, T, f32) -> T) {
+        if ![from.x, from.y, to.x, to.y].iter().all(|value| value.is_finite()) {
+            return;
+        }
+
+        let steep = (to.y - from.y).abs() > (to.x - from.x).abs();
+        let (limit, side) = match steep {
+            | true => (self.height as f32, self.width as f32),
+            | false => (self.width as f32, self.height as f32),
+        };
+        let (mut a, mut b) = match steep {
+            | true => (vec2!(from.y, from.x), vec2!(to.y, to.x)),
+            | false => (from, to),
+        };
+        if a.x > b.x {
+            (a, b) = (b, a);
+        }
+
+        let mut plot = |major: f32, minor: f32, coverage: f32| {
+            let (x, y) = match steep {
+                | true => (minor as isize, major as isize),
+                | false => (major as isize, minor as isize),
+            };
+            if x >= 0
+                && y >= 0
+                && coverage > 0.
+                && let Some(current) = self.get(x as usize, y as usize)
+            {
+                self.set(x as usize, y as usize, blend(current, value, coverage.min(1.)));
+            }
+        };
+
+        let gradient = match b.x - a.x {
+            | 0. => 1.,
+            | run => (b.y - a.y) / run,
+        };
+        let mut endpoint = |point: Vector2<f32>, left: bool| {
+            let major = point.x.round();
+            let minor = point.y + gradient * (major - point.x);
+            let gap = match left {
+                | true => 1. - (point.x + 0.5).fract(),
+                | false => (point.x + 0.5).fract(),
+            };
+            plot(major, minor.floor(), (1. - minor.fract()) * gap);
+            plot(major, minor.floor() + 1., minor.fract() * gap);
+            (major, minor)
+        };
+
+        let (start, first) = endpoint(a, true);
+        let (end, _) = endpoint(b, false);
+        let (mut major, mut stop) = ((start + 1.).max(0.), end.min(limit));
+        match gradient == 0. {
+            | true if !(-1. ..side).contains(&first) => stop = major,
+            | true => {}
+            | false => {
+                let (enter, exit) = ((-1. - first) / gradient + start, (side - first) / gradient + start);
+                major = major.max(enter.min(exit).floor());
+                stop = stop.min(enter.max(exit).ceil() + 1.);
+            }
+        }
+
+        let mut minor = first + gradient * (major - start);
+        while major < stop {
+            plot(major, minor.floor(), 1. - minor.fract());
+            plot(major, minor.floor() + 1., minor.fract());
+            minor += gradient;
+            major += 1.;
+        }
+    }
+
+    pub fn draw_thick_line(&mut self, from: Vector2<f32>, to: Vector2<f32>, thickness: f32, value: T) {
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        let length = dx.hypot(dy);
+        let (nx, ny) = match length > 0. {
+            | true => (-dy / length * thickness / 2., dx / length * thickness / 2.),
+            | false => (0., thickness / 2.),
+        };
+        let (ex, ey) = match length > 0. {
+            | true => (0., 0.),
+            | false => (thickness / 2., 0.),
+        };
+
+        let quad = [
+            vec2!(from.x + nx - ex, from.y + ny - ey),
+            vec2!(to.x + nx + ex, to.y + ny + ey),
+            vec2!(to.x - nx + ex, to.y - ny + ey),
+            vec2!(from.x - nx - ex, from.y - ny - ey),
+        ];
+        self.fill_polygon(&quad, FillRule::NonZero, value);
+    }
+
+    pub fn draw_rect(&mut self, min: Vector2<isize>, max: Vector2<isize>, value: T) {
+        self.hline(min.x, max.x, min.y, value);
+        self.hline(min.x, max.x, max.y, value);
+        let (start, end) = (min.y.min(max.y).max(0), min.y.max(max.y).min(self.height as isize - 1));
+        (start..=end).for_each(|y| {
+            self.plot(min.x, y, value);
+            self.plot(max.x, y, value);
+        });
+    }
+
+    pub fn fill_rect(&mut self, min: Vector2<isize>, max: Vector2<isize>, value: T) {
+        let (start, end) = (min.y.min(max.y).max(0), min.y.max(max.y).min(self.height as isize - 1));
+        (start..=end).for_each(|y| self.hline(min.x, max.x, y, value));
+    }
+
+    pub fn draw_rotated_rect(&mut self, center: Vector2<f32>, size: Vector2<f32>, angle: f32, value: T) {
+        let corners = rotated_rect(center, size, angle).map(|corner| vec2!(isize; corner.x.round(), corner.y.round()));
+        (0..4).for_each(|i| self.draw_line(corners[i], corners[(i + 1) % 4], value));
+    }
+
+    pub fn fill_rotated_rect(&mut self, center: Vector2<f32>, size: Vector2<f32>, angle: f32, value: T) {
+        self.fill_polygon(&rotated_rect(center, size, angle), FillRule::NonZero, value);
+    }
+
+    pub fn draw_circle(&mut self, center: Vector2<isize>, radius: isize, value: T) {
+        self.draw_ellipse(center, vec2!(isize; radius, radius), value);
+    }
+
+    pub fn fill_circle(&mut self, center: Vector2<isize>, radius: isize, value: T) {
+        self.fill_ellipse(center, vec2!(isize; radius, radius), value);
+    }
+
+    pub fn draw_ellipse(&mut self, center: Vector2<isize>, radii: Vector2<isize>, value: T) {
+        let rows = visible_offsets(center.y, radii.y, self.height);
+        ellipse_rows(radii, rows, |y, spans| {
+            let rows = [center.y.saturating_sub(y), center.y.saturating_add(y)];
+            spans.iter().filter(|&&(low, high)| low <= high).for_each(|&(low, high)| {
+                rows.iter().for_each(|&row| {
+                    self.hline(center.x.saturating_add(low), center.x.saturating_add(high), row, value);
+                    self.hline(center.x.saturating_sub(high), center.x.saturating_sub(low), row, value);
+                });
+            });
+        });
+    }
+
+    pub fn fill_ellipse(&mut self, center: Vector2<isize>, radii: Vector2<isize>, value: T) {
+        let rows = visible_offsets(center.y, radii.y, self.height);
+        ellipse_rows(radii, rows, |y, spans| {
+            let reach = spans[0].1.max(spans[1].1);
+            let (left, right) = (center.x.saturating_sub(reach), center.x.saturating_add(reach));
+            self.hline(left, right, center.y.saturating_sub(y), value);
+            self.hline(left, right, center.y.saturating_add(y), value);
+        });
+    }
+
+    pub fn fill_polygon(&mut self, polygon: &[Vector2<f32>], rule: FillRule, value: T) {
+        if polygon.len() < 3 || self.width == 0 {
+            return;
+        }
+
+        let (top, bottom) = polygon
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(top, bottom), point| (top.min(point.y), bottom.max(point.y)));
+        let first = ((top - 0.5).ceil().max(0.)) as isize;
+        let last = ((bottom - 0.5).ceil() as isize).min(self.height as isize);
+
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        (first..last).for_each(|y| {
+            let center = y as f32 + 0.5;
+            crossings.clear();
+            (0..polygon.len()).for_each(|i| {
+                let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+                let direction = match (a.y <= center, b.y <= center) {
+                    | (true, false) => 1,
+                    | (false, true) => -1,
+                    | _ => return,
+                };
+                crossings.push((a.x + (center - a.y) / (b.y - a.y) * (b.x - a.x), direction));
+            });
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            crossings.windows(2).for_each(|pair| {
+                winding += pair[0].1;
+                let inside = match rule {
+                    | FillRule::EvenOdd => winding % 2 != 0,
+                    | FillRule::NonZero => winding != 0,
+                };
+                let (start, end) = ((pair[0].0 - 0.5).ceil() as isize, (pair[1].0 - 0.5).ceil() as isize - 1);
+                if inside && start <= end {
+                    self.hline(start, end, y, value);
+                }
+            });
+        });
+    }
+}
+
+impl<T> Buffer2<T>
+where
+    T: Copy + PartialEq,
+{
+    pub fn flood_fill(&mut self, seed: Vector2<usize>, value: T) -> usize {
+        let Some(target) = self.get(seed.x, seed.y) else {
+            return 0;
+        };
+        if target == value {
+            return 0;
+        }
+
+        let (width, height) = (self.width, self.height);
+        let (mut stack, mut filled) = (vec![(seed.x, seed.y)], 0);
+        while let Some((x, y)) = stack.pop() {
+            let row = y * width;
+            if self.data[row + x] != target {
+                continue;
+            }
+
+            let (mut left, mut right) = (x, x);
+            while left > 0 && self.data[row + left - 1] == target {
+                left -= 1;
+            }
+            while right + 1 < width && self.data[row + right + 1] == target {
+                right += 1;
+            }
+            self.data[row + left..=row + right].fill(value);
+            filled += right - left + 1;
+
+            [y.checked_sub(1), (y + 1 < height).then_some(y + 1)].into_iter().flatten().for_each(|ny| {
+                let mut run = false;
+                (left..=right).for_each(|nx| match self.data[ny * width + nx] == target {
+                    | true if !run => {
+                        stack.push((nx, ny));
+                        run = true;
+                    }
+                    | true => {}
+                    | false => run = false,
+                });
+            });
+        }
+        filled
+    }
+}
+
+fn rotated_rect(center: Vector2<f32>, size: Vector2<f32>, angle: f32) -> [Vector2<f32>; 4] {
+    let (sin, cos) = angle.sin_cos();
+    [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)].map(|(u, v): (f32, f32)| {
+        let (x, y) = (u * size.x / 2., v * size.y / 2.);
+        vec2!(center.x + x * cos - y * sin, center.y + x * sin + y * cos)
+    })
+}
+
+// the outline of each row is where the curve rounds to it when sampled per column, plus where it crosses the
+// row itself, so flat and steep parts both stay connected without walking the rows that end up clipped
+fn ellipse_rows(radii: Vector2<isize>, rows: Option<(isize, isize)>, mut emit: impl FnMut(isize, [(isize, isize); 2])) {
+    let Some((first, last)) = rows.filter(|_| radii.x >= 0 && radii.y >= 0) else {
+        return;
+    };
+
+    let column = |y: isize| match y {
+        | 0 => radii.x,
+        | _ => extent(radii.x, radii.y, y as f64 - 0.5).map_or(-1, |reach| reach.floor() as isize),
+    };
+    (first..=last).for_each(|y| {
+        let crossing = extent(radii.x, radii.y, y as f64).map_or(0, |reach| (reach + 0.5).floor() as isize);
+        emit(
+            y,
+            [
+                (column(y.saturating_add(1)).saturating_add(1), column(y)),
+                (crossing, crossing),
+            ],
+        );
+    });
+}
+
+// horizontal reach of the ellipse at a vertical offset, None once the offset leaves it
+fn extent(radius: isize, other: isize, offset: f64) -> Option<f64> {
+    match other {
+        | 0 => (offset == 0.).then_some(radius as f64),
+        | _ => {
+            let t = offset / other as f64;
+            (t.abs() <= 1.).then(|| radius as f64 * ((1. - t) * (1. + t)).sqrt())
+        }
+    }
+}
+
+// offsets from the center row, within the radius, that land on a row of the buffer either above or below
+fn visible_offsets(center: isize, radius: isize, size: usize) -> Option<(isize, isize)> {
+    let (center, radius, last) = (center as i128, radius as i128, size as i128 - 1);
+    [
+        ((-center).max(0), (last - center).min(radius)),
+        ((center - last).max(0), center.min(radius)),
+    ]
+    .into_iter()
+    .filter(|(low, high)| low <= high)
+    .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)))
+    .map(|(low, high)| (low as isize, high as isize))
+}
+
+fn span(origin: i128, step: i128, size: i128) -> Option<(i128, i128)> {
+    match step {
+        | 0 if (0..size).contains(&origin) => Some((i128::MIN / 4, i128::MAX / 4)),
+        | 0 => None,
+        | 1 => Some((-origin, size - 1 - origin)),
+        | _ => Some((origin - (size - 1), origin)),
+    }
+}
+
+fn ceil_div(numerator: i128, denominator: i128) -> i128 {
+    -(-numerator).div_euclid(denominator)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count<T: Copy + PartialEq>(buffer: &Buffer2<T>, value: T) -> usize {
+        buffer.data.iter().filter(|&&v| v == value).count()
+    }
+
+    #[test]
+    fn lines() {
+        let mut buffer = Buffer2::new(16, 16, 0u8);
+        buffer.draw_line(vec2!(isize; 1, 2), vec2!(isize; 13, 7), 1);
+        assert!(buffer.get(1, 2) == Some(1) && buffer.get(13, 7) == Some(1) && count(&buffer, 1) == 13);
+
+        buffer.draw_line(vec2!(isize; -100, 8), vec2!(isize; 1_000_000_000, 8), 2);
+        assert!(count(&buffer, 2) == 16);
+        buffer.draw_line(vec2!(isize; -5, -5), vec2!(isize; -1, 30), 3);
+        assert!(count(&buffer, 3) == 0);
+
+        let mut far = Buffer2::new(8, 8, 0u8);
+        far.draw_line(vec2!(isize; -2_000_000_000, 3), vec2!(isize; 5, 3), 1);
+        assert!(count(&far, 1) == 6 && far.get(5, 3) == Some(1));
+        far.draw_line(vec2!(isize; 3, isize::MIN / 4), vec2!(isize; 4, isize::MAX / 4), 2);
+        assert!(count(&far, 2) == 8);
+        far.draw_line_aa(vec2!(-2e9, 1.), vec2!(3e9, 1.), 3, |_, value, _| value);
+        far.draw_line_aa(vec2!(f32::NEG_INFINITY, 1.), vec2!(3., 2.), 4, |_, value, _| value);
+        assert!(count(&far, 3) == 8 && count(&far, 4) == 0);
+
+        let mut state = 7u32;
+        let mut random = || {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 16) as isize % 81 - 40
+        };
+        for _ in 0..500 {
+            let (from, to) = (vec2!(isize; random(), random()), vec2!(isize; random(), random()));
+            let (mut clipped, mut reference) = (Buffer2::new(16, 12, 0u8), Buffer2::new(16, 12, 0u8));
+            clipped.draw_line(from, to, 1);
+
+            let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+            let (mut x, mut y, mut error) = (from.x, from.y, dx + dy);
+            loop {
+                _ = reference.plot(x, y, 1);
+                if x == to.x && y == to.y {
+                    break;
+                }
+                let doubled = 2 * error;
+                if doubled >= dy {
+                    (error, x) = (error + dy, x + (to.x - from.x).signum());
+                }
+                if doubled <= dx {
+                    (error, y) = (error + dx, y + (to.y - from.y).signum());
+                }
+            }
+            assert!(clipped.data == reference.data);
+        }
+
+        let mut coverage = Buffer2::new(16, 16, 0f32);
+        coverage.draw_line_aa(vec2!(2., 3.), vec2!(12., 6.3), 1., |current, value, alpha| current + value * alpha);
+        (3..12).for_each(|x| {
+            let column: f32 = (0..16).map(|y| coverage.get(x, y).unwrap()).sum();
+            assert!((column - 1.).abs() < 1e-4);
+        });
+        assert!(coverage.data.iter().filter(|&&c| c > 0. && c < 1.).count() > 10);
+
+        let mut thick = Buffer2::new(16, 16, 0u8);
+        thick.draw_thick_line(vec2!(2., 8.), vec2!(14., 8.), 4., 1);
+        assert!(count(&thick, 1) == 48 && thick.get(5, 6) == Some(1) && thick.get(5, 10) == Some(0));
+    }
+
+    #[test]
+    fn shapes() {
+        let mut buffer = Buffer2::new(21, 21, 0u8);
+        buffer.draw_circle(vec2!(isize; 10, 10), 6, 1);
+        assert!([(16, 10), (4, 10), (10, 16), (10, 4)].iter().all(|&(x, y)| buffer.get(x, y) == Some(1)));
+        assert!((0..21).all(|y| (0..21).all(|x| buffer.get(x, y) == buffer.get(20 - x, y))));
+        assert!(buffer.get(10, 10) == Some(0) && buffer.flood_fill(vec2!(usize; 10, 10), 2) > 90);
+
+        let mut ellipse = Buffer2::new(21, 21, 0u8);
+        ellipse.fill_ellipse(vec2!(isize; 10, 10), vec2!(isize; 8, 3), 1);
+        assert!(ellipse.get(18, 10) == Some(1) && ellipse.get(10, 13) == Some(1) && ellipse.get(10, 14) == Some(0));
+        let mut flat = Buffer2::new(21, 21, 0u8);
+        flat.fill_ellipse(vec2!(isize; 10, 10), vec2!(isize; 8, 0), 1);
+        flat.draw_ellipse(vec2!(isize; 10, 10), vec2!(isize; 0, 3), 2);
+        assert!(count(&flat, 1) == 16 && flat.get(2, 10) == Some(1) && flat.get(18, 10) == Some(1));
+        assert!(count(&flat, 2) == 7 && flat.get(10, 7) == Some(2) && flat.get(10, 13) == Some(2));
+        flat.fill_circle(vec2!(isize; 3, 3), 0, 3);
+        assert!(count(&flat, 3) == 1);
+        ellipse.fill_circle(vec2!(isize; 0, 0), 4, 2);
+        assert!(ellipse.get(0, 0) == Some(2) && ellipse.get(4, 0) == Some(2) && ellipse.get(4, 3) == Some(0));
+
+        let mut rects = Buffer2::new(10, 10, 0u8);
+        rects.fill_rect(vec2!(isize; -5, 7), vec2!(isize; 3, 20), 1);
+        assert!(count(&rects, 1) == 12);
+        rects.draw_rect(vec2!(isize; 5, 0), vec2!(isize; 8, 3), 2);
+        assert!(count(&rects, 2) == 12 && rects.get(6, 1) == Some(0));
+
+        let mut rotated = Buffer2::new(20, 20, 0u8);
+        rotated.fill_rotated_rect(vec2!(10., 10.), vec2!(8., 8.), std::f32::consts::FRAC_PI_4, 1);
+        assert!(
+            rotated.get(10, 5) == Some(1) && rotated.get(6, 6) == Some(0) && (60..=68).contains(&count(&rotated, 1))
+        );
+        rotated.draw_rotated_rect(vec2!(10., 10.), vec2!(8., 8.), std::f32::consts::FRAC_PI_4, 2);
+        assert!(rotated.get(10, 4) == Some(2) && rotated.get(10, 10) == Some(1));
+    }
+
+    #[test]
+    fn offscreen_shapes() {
+        let mut rects = Buffer2::new(8, 8, 0u8);
+        rects.draw_rect(vec2!(isize; isize::MIN, isize::MIN), vec2!(isize; isize::MAX, isize::MAX), 1);
+        assert!(count(&rects, 1) == 0);
+        rects.draw_rect(vec2!(isize; 2, isize::MIN), vec2!(isize; 5, isize::MAX), 2);
+        assert!(count(&rects, 2) == 16 && rects.get(2, 0) == Some(2) && rects.get(5, 7) == Some(2));
+
+        let huge = 1_000_000_000_000;
+        let mut circles = Buffer2::new(8, 8, 0u8);
+        circles.fill_circle(vec2!(isize; 4, 4), huge, 1);
+        assert!(count(&circles, 1) == 64);
+        circles.draw_circle(vec2!(isize; 4, 4), huge, 2);
+        assert!(count(&circles, 2) == 0);
+        circles.draw_circle(vec2!(isize; 4, huge), huge, 3);
+        assert!(count(&circles, 3) == 8 && (0..8).all(|x| circles.get(x, 0) == Some(3)));
+
+        circles.draw_ellipse(vec2!(isize; isize::MAX, isize::MIN), vec2!(isize; isize::MAX, isize::MAX), 4);
+        circles.fill_ellipse(vec2!(isize; 0, 0), vec2!(isize; isize::MAX, isize::MAX), 5);
+        assert!(count(&circles, 4) == 0 && count(&circles, 5) == 64);
+    }
+
+    #[test]
+    fn polygon_rules() {
+        let star: Vec<Vector2<f32>> = (0..5)
+            .map(|i| {
+                let angle = i as f32 * 4. * std::f32::consts::PI / 5. - std::f32::consts::FRAC_PI_2;
+                vec2!(16. + 14. * angle.cos(), 16. + 14. * angle.sin())
+            })
+            .collect();
+
+        let mut even_odd = Buffer2::new(32, 32, 0u8);
+        even_odd.fill_polygon(&star, FillRule::EvenOdd, 1);
+        let mut non_zero = Buffer2::new(32, 32, 0u8);
+        non_zero.fill_polygon(&star, FillRule::NonZero, 1);
+        assert!(even_odd.get(16, 16) == Some(0) && non_zero.get(16, 16) == Some(1));
+        assert!(even_odd.get(16, 4) == Some(1) && non_zero.get(16, 4) == Some(1));
+        assert!(count(&non_zero, 1) > count(&even_odd, 1));
+
+        let mut square = Buffer2::new(8, 8, 0u8);
+        let corners = [vec2!(2., 2.), vec2!(6., 2.), vec2!(6., 6.), vec2!(2., 6.)];
+        square.fill_polygon(&corners, FillRule::EvenOdd, 1);
+        assert!(count(&square, 1) == 16 && square.get(2, 2) == Some(1) && square.get(6, 6) == Some(0));
+
+        let mut offscreen = Buffer2::new(8, 8, 0u8);
+        offscreen.fill_polygon(&[vec2!(-50., -50.), vec2!(50., -50.), vec2!(50., 50.)], FillRule::NonZero, 1);
+        assert!(offscreen.get(7, 0) == Some(1) && offscreen.get(0, 7) == Some(0));
+    }
+
+    #[test]
+    fn flood_fill() {
+        let mut buffer = Buffer2::new(12, 12, 0u8);
+        buffer.draw_rect(vec2!(isize; 2, 2), vec2!(isize; 9, 9), 1);
+        buffer.draw_line(vec2!(isize; 2, 5), vec2!(isize; 9, 5), 1);
+
+        assert!(buffer.flood_fill(vec2!(usize; 4, 3), 2) == 12 && buffer.flood_fill(vec2!(usize; 4, 7), 3) == 18);
+        assert!(buffer.flood_fill(vec2!(usize; 0, 0), 4) == 144 - 64 && buffer.flood_fill(vec2!(usize; 0, 0), 4) == 0);
+        assert!(buffer.flood_fill(vec2!(usize; 40, 0), 5) == 0 && count(&buffer, 1) == 34);
+    }
+}
@@ -0,0 +1,554 @@
+use super::Buffer2;
+use crate::math::traits::Linear;
+use crate::math::vector::Vector2;
+use crate::math::vector::Vector3;
+use crate::math::vector::Vector4;
+
+const LANCZOS_LOBES: f32 = 3.;
+
+pub trait Pixel
+where
+    Self: Linear,
+{
+    fn zero() -> Self;
+
+    fn min(self, other: Self) -> Self;
+
+    fn max(self, other: Self) -> Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.add(other.scale(-1.))
+    }
+}
+
+impl Pixel for f32 {
+    fn zero() -> Self {
+        0.
+    }
+
+    fn min(self, other: Self) -> Self {
+        f32::min(self, other)
+    }
+
+    fn max(self, other: Self) -> Self {
+        f32::max(self, other)
+    }
+}
+
+macro_rules! impl_pixel_vector {
+    ($type:ident; $($field:ident),+) => {
+        impl Pixel for $type<f32> {
+            fn zero() -> Self {
+                Self::zeros()
+            }
+
+            fn min(self, other: Self) -> Self {
+                Self::build($(self.$field.min(other.$field)),+)
+            }
+
+            fn max(self, other: Self) -> Self {
+                Self::build($(self.$field.max(other.$field)),+)
+            }
+        }
+    };
+}
+
+impl_pixel_vector!(Vector2; x, y);
+impl_pixel_vector!(Vector3; x, y, z);
+impl_pixel_vector!(Vector4; x, y, z, w);
+
+fn clamped<T>(line: &[T], index: isize) -> T
+where
+    T: Copy,
+{
+    line[index.clamp(0, line.len() as isize - 1) as usize]
+}
+
+fn transpose<T>(image: &Buffer2<T>) -> Buffer2<T>
+where
+    T: Copy,
+{
+    let mut out = Buffer2::new(image.height, image.width, image.clear_value);
+    (0..image.height).for_each(|y| (0..image.width).for_each(|x| out.set_unchecked(y, x, image.get_unchecked(x, y))));
+    out
+}
+
+fn map_rows<T>(image: &Buffer2<T>, width: usize, f: impl Fn(&[T]) -> Vec<T>) -> Buffer2<T>
+where
+    T: Copy,
+{
+    let mut out = Buffer2::new(width, image.height, image.clear_value);
+    if image.width > 0 {
+        out.rows_mut()
+            .zip(image.data.chunks_exact(image.width))
+            .for_each(|(out, row)| out.copy_from_slice(&f(row)));
+    }
+    out
+}
+
+fn map_separable<T>(
+    image: &Buffer2<T>,
+    size: Vector2<usize>,
+    horizontal: impl Fn(&[T]) -> Vec<T>,
+    vertical: impl Fn(&[T]) -> Vec<T>,
+) -> Buffer2<T>
+where
+    T: Copy,
+{
+    let rows = map_rows(image, size.x, horizontal);
+    transpose(&map_rows(&transpose(&rows), size.y, vertical))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* CONVOLUTION */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+fn correlate_line<T>(line: &[T], kernel: &[f32]) -> Vec<T>
+where
+    T: Pixel,
+{
+    let center = (kernel.len() / 2) as isize;
+    (0..line.len() as isize)
+        .map(|x| {
+            kernel
+                .iter()
+                .enumerate()
+                .fold(T::zero(), |total, (i, &weight)| total.add(clamped(line, x + i as isize - center).scale(weight)))
+        })
+        .collect()
+}
+
+pub fn convolve<T>(image: &Buffer2<T>, kernel: &Buffer2<f32>) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    let (center_x, center_y) = ((kernel.width / 2) as isize, (kernel.height / 2) as isize);
+    let mut out = Buffer2::new(image.width, image.height, image.clear_value);
+    if image.width == 0 || image.height == 0 {
+        return out;
+    }
+
+    let texel = |x: isize, y: isize| {
+        let x = x.clamp(0, image.width as isize - 1) as usize;
+        let y = y.clamp(0, image.height as isize - 1) as usize;
+        image.get_unchecked(x, y)
+    };
+    (0..image.height as isize).for_each(|y| {
+        (0..image.width as isize).for_each(|x| {
+            let total = (0..kernel.height).fold(T::zero(), |total, j| {
+                (0..kernel.width).fold(total, |total, i| {
+                    let sample = texel(x + i as isize - center_x, y + j as isize - center_y);
+                    total.add(sample.scale(kernel.get_unchecked(i, j)))
+                })
+            });
+            out.set_unchecked(x as usize, y as usize, total);
+        });
+    });
+    out
+}
+
+pub fn convolve_separable<T>(image: &Buffer2<T>, horizontal: &[f32], vertical: &[f32]) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    let size = Vector2::build(image.width, image.height);
+    map_separable(image, size, |row| correlate_line(row, horizontal), |column| correlate_line(column, vertical))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BLUR */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn gaussian_kernel(sigma: f32) -> Vec<f32> {
+    if sigma <= 0. {
+        return vec![1.];
+    }
+
+    let radius = (sigma * 3.).ceil() as isize;
+    let weights: Vec<f32> = (-radius..=radius).map(|i| (-(i * i) as f32 / (2. * sigma * sigma)).exp()).collect();
+    let total: f32 = weights.iter().sum();
+    weights.iter().map(|weight| weight / total).collect()
+}
+
+pub fn gaussian_blur<T>(image: &Buffer2<T>, sigma: f32) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    let kernel = gaussian_kernel(sigma);
+    convolve_separable(image, &kernel, &kernel)
+}
+
+fn box_line<T>(line: &[T], radius: usize) -> Vec<T>
+where
+    T: Pixel,
+{
+    let (radius, norm) = (radius as isize, 1. / (radius * 2 + 1) as f32);
+    let mut sum = (-radius..=radius).fold(T::zero(), |sum, i| sum.add(clamped(line, i)));
+    (0..line.len() as isize)
+        .map(|x| {
+            let value = sum.scale(norm);
+            sum = sum.add(clamped(line, x + radius + 1)).sub(clamped(line, x - radius));
+            value
+        })
+        .collect()
+}
+
+pub fn box_blur<T>(image: &Buffer2<T>, radius: usize) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    let size = Vector2::build(image.width, image.height);
+    map_separable(image, size, |row| box_line(row, radius), |column| box_line(column, radius))
+}
+
+pub fn box_radii(sigma: f32, passes: usize) -> Vec<usize> {
+    let (variance, passes) = (12. * sigma * sigma, passes.max(1) as f32);
+    let mut lower = ((variance / passes + 1.).sqrt().floor() as isize).max(1);
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+
+    let lower = lower as f32;
+    let wide = ((variance - passes * lower * lower - 4. * passes * lower - 3. * passes) / (-4. * lower - 4.)).round();
+    (0..passes as usize)
+        .map(|pass| match (pass as f32) < wide {
+            | true => lower as usize / 2,
+            | false => lower as usize / 2 + 1,
+        })
+        .collect()
+}
+
+pub fn fast_gaussian_blur<T>(image: &Buffer2<T>, sigma: f32, passes: usize) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    let radii = box_radii(sigma, passes);
+    let size = Vector2::build(image.width, image.height);
+    let iterate = |line: &[T]| radii.iter().fold(line.to_vec(), |line, &radius| box_line(&line, radius));
+    map_separable(image, size, iterate, iterate)
+}
+
+pub fn unsharp_mask<T>(image: &Buffer2<T>, sigma: f32, amount: f32) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    let mut out = gaussian_blur(image, sigma);
+    out.data
+        .iter_mut()
+        .zip(&image.data)
+        .for_each(|(blurred, &original)| *blurred = original.add(original.sub(*blurred).scale(amount)));
+    out
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* GRADIENTS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gradient {
+    Sobel,
+    Scharr,
+}
+
+impl Gradient {
+    pub fn smoothing(self) -> [f32; 3] {
+        match self {
+            | Self::Sobel => [1., 2., 1.],
+            | Self::Scharr => [3., 10., 3.],
+        }
+    }
+}
+
+pub fn gradient<T>(image: &Buffer2<T>, operator: Gradient) -> (Buffer2<T>, Buffer2<T>)
+where
+    T: Pixel,
+{
+    let (derivative, smoothing) = ([-1., 0., 1.], operator.smoothing());
+    (convolve_separable(image, &derivative, &smoothing), convolve_separable(image, &smoothing, &derivative))
+}
+
+pub fn gradient_magnitude(dx: &Buffer2<f32>, dy: &Buffer2<f32>) -> Buffer2<f32> {
+    let mut out = Buffer2::new(dx.width, dx.height, 0.);
+    out.data
+        .iter_mut()
+        .zip(dx.data.iter().zip(&dy.data))
+        .for_each(|(out, (dx, dy))| *out = dx.hypot(*dy));
+    out
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* RESIZE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resample {
+    Nearest,
+    Bilinear,
+    Bicubic,
+    Lanczos,
+}
+
+impl Resample {
+    pub fn support(self) -> f32 {
+        match self {
+            | Self::Nearest => 0.5,
+            | Self::Bilinear => 1.,
+            | Self::Bicubic => 2.,
+            | Self::Lanczos => LANCZOS_LOBES,
+        }
+    }
+
+    pub fn weight(self, x: f32) -> f32 {
+        let x = x.abs();
+        let sinc = |x: f32| match x < f32::EPSILON {
+            | true => 1.,
+            | false => (std::f32::consts::PI * x).sin() / (std::f32::consts::PI * x),
+        };
+        match self {
+            | Self::Nearest => (x < 0.5) as u8 as f32,
+            | Self::Bilinear => (1. - x).max(0.),
+            | Self::Bicubic if x < 1. => 1.5 * x * x * x - 2.5 * x * x + 1.,
+            | Self::Bicubic if x < 2. => -0.5 * x * x * x + 2.5 * x * x - 4. * x + 2.,
+            | Self::Lanczos if x < LANCZOS_LOBES => sinc(x) * sinc(x / LANCZOS_LOBES),
+            | Self::Bicubic | Self::Lanczos => 0.,
+        }
+    }
+}
+
+fn resample_line<T>(line: &[T], length: usize, filter: Resample) -> Vec<T>
+where
+    T: Pixel,
+{
+    let ratio = line.len() as f32 / length as f32;
+    if filter == Resample::Nearest {
+        return (0..length).map(|i| clamped(line, ((i as f32 + 0.5) * ratio) as isize)).collect();
+    }
+
+    let stretch = ratio.max(1.);
+    let support = filter.support() * stretch;
+    (0..length)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * ratio;
+            let (first, last) = ((center - support).floor() as isize, (center + support).ceil() as isize);
+            let (total, norm) = (first..=last).fold((T::zero(), 0.), |(total, norm), j| {
+                let weight = filter.weight((j as f32 + 0.5 - center) / stretch);
+                (total.add(clamped(line, j).scale(weight)), norm + weight)
+            });
+            total.scale(1. / norm)
+        })
+        .collect()
+}
+
+pub fn resize<T>(image: &Buffer2<T>, width: usize, height: usize, filter: Resample) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    if image.width == 0 || image.height == 0 {
+        return Buffer2::new(width, height, image.clear_value);
+    }
+
+    let size = Vector2::build(width, height);
+    map_separable(image, size, |row| resample_line(row, width, filter), |column| resample_line(column, height, filter))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* MORPHOLOGY */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+fn extremum_line<T>(line: &[T], radius: usize, pick: impl Fn(T, T) -> T) -> Vec<T>
+where
+    T: Pixel,
+{
+    let radius = radius as isize;
+    (0..line.len() as isize)
+        .map(|x| (x - radius + 1..=x + radius).fold(clamped(line, x - radius), |best, i| pick(best, clamped(line, i))))
+        .collect()
+}
+
+pub fn erode<T>(image: &Buffer2<T>, radius: usize) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    let size = Vector2::build(image.width, image.height);
+    map_separable(image, size, |row| extremum_line(row, radius, T::min), |column| extremum_line(column, radius, T::min))
+}
+
+pub fn dilate<T>(image: &Buffer2<T>, radius: usize) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    let size = Vector2::build(image.width, image.height);
+    map_separable(image, size, |row| extremum_line(row, radius, T::max), |column| extremum_line(column, radius, T::max))
+}
+
+pub fn open<T>(image: &Buffer2<T>, radius: usize) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    dilate(&erode(image, radius), radius)
+}
+
+pub fn close<T>(image: &Buffer2<T>, radius: usize) -> Buffer2<T>
+where
+    T: Pixel,
+{
+    erode(&dilate(image, radius), radius)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* INTEGRAL IMAGE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Integral<T> {
+    sums: Buffer2<T>,
+}
+
+impl<T> Integral<T>
+where
+    T: Pixel,
+{
+    pub fn build(image: &Buffer2<T>) -> Self {
+        let mut sums = Buffer2::new(image.width + 1, image.height + 1, T::zero());
+        (0..image.height).for_each(|y| {
+            let mut row = T::zero();
+            (0..image.width).for_each(|x| {
+                row = row.add(image.get_unchecked(x, y));
+                sums.set_unchecked(x + 1, y + 1, row.add(sums.get_unchecked(x + 1, y)));
+            });
+        });
+        Self { sums }
+    }
+
+    pub fn sum(&self, min: Vector2<usize>, max: Vector2<usize>) -> T {
+        let (x0, y0, x1, y1) = self.corners(min, max);
+        let corner = |x: usize, y: usize| self.sums.get_unchecked(x, y);
+        corner(x1, y1).sub(corner(x0, y1)).sub(corner(x1, y0)).add(corner(x0, y0))
+    }
+
+    pub fn mean(&self, min: Vector2<usize>, max: Vector2<usize>) -> T {
+        let (x0, y0, x1, y1) = self.corners(min, max);
+        let area = (x1 - x0) * (y1 - y0);
+        self.sum(min, max).scale(1. / area.max(1) as f32)
+    }
+
+    fn corners(&self, min: Vector2<usize>, max: Vector2<usize>) -> (usize, usize, usize, usize) {
+        let (x0, y0) = (min.x.min(self.sums.width - 1), min.y.min(self.sums.height - 1));
+        let (x1, y1) = (max.x.clamp(x0, self.sums.width - 1), max.y.clamp(y0, self.sums.height - 1));
+        (x0, y0, x1, y1)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::super::Color;
+    use super::*;
+    use crate::math::vector::FloatVectorOps;
+    use crate::vec2;
+    use crate::vec3;
+
+    fn ramp(width: usize, height: usize, f: impl Fn(usize, usize) -> f32) -> Buffer2<f32> {
+        let mut out = Buffer2::new(width, height, 0.);
+        (0..height).for_each(|y| (0..width).for_each(|x| out.set_unchecked(x, y, f(x, y))));
+        out
+    }
+
+    fn near(a: &Buffer2<f32>, b: &Buffer2<f32>, tolerance: f32) -> bool {
+        a.data.iter().zip(&b.data).all(|(a, b)| (a - b).abs() <= tolerance)
+    }
+
+    #[test]
+    fn convolution_and_blur() {
+        let image = ramp(9, 7, |x, y| ((x * 7 + y * 3) % 5) as f32);
+        let (horizontal, vertical) = ([1., 2., -1.], [0.5, 0.25, 0.25]);
+        let mut kernel = Buffer2::new(3, 3, 0.);
+        (0..3).for_each(|j| (0..3).for_each(|i| kernel.set_unchecked(i, j, horizontal[i] * vertical[j])));
+        assert!(near(&convolve(&image, &kernel), &convolve_separable(&image, &horizontal, &vertical), 1e-5));
+
+        let flat = Buffer2::new(6, 5, vec3!(0.25, 0.5, 1.));
+        assert!(gaussian_blur(&flat, 1.5).data.iter().all(|&c: &Color| (c - vec3!(0.25, 0.5, 1.)).length() < 1e-5));
+
+        let impulse = ramp(41, 41, |x, y| (x == 20 && y == 20) as u8 as f32);
+        let boxed = box_blur(&impulse, 2);
+        assert!((boxed.get_unchecked(18, 22) - 1. / 25.).abs() < 1e-6 && boxed.get_unchecked(17, 20) == 0.);
+
+        let exact = gaussian_blur(&impulse, 3.);
+        let fast = fast_gaussian_blur(&impulse, 3., 3);
+        assert!((fast.data.iter().sum::<f32>() - 1.).abs() < 1e-4);
+        assert!(near(&exact, &fast, exact.get_unchecked(20, 20) * 0.1));
+        assert!(box_radii(3., 3) == [2, 2, 3] && box_radii(0.1, 2) == [0, 0]);
+    }
+
+    #[test]
+    fn gradients_and_sharpening() {
+        let image = ramp(8, 8, |x, y| x as f32 * 2. + y as f32);
+        let (dx, dy) = gradient(&image, Gradient::Sobel);
+        assert!(dx.get_unchecked(3, 4) == 16. && dy.get_unchecked(3, 4) == 8.);
+        let (dx, dy) = gradient(&image, Gradient::Scharr);
+        assert!(dx.get_unchecked(3, 4) == 64. && dy.get_unchecked(3, 4) == 32.);
+        assert!((gradient_magnitude(&dx, &dy).get_unchecked(3, 4) - 64f32.hypot(32.)).abs() < 1e-4);
+
+        let step = ramp(16, 4, |x, _| (x >= 8) as u8 as f32);
+        let sharp = unsharp_mask(&step, 1., 1.);
+        assert!(sharp.get_unchecked(7, 2) < 0. && sharp.get_unchecked(8, 2) > 1.);
+        assert!(sharp.get_unchecked(0, 2).abs() < 1e-6 && (sharp.get_unchecked(15, 2) - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn resizing() {
+        let filters = [
+            Resample::Nearest,
+            Resample::Bilinear,
+            Resample::Bicubic,
+            Resample::Lanczos,
+        ];
+        let flat = Buffer2::new(7, 5, vec3!(0.2, 0.4, 0.6));
+        assert!(filters.iter().all(|&filter| {
+            let out = resize(&flat, 13, 3, filter);
+            out.width == 13 && out.height == 3 && out.data.iter().all(|&c| (c - vec3!(0.2, 0.4, 0.6)).length() < 1e-5)
+        }));
+
+        let small = ramp(2, 2, |x, y| (x + y * 2) as f32);
+        let nearest = resize(&small, 4, 4, Resample::Nearest);
+        assert!(nearest.data == [0., 0., 1., 1., 0., 0., 1., 1., 2., 2., 3., 3., 2., 2., 3., 3.]);
+        let linear = resize(&ramp(4, 1, |x, _| x as f32), 8, 1, Resample::Bilinear);
+        assert!(linear.data.windows(2).all(|pair| pair[0] <= pair[1]) && (linear.data[3] - 1.25).abs() < 1e-5);
+
+        let checker = ramp(32, 32, |x, y| ((x + y) % 2) as f32);
+        assert!(filters[1..].iter().all(|&filter| {
+            let out = resize(&checker, 8, 8, filter);
+            out.data.iter().all(|value| (value - 0.5).abs() < 0.05)
+        }));
+        assert!(resize(&checker, 0, 4, Resample::Lanczos).data.is_empty());
+    }
+
+    #[test]
+    fn morphology_and_integral() {
+        let dot = ramp(7, 7, |x, y| (x == 3 && y == 3) as u8 as f32);
+        let grown = dilate(&dot, 1);
+        assert!(
+            grown.data.iter().sum::<f32>() == 9. && grown.get_unchecked(2, 4) == 1. && grown.get_unchecked(1, 3) == 0.
+        );
+        assert!(erode(&grown, 1).data == dot.data);
+        assert!(open(&dot, 1).data.iter().all(|&value| value == 0.));
+
+        let mut hole = ramp(9, 9, |x, y| (x > 1 && y > 1 && x < 7 && y < 7) as u8 as f32);
+        hole.set_unchecked(4, 4, 0.);
+        let closed = close(&hole, 1);
+        assert!(closed.get_unchecked(4, 4) == 1. && closed.get_unchecked(1, 4) == 0.);
+
+        let image = ramp(6, 5, |x, y| (x * 3 + y * 5 % 7) as f32);
+        let integral = Integral::build(&image);
+        let brute = |min: Vector2<usize>, max: Vector2<usize>| {
+            (min.y..max.y)
+                .flat_map(|y| (min.x..max.x).map(move |x| (x, y)))
+                .map(|(x, y)| image.get_unchecked(x, y))
+                .sum::<f32>()
+        };
+        assert!(integral.sum(vec2!(usize; 1, 2), vec2!(usize; 5, 4)) == brute(vec2!(usize; 1, 2), vec2!(usize; 5, 4)));
+        assert!(integral.sum(vec2!(usize; 0, 0), vec2!(usize; 9, 9)) == image.data.iter().sum::<f32>());
+        assert!(
+            integral.mean(vec2!(usize; 2, 1), vec2!(usize; 4, 3)) == brute(vec2!(usize; 2, 1), vec2!(usize; 4, 3)) / 4.
+        );
+        assert!(
+            integral.mean(vec2!(usize; 4, 3), vec2!(usize; 20, 20))
+                == brute(vec2!(usize; 4, 3), vec2!(usize; 6, 5)) / 4.
+        );
+    }
+}
@@ -0,0 +1,874 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::Bitmap;
+use super::Buffer2;
+use super::PackedColor;
+use super::deflate::InflateError;
+use super::deflate::zlib_compress;
+use super::deflate::zlib_decompress_limited;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    PpmAscii,
+    Pgm,
+    PgmAscii,
+    Bmp,
+    Tga,
+    Png,
+}
+
+impl Format {
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            | "ppm" => Some(Self::Ppm),
+            | "pgm" => Some(Self::Pgm),
+            | "bmp" => Some(Self::Bmp),
+            | "tga" => Some(Self::Tga),
+            | "png" => Some(Self::Png),
+            | _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    Unsupported(&'static str),
+    Malformed(&'static str),
+    Inflate(InflateError),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            | Self::Io(error) => write!(f, "image io failed: {error}"),
+            | Self::Unsupported(what) => write!(f, "unsupported image: {what}"),
+            | Self::Malformed(what) => write!(f, "malformed image: {what}"),
+            | Self::Inflate(error) => write!(f, "image data failed to inflate: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl From<io::Error> for ImageError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<InflateError> for ImageError {
+    fn from(error: InflateError) -> Self {
+        Self::Inflate(error)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* IMAGE IO */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn encode(image: &Buffer2<PackedColor>, format: Format) -> Vec<u8> {
+    Raster::from_packed(image).encode(format)
+}
+
+pub fn encode_grey(image: &Buffer2<u8>, format: Format) -> Vec<u8> {
+    Raster::from_grey(image).encode(format)
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Buffer2<PackedColor>, ImageError> {
+    let raster = match bytes {
+        | [0x89, b'P', b'N', b'G', ..] => decode_png(bytes)?,
+        | [b'B', b'M', ..] => decode_bmp(bytes)?,
+        | [b'P', b'2' | b'3' | b'5' | b'6', ..] => decode_pnm(bytes)?,
+        | _ => decode_tga(bytes)?,
+    };
+    Ok(raster.to_packed())
+}
+
+pub fn save(path: impl AsRef<Path>, image: &Buffer2<PackedColor>) -> Result<(), ImageError> {
+    let format = Format::from_path(&path).ok_or(ImageError::Unsupported("file extension"))?;
+    Ok(fs::write(path, encode(image, format))?)
+}
+
+pub fn save_grey(path: impl AsRef<Path>, image: &Buffer2<u8>) -> Result<(), ImageError> {
+    let format = Format::from_path(&path).ok_or(ImageError::Unsupported("file extension"))?;
+    Ok(fs::write(path, encode_grey(image, format))?)
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<Buffer2<PackedColor>, ImageError> {
+    decode(&fs::read(path)?)
+}
+
+pub fn depth_to_grey(depth: &Buffer2<f32>) -> Buffer2<u8> {
+    let visible = |value: f32| value.is_finite() && value != depth.clear_value;
+    let (low, high) = depth
+        .data
+        .iter()
+        .filter(|&&value| visible(value))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), &value| (low.min(value), high.max(value)));
+
+    let mut out = Buffer2::new(depth.width, depth.height, 0);
+    out.data
+        .iter_mut()
+        .zip(&depth.data)
+        .filter(|&(_, &value)| visible(value))
+        .for_each(|(grey, &value)| {
+            let range = (high - low).max(f32::EPSILON);
+            *grey = (255. - (value - low) / range * 255.).round() as u8;
+        });
+    out
+}
+
+impl<D> Bitmap<PackedColor, D> {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        save(path, &self.main)
+    }
+
+    pub fn save_depth(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        save_grey(path, &depth_to_grey(&self.depth))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* RASTER */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+struct Raster {
+    width: usize,
+    height: usize,
+    channels: usize,
+    data: Vec<u8>,
+}
+
+impl Raster {
+    fn from_packed(image: &Buffer2<PackedColor>) -> Self {
+        let channels = match image.data.iter().all(|&color| color >> 24 == 0xff) {
+            | true => 3,
+            | false => 4,
+        };
+        let data = image.data.iter().flat_map(|&color| unpack(color)[..channels].to_vec()).collect();
+        Self { width: image.width, height: image.height, channels, data }
+    }
+
+    fn from_grey(image: &Buffer2<u8>) -> Self {
+        Self {
+            width: image.width,
+            height: image.height,
+            channels: 1,
+            data: image.data.clone(),
+        }
+    }
+
+    fn to_packed(&self) -> Buffer2<PackedColor> {
+        let mut out = Buffer2::new(self.width, self.height, 0);
+        out.data.iter_mut().zip(self.data.chunks_exact(self.channels)).for_each(|(color, pixel)| {
+            *color = match *pixel {
+                | [v] => pack([v, v, v, 0xff]),
+                | [v, a] => pack([v, v, v, a]),
+                | [r, g, b] => pack([r, g, b, 0xff]),
+                | [r, g, b, a] => pack([r, g, b, a]),
+                | _ => 0,
+            }
+        });
+        out
+    }
+
+    fn convert(&self, channels: usize) -> Vec<u8> {
+        if channels == self.channels {
+            return self.data.clone();
+        }
+
+        self.data
+            .chunks_exact(self.channels)
+            .flat_map(|pixel| {
+                let [r, g, b, a] = match *pixel {
+                    | [v] => [v, v, v, 0xff],
+                    | [r, g, b] => [r, g, b, 0xff],
+                    | [r, g, b, a] => [r, g, b, a],
+                    | _ => [0; 4],
+                };
+                let luma = ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114 + 500) / 1000) as u8;
+                match channels {
+                    | 1 => vec![luma],
+                    | 3 => vec![r, g, b],
+                    | _ => vec![r, g, b, a],
+                }
+            })
+            .collect()
+    }
+
+    fn encode(&self, format: Format) -> Vec<u8> {
+        match format {
+            | Format::Ppm => self.encode_pnm(b"P6", 3),
+            | Format::PpmAscii => self.encode_pnm(b"P3", 3),
+            | Format::Pgm => self.encode_pnm(b"P5", 1),
+            | Format::PgmAscii => self.encode_pnm(b"P2", 1),
+            | Format::Bmp => self.encode_bmp(),
+            | Format::Tga => self.encode_tga(),
+            | Format::Png => self.encode_png(),
+        }
+    }
+}
+
+fn unpack(color: PackedColor) -> [u8; 4] {
+    let [a, r, g, b] = color.to_be_bytes();
+    [r, g, b, a]
+}
+
+fn pack([r, g, b, a]: [u8; 4]) -> PackedColor {
+    u32::from_be_bytes([a, r, g, b])
+}
+
+struct Reader<'d> {
+    data: &'d [u8],
+    position: usize,
+}
+
+impl<'d> Reader<'d> {
+    fn bytes(&mut self, count: usize) -> Result<&'d [u8], ImageError> {
+        let out = self.data.get(self.position..self.position + count).ok_or(ImageError::Malformed("truncated"))?;
+        self.position += count;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, ImageError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16_le(&mut self) -> Result<u16, ImageError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap_or_default()))
+    }
+
+    fn u32_le(&mut self) -> Result<u32, ImageError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap_or_default()))
+    }
+
+    fn u32_be(&mut self) -> Result<u32, ImageError> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap_or_default()))
+    }
+}
+
+fn checked_size(width: usize, height: usize, channels: usize) -> Result<usize, ImageError> {
+    width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(channels))
+        .filter(|&size| size <= 1 << 30)
+        .ok_or(ImageError::Unsupported("image dimensions"))
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PNM */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Raster {
+    fn encode_pnm(&self, magic: &[u8], channels: usize) -> Vec<u8> {
+        let mut out = magic.to_vec();
+        out.extend(format!("\n{} {}\n255\n", self.width, self.height).bytes());
+
+        let samples = self.convert(channels);
+        if magic == b"P5" || magic == b"P6" {
+            out.extend(samples);
+            return out;
+        }
+
+        let mut line = 0;
+        samples.iter().enumerate().for_each(|(i, sample)| {
+            let text = sample.to_string();
+            let row_start = i % (self.width * channels) == 0;
+            if i > 0 && (row_start || line + text.len() + 1 > 70) {
+                out.push(b'\n');
+                line = 0;
+            } else if i > 0 {
+                out.push(b' ');
+                line += 1;
+            }
+            out.extend(text.bytes());
+            line += text.len();
+        });
+        out.push(b'\n');
+        out
+    }
+}
+
+fn decode_pnm(bytes: &[u8]) -> Result<Raster, ImageError> {
+    let mut position = 2;
+    let mut token = || {
+        loop {
+            match bytes.get(position) {
+                | Some(b'#') => {
+                    while bytes.get(position).is_some_and(|&byte| byte != b'\n') {
+                        position += 1;
+                    }
+                }
+                | Some(byte) if byte.is_ascii_whitespace() => position += 1,
+                | _ => break,
+            }
+        }
+
+        let start = position;
+        while bytes.get(position).is_some_and(|byte| byte.is_ascii_digit()) {
+            position += 1;
+        }
+        let value = std::str::from_utf8(&bytes[start..position]).ok().and_then(|text| text.parse::<usize>().ok());
+        (value.ok_or(ImageError::Malformed("pnm header")), position)
+    };
+
+    let (width, height, max) = (token().0?, token().0?, token());
+    let (max, end) = (max.0?, max.1);
+    if max == 0 || max > 65535 {
+        return Err(ImageError::Malformed("pnm maximum value"));
+    }
+
+    let channels = match bytes[1] {
+        | b'2' | b'5' => 1,
+        | _ => 3,
+    };
+    let count = checked_size(width, height, channels)?;
+    let scale = |value: usize| ((value.min(max) * 255 + max / 2) / max) as u8;
+
+    let data = match bytes[1] {
+        | b'5' | b'6' => {
+            let wide = (max > 255) as usize + 1;
+            let body = bytes.get(end + 1..end + 1 + count * wide).ok_or(ImageError::Malformed("truncated"))?;
+            body.chunks_exact(wide)
+                .map(|sample| scale(sample.iter().fold(0, |acc, &byte| acc << 8 | byte as usize)))
+                .collect()
+        }
+        | _ => {
+            let mut token = token;
+            (0..count).map(|_| token().0.map(scale)).collect::<Result<Vec<_>, _>>()?
+        }
+    };
+    Ok(Raster { width, height, channels, data })
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BMP */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Raster {
+    fn encode_bmp(&self) -> Vec<u8> {
+        let channels = self.channels.max(3);
+        let samples = self.convert(channels);
+        let stride = (self.width * channels + 3) & !3;
+        let size = stride * self.height;
+
+        let mut out = Vec::with_capacity(54 + size);
+        out.extend(b"BM");
+        [54 + size as u32, 0, 54].iter().for_each(|value| out.extend(value.to_le_bytes()));
+        [40, self.width as u32, self.height as u32].iter().for_each(|value| out.extend(value.to_le_bytes()));
+        out.extend(1u16.to_le_bytes());
+        out.extend((channels as u16 * 8).to_le_bytes());
+        [0, size as u32, 2835, 2835, 0, 0].iter().for_each(|value| out.extend(value.to_le_bytes()));
+
+        (0..self.height).rev().for_each(|y| {
+            let row = &samples[y * self.width * channels..(y + 1) * self.width * channels];
+            row.chunks_exact(channels).for_each(|pixel| {
+                out.extend([pixel[2], pixel[1], pixel[0]]);
+                out.extend(&pixel[3..]);
+            });
+            out.resize(out.len() + stride - self.width * channels, 0);
+        });
+        out
+    }
+}
+
+fn decode_bmp(bytes: &[u8]) -> Result<Raster, ImageError> {
+    let mut reader = Reader { data: bytes, position: 10 };
+    let offset = reader.u32_le()? as usize;
+    let header = reader.u32_le()? as usize;
+    if header < 40 {
+        return Err(ImageError::Unsupported("bmp core header"));
+    }
+
+    let (width, height) = (reader.u32_le()? as i32, reader.u32_le()? as i32);
+    let (_, bits, compression) = (reader.u16_le()?, reader.u16_le()?, reader.u32_le()?);
+    if compression != 0 || !matches!(bits, 24 | 32) {
+        return Err(ImageError::Unsupported("compressed or paletted bmp"));
+    }
+    if width <= 0 || height == 0 {
+        return Err(ImageError::Malformed("bmp dimensions"));
+    }
+
+    let (width, rows, channels) = (width as usize, height.unsigned_abs() as usize, bits as usize / 8);
+    let stride = (width * channels + 3) & !3;
+    let mut data = vec![0; checked_size(width, rows, 4)?];
+    let body = bytes.get(offset..offset + stride * rows).ok_or(ImageError::Malformed("truncated"))?;
+    body.chunks_exact(stride).enumerate().for_each(|(row, line)| {
+        let y = match height < 0 {
+            | true => row,
+            | false => rows - 1 - row,
+        };
+        line.chunks_exact(channels).take(width).enumerate().for_each(|(x, pixel)| {
+            let alpha = pixel.get(3).copied().unwrap_or(0xff);
+            data[(y * width + x) * 4..][..4].copy_from_slice(&[pixel[2], pixel[1], pixel[0], alpha]);
+        });
+    });
+
+    if channels == 4 && data.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+        data.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 0xff);
+    }
+    Ok(Raster { width, height: rows, channels: 4, data })
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TGA */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+impl Raster {
+    fn encode_tga(&self) -> Vec<u8> {
+        let (kind, channels) = match self.channels {
+            | 1 => (3, 1),
+            | 4 => (2, 4),
+            | _ => (2, 3),
+        };
+        let alpha_bits = (channels == 4) as u8 * 8;
+
+        let mut out = vec![0, 0, kind, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        out.extend((self.width as u16).to_le_bytes());
+        out.extend((self.height as u16).to_le_bytes());
+        out.extend([channels as u8 * 8, 0x20 | alpha_bits]);
+        self.convert(channels).chunks_exact(channels).for_each(|pixel| match *pixel {
+            | [r, g, b, ..] => {
+                out.extend([b, g, r]);
+                out.extend(&pixel[3..]);
+            }
+            | _ => out.extend(pixel),
+        });
+        out
+    }
+}
+
+fn decode_tga(bytes: &[u8]) -> Result<Raster, ImageError> {
+    let mut reader = Reader { data: bytes, position: 0 };
+    let (id_length, map_type, kind) = (reader.u8()? as usize, reader.u8()?, reader.u8()?);
+    let (_, map_length, map_bits) = (reader.u16_le()?, reader.u16_le()? as usize, reader.u8()? as usize);
+    reader.bytes(4)?;
+    let (width, height) = (reader.u16_le()? as usize, reader.u16_le()? as usize);
+    let (bits, descriptor) = (reader.u8()?, reader.u8()?);
+
+    let channels = match (kind, bits) {
+        | (2, 24) => 3,
+        | (2, 32) => 4,
+        | (3, 8) => 1,
+        | (1 | 9 | 10 | 11, _) => return Err(ImageError::Unsupported("paletted or run-length tga")),
+        | _ => return Err(ImageError::Malformed("tga header")),
+    };
+    if map_type > 1 {
+        return Err(ImageError::Malformed("tga header"));
+    }
+    if width == 0 || height == 0 {
+        return Err(ImageError::Malformed("tga dimensions"));
+    }
+
+    reader.bytes(id_length + map_type as usize * map_length * map_bits.div_ceil(8))?;
+    let body = reader.bytes(checked_size(width, height, channels)?)?;
+    let mut data = vec![0; width * height * channels];
+    body.chunks_exact(width * channels).enumerate().for_each(|(row, line)| {
+        let y = match descriptor & 0x20 != 0 {
+            | true => row,
+            | false => height - 1 - row,
+        };
+        line.chunks_exact(channels).enumerate().for_each(|(column, pixel)| {
+            let x = match descriptor & 0x10 != 0 {
+                | true => width - 1 - column,
+                | false => column,
+            };
+            let target = &mut data[(y * width + x) * channels..][..channels];
+            match *pixel {
+                | [b, g, r, ..] => {
+                    target[..3].copy_from_slice(&[r, g, b]);
+                    target[3..].copy_from_slice(&pixel[3..]);
+                }
+                | _ => target.copy_from_slice(pixel),
+            }
+        });
+    });
+    Ok(Raster { width, height, channels, data })
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PNG */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = match c & 1 {
+                | 1 => 0xedb8_8320 ^ (c >> 1),
+                | _ => c >> 1,
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &byte| CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    match (pa <= pb && pa <= pc, pb <= pc) {
+        | (true, _) => a,
+        | (false, true) => b,
+        | (false, false) => c,
+    }
+}
+
+fn predict(kind: u8, a: u8, b: u8, c: u8) -> u8 {
+    match kind {
+        | 1 => a,
+        | 2 => b,
+        | 3 => ((a as u16 + b as u16) / 2) as u8,
+        | 4 => paeth(a, b, c),
+        | _ => 0,
+    }
+}
+
+impl Raster {
+    fn encode_png(&self) -> Vec<u8> {
+        let color_type = match self.channels {
+            | 1 => 0,
+            | 3 => 2,
+            | _ => 6,
+        };
+        let (bpp, stride) = (self.channels, self.width * self.channels);
+
+        let mut filtered = Vec::with_capacity((stride + 1) * self.height);
+        let mut candidate = vec![0; stride];
+        let zero = vec![0; stride];
+        self.data.chunks_exact(stride.max(1)).enumerate().for_each(|(y, line)| {
+            let previous = match y {
+                | 0 => &zero[..],
+                | _ => &self.data[(y - 1) * stride..y * stride],
+            };
+
+            let (mut best, mut best_score) = (0, u64::MAX);
+            (0..5u8).for_each(|kind| {
+                let score = (0..stride).fold(0, |score, i| {
+                    let (a, c) = match i >= bpp {
+                        | true => (line[i - bpp], previous[i - bpp]),
+                        | false => (0, 0),
+                    };
+                    score + (line[i].wrapping_sub(predict(kind, a, previous[i], c)) as i8).unsigned_abs() as u64
+                });
+                if score < best_score {
+                    (best, best_score) = (kind, score);
+                }
+            });
+
+            (0..stride).for_each(|i| {
+                let (a, c) = match i >= bpp {
+                    | true => (line[i - bpp], previous[i - bpp]),
+                    | false => (0, 0),
+                };
+                candidate[i] = line[i].wrapping_sub(predict(best, a, previous[i], c));
+            });
+            filtered.push(best);
+            filtered.extend(&candidate);
+        });
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        header.extend([8, color_type, 0, 0, 0]);
+
+        let mut out = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut out, b"IHDR", &header);
+        write_chunk(&mut out, b"IDAT", &zlib_compress(&filtered));
+        write_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Raster, ImageError> {
+    if bytes.get(..8) != Some(&PNG_SIGNATURE[..]) {
+        return Err(ImageError::Malformed("png signature"));
+    }
+
+    let mut reader = Reader { data: bytes, position: 8 };
+    let (mut header, mut palette, mut transparency, mut compressed) = (None, Vec::new(), Vec::new(), Vec::new());
+    loop {
+        let length = reader.u32_be()? as usize;
+        let start = reader.position;
+        let kind = reader.bytes(4)?;
+        let data = reader.bytes(length)?;
+        if reader.u32_be()? != crc32(&bytes[start..start + 4 + length]) {
+            return Err(ImageError::Malformed("png chunk checksum"));
+        }
+
+        match kind {
+            | b"IHDR" => header = Some(data),
+            | b"PLTE" => palette = data.to_vec(),
+            | b"tRNS" => transparency = data.to_vec(),
+            | b"IDAT" => compressed.extend_from_slice(data),
+            | b"IEND" => break,
+            | _ if kind[0] & 0x20 == 0 => return Err(ImageError::Unsupported("critical png chunk")),
+            | _ => {}
+        }
+    }
+
+    let header = header.filter(|header| header.len() == 13).ok_or(ImageError::Malformed("png header"))?;
+    let width = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let height = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let (depth, color_type) = (header[8] as usize, header[9]);
+    if header[12] != 0 {
+        return Err(ImageError::Unsupported("interlaced png"));
+    }
+
+    let samples = match (color_type, depth) {
+        | (0, 1 | 2 | 4 | 8 | 16) => 1,
+        | (2, 8 | 16) => 3,
+        | (3, 1 | 2 | 4 | 8) => 1,
+        | (4, 8 | 16) => 2,
+        | (6, 8 | 16) => 4,
+        | _ => return Err(ImageError::Malformed("png color type and depth")),
+    };
+    let bpp = (samples * depth).div_ceil(8);
+    let stride = (width * samples * depth).div_ceil(8);
+    checked_size(width, height, 4)?;
+
+    let filtered = zlib_decompress_limited(&compressed, height * (stride + 1))?;
+    if filtered.len() < (stride + 1) * height {
+        return Err(ImageError::Malformed("png image data"));
+    }
+
+    let mut lines = vec![0u8; stride * height];
+    (0..height).try_for_each(|y| {
+        let (kind, line) = (filtered[y * (stride + 1)], &filtered[y * (stride + 1) + 1..(y + 1) * (stride + 1)]);
+        if kind > 4 {
+            return Err(ImageError::Malformed("png filter"));
+        }
+
+        (0..stride).for_each(|i| {
+            let a = match i >= bpp {
+                | true => lines[y * stride + i - bpp],
+                | false => 0,
+            };
+            let (b, c) = match (y > 0, i >= bpp) {
+                | (true, true) => (lines[(y - 1) * stride + i], lines[(y - 1) * stride + i - bpp]),
+                | (true, false) => (lines[(y - 1) * stride + i], 0),
+                | _ => (0, 0),
+            };
+            lines[y * stride + i] = line[i].wrapping_add(predict(kind, a, b, c));
+        });
+        Ok(())
+    })?;
+
+    let sample = |y: usize, index: usize| -> u16 {
+        let line = &lines[y * stride..(y + 1) * stride];
+        match depth {
+            | 16 => u16::from_be_bytes([line[index * 2], line[index * 2 + 1]]),
+            | 8 => line[index] as u16,
+            | _ => {
+                let bit = index * depth;
+                ((line[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1) as u8) as u16
+            }
+        }
+    };
+    let scale = |value: u16| match depth {
+        | 16 => (value >> 8) as u8,
+        | _ => (value as usize * 255 / ((1 << depth) - 1)) as u8,
+    };
+    let key =
+        |index: usize| transparency.get(index * 2..index * 2 + 2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+
+    let mut data = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let raw: Vec<u16> = (0..samples).map(|s| sample(y, x * samples + s)).collect();
+            let pixel = match color_type {
+                | 3 => {
+                    let index = raw[0] as usize;
+                    let rgb = palette.get(index * 3..index * 3 + 3).ok_or(ImageError::Malformed("png palette"))?;
+                    [rgb[0], rgb[1], rgb[2], transparency.get(index).copied().unwrap_or(0xff)]
+                }
+                | 0 => {
+                    let v = scale(raw[0]);
+                    [v, v, v, if key(0) == Some(raw[0]) { 0 } else { 0xff }]
+                }
+                | 4 => [scale(raw[0]), scale(raw[0]), scale(raw[0]), scale(raw[1])],
+                | 2 => {
+                    let clear = (0..3).all(|s| key(s) == Some(raw[s]));
+                    [
+                        scale(raw[0]),
+                        scale(raw[1]),
+                        scale(raw[2]),
+                        if clear { 0 } else { 0xff },
+                    ]
+                }
+                | _ => [scale(raw[0]), scale(raw[1]), scale(raw[2]), scale(raw[3])],
+            };
+            data.extend(pixel);
+        }
+    }
+    Ok(Raster { width, height, channels: 4, data })
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! golden {
+        ($name:literal) => {
+            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/", $name)).as_slice()
+        };
+    }
+
+    fn pattern(alpha: bool) -> Buffer2<PackedColor> {
+        let mut out = Buffer2::new(5, 3, 0);
+        (0..3).for_each(|y| {
+            (0..5).for_each(|x| {
+                let a = match alpha {
+                    | true => 255 - x * 50 - y * 10,
+                    | false => 255,
+                };
+                out.set(x, y, pack([x as u8 * 60, y as u8 * 100 + 20, (x + y) as u8 * 30, a as u8]));
+            });
+        });
+        out
+    }
+
+    fn grey(value: impl Fn(usize, usize) -> u8) -> Buffer2<PackedColor> {
+        let mut out = Buffer2::new(5, 3, 0);
+        (0..3).for_each(|y| {
+            (0..5).for_each(|x| {
+                let v = value(x, y);
+                out.set(x, y, pack([v, v, v, 255]));
+            });
+        });
+        out
+    }
+
+    #[test]
+    fn simple_formats() {
+        let (opaque, translucent) = (pattern(false), pattern(true));
+        let shades = grey(|x, y| (x * 50 + y * 5) as u8);
+
+        assert!(decode(golden!("pattern.ppm")).unwrap().data == opaque.data);
+        assert!(decode(golden!("pattern_ascii.ppm")).unwrap().data == opaque.data);
+        assert!(decode(golden!("grey.pgm")).unwrap().data == shades.data);
+        assert!(decode(golden!("grey_ascii.pgm")).unwrap().data == grey(|x, y| (x * 3 + y) as u8 * 17).data);
+        assert!(decode(golden!("grey.tga")).unwrap().data == shades.data);
+
+        assert!(encode(&opaque, Format::Bmp) == golden!("pattern.bmp"));
+        assert!(encode(&translucent, Format::Bmp) == golden!("alpha.bmp"));
+        assert!(encode(&opaque, Format::Tga) == golden!("pattern.tga"));
+        assert!(encode(&translucent, Format::Tga) == golden!("alpha.tga"));
+        assert!(decode(golden!("alpha.bmp")).unwrap().data == translucent.data);
+        assert!(decode(golden!("alpha.tga")).unwrap().data == translucent.data);
+
+        for format in [Format::Ppm, Format::PpmAscii, Format::Bmp, Format::Tga, Format::Png] {
+            assert!(decode(&encode(&opaque, format)).unwrap().data == opaque.data);
+        }
+        for format in [Format::Pgm, Format::PgmAscii] {
+            assert!(decode(&encode(&shades, format)).unwrap().data == shades.data);
+        }
+    }
+
+    #[test]
+    fn png_codec() {
+        let (opaque, translucent) = (pattern(false), pattern(true));
+        assert!(decode(golden!("pattern.png")).unwrap().data == opaque.data);
+        assert!(decode(golden!("alpha.png")).unwrap().data == translucent.data);
+        assert!(decode(golden!("grey16.png")).unwrap().data == grey(|x, y| (x * 50 + y * 5) as u8).data);
+
+        let palette = decode(golden!("palette.png")).unwrap();
+        let colors = [
+            pack([255, 0, 0, 255]),
+            pack([0, 255, 0, 128]),
+            pack([0, 0, 255, 255]),
+            pack([255; 4]),
+        ];
+        assert!((0..3).all(|y| (0..5).all(|x| palette.get(x, y) == Some(colors[(x + y) % 4]))));
+
+        let mut large = Buffer2::new(97, 61, 0);
+        (0..61).for_each(|y| {
+            (0..97).for_each(|x| _ = large.set(x, y, pack([(x * 3) as u8, (y * 4) as u8, (x ^ y) as u8, 255])))
+        });
+        let encoded = encode(&large, Format::Png);
+        assert!(decode(&encoded).unwrap().data == large.data && encoded.len() < 97 * 61 * 3);
+        assert!(decode(&encode(&translucent, Format::Png)).unwrap().data == translucent.data);
+
+        let mut corrupted = encoded.clone();
+        corrupted[40] ^= 0xff;
+        assert!(matches!(decode(&corrupted), Err(ImageError::Malformed(_) | ImageError::Inflate(_))));
+        assert!(crc32(b"123456789") == 0xcbf4_3926);
+
+        let mut bomb = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        write_chunk(&mut bomb, b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]);
+        write_chunk(&mut bomb, b"IDAT", &zlib_compress(&[0; 1 << 20]));
+        write_chunk(&mut bomb, b"IEND", &[]);
+        assert!(matches!(decode(&bomb), Err(ImageError::Inflate(InflateError::TooLarge))));
+    }
+
+    #[test]
+    fn hostile_input() {
+        let goldens: [&[u8]; 7] = [
+            golden!("pattern.ppm"),
+            golden!("grey.pgm"),
+            golden!("pattern.bmp"),
+            golden!("alpha.tga"),
+            golden!("pattern.png"),
+            golden!("palette.png"),
+            golden!("grey16.png"),
+        ];
+        for file in goldens {
+            (0..file.len()).for_each(|length| _ = decode(&file[..length]));
+            (0..file.len().min(64)).for_each(|at| {
+                let mut zeroed = file.to_vec();
+                zeroed[at..(at + 4).min(file.len())].fill(0);
+                _ = decode(&zeroed);
+            });
+        }
+
+        let mut header = vec![0u8; 18 + 64];
+        header[2] = 2;
+        header[14] = 4;
+        header[16] = 24;
+        assert!(matches!(decode(&header), Err(ImageError::Malformed(_))));
+        assert!(decode(&[]).is_err() && decode(&[0; 18]).is_err());
+    }
+
+    #[test]
+    fn depth_export() {
+        let mut bitmap = Bitmap::<PackedColor, ()>::new(4, 2, pack([0, 0, 0, 255]), f32::INFINITY);
+        [(0, 0, 1.), (1, 0, 2.), (2, 0, 3.), (3, 1, 1.5)]
+            .iter()
+            .for_each(|&(x, y, depth)| _ = bitmap.depth.set(x, y, depth));
+
+        let grey = depth_to_grey(&bitmap.depth);
+        assert!(grey.data == [255, 128, 0, 0, 0, 0, 0, 191]);
+
+        let path = std::env::temp_dir().join(format!("toolbox-depth-{}.pgm", std::process::id()));
+        bitmap.save_depth(&path).unwrap();
+        let loaded = load(&path).unwrap();
+        _ = fs::remove_file(&path);
+        assert!(loaded.data.iter().map(|&color| unpack(color)[0]).collect::<Vec<_>>() == grey.data);
+        assert!(matches!(bitmap.save("image.unknown"), Err(ImageError::Unsupported(_))));
+    }
+}
@@ -0,0 +1,393 @@
+pub mod color;
+pub mod deflate;
+pub mod draw;
+pub mod filters;
+pub mod image;
+
+use std::slice;
+use std::thread;
+
+use crate::math::traits::Scalar;
+use crate::math::vector::Vector2;
+use crate::math::vector::Vector3;
+use crate::vec3;
+use crate::vector;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BUFFER 2D */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Buffer2<T> {
+    pub data: Vec<T>,
+    pub clear_value: T,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<T> Buffer2<T>
+where
+    T: Clone + Copy,
+{
+    pub fn new(width: usize, height: usize, clear_value: T) -> Self {
+        Self {
+            data: vec![clear_value; width * height],
+            clear_value,
+            width,
+            height,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(self.clear_value);
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<T> {
+        if !self.inbounds(x, y) {
+            return None;
+        }
+
+        let index = self.index(x, y);
+        Some(self.data[index])
+    }
+
+    pub fn get_unchecked(&self, x: usize, y: usize) -> T {
+        let index = self.index(x, y);
+        self.data[index]
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, data: T) -> Option<()> {
+        if !self.inbounds(x, y) {
+            return None;
+        }
+
+        let index = self.index(x, y);
+        self.data[index] = data;
+        Some(())
+    }
+
+    #[inline(always)]
+    pub fn set_unchecked(&mut self, x: usize, y: usize, data: T) {
+        let index = self.index(x, y);
+        self.data[index] = data;
+    }
+
+    #[inline(always)]
+    pub fn inbounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn rows_mut(&mut self) -> slice::ChunksExactMut<'_, T> {
+        self.data.chunks_exact_mut(self.width.max(1))
+    }
+
+    pub fn chunks_mut(&mut self, rows: usize) -> impl Iterator<Item = (usize, &mut [T])> {
+        let rows = rows.max(1);
+        self.data
+            .chunks_mut((self.width * rows).max(1))
+            .enumerate()
+            .map(move |(band, chunk)| (band * rows, chunk))
+    }
+
+    pub fn tiles_mut(&mut self, tile_width: usize, tile_height: usize) -> Vec<Tile<'_, T>> {
+        let (width, tile_width, tile_height) = (self.width, tile_width.max(1), tile_height.max(1));
+        let mut tiles = Vec::new();
+        if width == 0 {
+            return tiles;
+        }
+
+        for (band, rows) in self.data.chunks_mut(width * tile_height).enumerate() {
+            let first = tiles.len();
+            tiles.extend((0..width.div_ceil(tile_width)).map(|column| Tile {
+                x: column * tile_width,
+                y: band * tile_height,
+                width: tile_width.min(width - column * tile_width),
+                height: rows.len() / width,
+                rows: Vec::with_capacity(tile_height),
+            }));
+
+            rows.chunks_mut(width).for_each(|row| {
+                row.chunks_mut(tile_width)
+                    .enumerate()
+                    .for_each(|(column, piece)| tiles[first + column].rows.push(piece));
+            });
+        }
+        tiles
+    }
+
+    pub fn par_for_each_tile(&mut self, tile_width: usize, tile_height: usize, f: impl Fn(&mut Tile<'_, T>) + Sync)
+    where
+        T: Send,
+    {
+        let workers = thread::available_parallelism().map_or(1, |count| count.get());
+        let mut buckets: Vec<Vec<Tile<'_, T>>> = (0..workers).map(|_| Vec::new()).collect();
+        self.tiles_mut(tile_width, tile_height)
+            .into_iter()
+            .enumerate()
+            .for_each(|(i, tile)| buckets[i % workers].push(tile));
+
+        let f = &f;
+        thread::scope(|scope| {
+            buckets.into_iter().filter(|bucket| !bucket.is_empty()).for_each(|mut bucket| {
+                scope.spawn(move || bucket.iter_mut().for_each(f));
+            });
+        });
+    }
+
+    #[inline(always)]
+    fn index(&self, x: usize, y: usize) -> usize {
+        self.width * y + x
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BUFFER TILE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Tile<'d, T> {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    rows: Vec<&'d mut [T]>,
+}
+
+impl<T> Tile<'_, T>
+where
+    T: Clone + Copy,
+{
+    pub fn get(&self, x: usize, y: usize) -> Option<T> {
+        self.rows.get(y)?.get(x).copied()
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, data: T) -> Option<()> {
+        *self.rows.get_mut(y)?.get_mut(x)? = data;
+        Some(())
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        self.rows[y]
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = (usize, &mut [T])> {
+        let top = self.y;
+        self.rows.iter_mut().enumerate().map(move |(y, row)| (top + y, &mut **row))
+    }
+
+    pub fn fill(&mut self, data: T) {
+        self.rows.iter_mut().for_each(|row| row.fill(data));
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BUFFER 3D */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Buffer3<T> {
+    pub data: Vec<T>,
+    pub clear_value: T,
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+}
+
+impl<T> Buffer3<T>
+where
+    T: Clone + Copy,
+{
+    pub fn new(width: usize, height: usize, depth: usize, clear_value: T) -> Self {
+        Self {
+            data: vec![clear_value; width * height * depth],
+            clear_value,
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(self.clear_value);
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<T> {
+        if !self.inbounds(x, y, z) {
+            return None;
+        }
+
+        let index = self.index(x, y, z);
+        Some(self.data[index])
+    }
+
+    pub fn get_unchecked(&self, x: usize, y: usize, z: usize) -> T {
+        let index = self.index(x, y, z);
+        self.data[index]
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, z: usize, data: T) -> Option<()> {
+        if !self.inbounds(x, y, z) {
+            return None;
+        }
+
+        let index = self.index(x, y, z);
+        self.data[index] = data;
+        Some(())
+    }
+
+    #[inline(always)]
+    pub fn set_unchecked(&mut self, x: usize, y: usize, z: usize, data: T) {
+        let index = self.index(x, y, z);
+        self.data[index] = data;
+    }
+
+    #[inline(always)]
+    pub fn inbounds(&self, x: usize, y: usize, z: usize) -> bool {
+        x < self.width && y < self.height && z < self.depth
+    }
+
+    pub fn rows_mut(&mut self) -> slice::ChunksExactMut<'_, T> {
+        self.data.chunks_exact_mut(self.width.max(1))
+    }
+
+    pub fn slices_mut(&mut self) -> slice::ChunksExactMut<'_, T> {
+        self.data.chunks_exact_mut((self.width * self.height).max(1))
+    }
+
+    pub fn chunks_mut(&mut self, layers: usize) -> impl Iterator<Item = (usize, &mut [T])> {
+        let layers = layers.max(1);
+        let size = (self.width * self.height * layers).max(1);
+        self.data.chunks_mut(size).enumerate().map(move |(chunk, data)| (chunk * layers, data))
+    }
+
+    pub fn par_for_each_slice(&mut self, f: impl Fn(usize, &mut [T]) + Sync)
+    where
+        T: Send,
+    {
+        let workers = thread::available_parallelism().map_or(1, |count| count.get());
+        let layers = self.depth.div_ceil(workers);
+
+        let f = &f;
+        thread::scope(|scope| {
+            let size = self.width * self.height;
+            self.chunks_mut(layers).for_each(|(first, chunk)| {
+                scope.spawn(move || {
+                    chunk.chunks_exact_mut(size.max(1)).enumerate().for_each(|(z, slice)| f(first + z, slice));
+                });
+            });
+        });
+    }
+
+    #[inline(always)]
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        self.width * self.height * z + self.width * y + x
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* RENDER BITMAP */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Bitmap<T, D> {
+    pub main: Buffer2<T>,
+    pub depth: Buffer2<f32>,
+    pub dim: Vector2<usize>,
+
+    pub util: Vec<D>,
+}
+
+impl<T, D> Bitmap<T, D>
+where
+    T: Scalar,
+{
+    pub fn new(width: usize, height: usize, main_clear: T, depth_clear: f32) -> Self {
+        Self {
+            main: Buffer2::new(width, height, main_clear),
+            depth: Buffer2::new(width, height, depth_clear),
+            dim: vector!(usize; width, height),
+
+            util: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.main.clear();
+        self.depth.clear();
+    }
+
+    pub fn update_from(&mut self, payload: BitmapPayload<T>) -> Option<()> {
+        let [x, y] = payload.position.array();
+        if !self.inbounds(x, y) {
+            return None;
+        }
+
+        self.main.set_unchecked(x, y, payload.data);
+        self.depth.set_unchecked(x, y, payload.depth);
+        Some(())
+    }
+
+    pub fn inbounds(&self, x: usize, y: usize) -> bool {
+        self.dim.x > x && self.dim.y > y
+    }
+}
+
+pub struct BitmapPayload<T> {
+    pub position: Vector2<usize>,
+    pub data: T,
+    pub depth: f32,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BITMAP UTILS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub type Color = Vector3<f32>;
+
+pub type PackedColor = u32;
+
+pub fn pack_color(color: Color) -> PackedColor {
+    let [r, g, b] = color.array().map(|value| color::to_byte(value) as u32);
+    (0xff << 24) | (r << 16) | (g << 8) | b
+}
+
+pub fn unpack_color(color: PackedColor) -> Color {
+    let [_, r, g, b] = color.to_be_bytes();
+    vec3!(r, g, b) / 255.
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer2_splitting() {
+        let mut buffer = Buffer2::new(7, 5, 0usize);
+        buffer.rows_mut().enumerate().for_each(|(y, row)| row.fill(y));
+        assert!(buffer.get(6, 4) == Some(4));
+
+        buffer.chunks_mut(2).for_each(|(y, chunk)| chunk.fill(y * 10));
+        assert!(buffer.get(0, 1) == Some(0) && buffer.get(3, 3) == Some(20) && buffer.get(6, 4) == Some(40));
+
+        let tiles = buffer.tiles_mut(3, 2);
+        assert!(tiles.len() == 9 && tiles[2].width == 1 && tiles[8].height == 1 && tiles[4].x == 3);
+
+        buffer.par_for_each_tile(3, 2, |tile| {
+            let left = tile.x;
+            tile.rows_mut().for_each(|(y, row)| {
+                row.iter_mut().enumerate().for_each(|(i, value)| *value = y * 100 + left + i);
+            });
+        });
+        assert!((0..5).all(|y| (0..7).all(|x| buffer.get(x, y) == Some(y * 100 + x))));
+    }
+
+    #[test]
+    fn buffer3_splitting() {
+        let mut buffer = Buffer3::new(4, 3, 5, 0u32);
+        buffer.slices_mut().enumerate().for_each(|(z, slice)| slice.fill(z as u32));
+        assert!(buffer.get(3, 2, 4) == Some(4) && buffer.rows_mut().count() == 15);
+
+        buffer
+            .par_for_each_slice(|z, slice| slice.iter_mut().enumerate().for_each(|(i, v)| *v += (z * 100 + i) as u32));
+        assert!(buffer.get(1, 2, 3) == Some(3 + 300 + 9));
+
+        let mut empty = Buffer2::new(0, 4, 0u8);
+        assert!(empty.tiles_mut(2, 2).is_empty() && empty.rows_mut().count() == 0);
+    }
+}
@@ -0,0 +1,526 @@
+use crate::math::geometry::Aabb;
+use crate::math::geometry::Capsule;
+use crate::math::geometry::ClosestPoint;
+use crate::math::geometry::Intersects;
+use crate::math::geometry::Obb;
+use crate::math::geometry::Ray;
+use crate::math::geometry::RayCast;
+use crate::math::geometry::RayHit;
+use crate::math::geometry::Sphere;
+use crate::math::geometry::Triangle;
+use crate::math::traits::FloatNumber;
+use crate::math::vector::BasicVectorOps;
+use crate::math::vector::Vector3;
+
+const BINS: usize = 12;
+const MAX_LEAF: usize = 4;
+
+pub trait Bounded<T>
+where
+    T: FloatNumber<T>,
+{
+    fn bounds(&self) -> Aabb<T>;
+
+    fn centroid(&self) -> Vector3<T> {
+        self.bounds().center()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BVH */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Node<T> {
+    pub bounds: Aabb<T>,
+    pub first: usize,
+    pub count: usize,
+}
+
+impl<T> Node<T> {
+    pub fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bvh<T, P> {
+    primitives: Vec<P>,
+    indices: Vec<usize>,
+    nodes: Vec<Node<T>>,
+}
+
+impl<T, P> Bvh<T, P>
+where
+    T: FloatNumber<T>,
+    P: Bounded<T>,
+{
+    pub fn build(primitives: Vec<P>) -> Self {
+        let mut out = Self {
+            indices: (0..primitives.len()).collect(),
+            nodes: Vec::new(),
+            primitives,
+        };
+        if out.primitives.is_empty() {
+            return out;
+        }
+
+        let bounds: Vec<Aabb<T>> = out.primitives.iter().map(|primitive| primitive.bounds()).collect();
+        let centroids: Vec<Vector3<T>> = out.primitives.iter().map(|primitive| primitive.centroid()).collect();
+
+        out.nodes.push(Node { bounds: bounds[0], first: 0, count: out.primitives.len() });
+        out.subdivide(0, &bounds, &centroids);
+        out
+    }
+
+    pub fn primitives(&self) -> &[P] {
+        &self.primitives
+    }
+
+    pub fn primitives_mut(&mut self) -> &mut [P] {
+        &mut self.primitives
+    }
+
+    pub fn nodes(&self) -> &[Node<T>] {
+        &self.nodes
+    }
+
+    pub fn leaf_primitives(&self, node: &Node<T>) -> &[usize] {
+        &self.indices[node.first..node.first + node.count]
+    }
+
+    pub fn refit(&mut self) {
+        (0..self.nodes.len()).rev().for_each(|index| {
+            let node = self.nodes[index];
+            self.nodes[index].bounds = match node.is_leaf() {
+                | true => self.leaf_bounds(node.first, node.count),
+                | false => self.nodes[node.first].bounds.union(&self.nodes[node.first + 1].bounds),
+            };
+        });
+    }
+
+    pub fn overlapping(&self, query: &Aabb<T>) -> Vec<usize> {
+        let mut out = Vec::new();
+        self.walk(
+            |bounds| bounds.intersects(query),
+            |index| {
+                if self.primitives[index].bounds().intersects(query) {
+                    out.push(index);
+                }
+                false
+            },
+        );
+        out
+    }
+
+    fn subdivide(&mut self, index: usize, bounds: &[Aabb<T>], centroids: &[Vector3<T>]) {
+        let Node { first, count, .. } = self.nodes[index];
+        let slice = &self.indices[first..first + count];
+        self.nodes[index].bounds = slice.iter().skip(1).fold(bounds[slice[0]], |acc, &i| acc.union(&bounds[i]));
+        if count <= 1 {
+            return;
+        }
+
+        let Some((axis, split)) = self.best_split(first, count, bounds, centroids) else {
+            return;
+        };
+
+        let (mut low, mut high) = (first, first + count);
+        while low < high {
+            match centroids[self.indices[low]].array()[axis] < split {
+                | true => low += 1,
+                | false => {
+                    high -= 1;
+                    self.indices.swap(low, high);
+                }
+            }
+        }
+
+        let left_count = low - first;
+        if left_count == 0 || left_count == count {
+            return;
+        }
+
+        let left = self.nodes.len();
+        self.nodes.push(Node {
+            bounds: bounds[self.indices[first]],
+            first,
+            count: left_count,
+        });
+        self.nodes.push(Node {
+            bounds: bounds[self.indices[low]],
+            first: low,
+            count: count - left_count,
+        });
+        self.nodes[index].first = left;
+        self.nodes[index].count = 0;
+
+        self.subdivide(left, bounds, centroids);
+        self.subdivide(left + 1, bounds, centroids);
+    }
+
+    fn best_split(
+        &self,
+        first: usize,
+        count: usize,
+        bounds: &[Aabb<T>],
+        centroids: &[Vector3<T>],
+    ) -> Option<(usize, T)> {
+        let slice = &self.indices[first..first + count];
+        let spread = Aabb::from_points(&slice.iter().map(|&i| centroids[i]).collect::<Vec<_>>())?;
+
+        let mut best: Option<(usize, T, T)> = None;
+        for axis in 0..3 {
+            let (low, high) = (spread.min.array()[axis], spread.max.array()[axis]);
+            if low == high {
+                continue;
+            }
+
+            let step = (high - low) / T::constant(BINS as f64);
+            let planes: Vec<T> = (1..BINS).map(|i| low + step * T::constant(i as f64)).collect();
+
+            let mut bins: [(Option<Aabb<T>>, usize); BINS] = [(None, 0); BINS];
+            slice.iter().for_each(|&i| {
+                let value = centroids[i].array()[axis];
+                let bin = planes.iter().take_while(|&&plane| value >= plane).count();
+                bins[bin].0 = Some(bins[bin].0.map_or(bounds[i], |acc| acc.union(&bounds[i])));
+                bins[bin].1 += 1;
+            });
+
+            let sweep = |range: &mut dyn Iterator<Item = usize>| {
+                let mut acc: (Option<Aabb<T>>, usize) = (None, 0);
+                range
+                    .map(|bin| {
+                        if let Some(aabb) = bins[bin].0 {
+                            acc.0 = Some(acc.0.map_or(aabb, |current| current.union(&aabb)));
+                        }
+                        acc.1 += bins[bin].1;
+                        acc
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let left = sweep(&mut (0..BINS - 1));
+            let mut right = sweep(&mut (1..BINS).rev());
+            right.reverse();
+
+            (0..BINS - 1).for_each(|plane| {
+                let cost = |(aabb, n): (Option<Aabb<T>>, usize)| {
+                    aabb.map_or(T::zero(), |aabb| aabb.surface_area() * T::constant(n as f64))
+                };
+                let total = cost(left[plane]) + cost(right[plane]);
+                if best.is_none_or(|(_, _, current)| total < current) {
+                    best = Some((axis, planes[plane], total));
+                }
+            });
+        }
+
+        let (axis, split, cost) = best?;
+        let area = slice.iter().skip(1).fold(bounds[slice[0]], |acc, &i| acc.union(&bounds[i])).surface_area();
+        let leaf_cost = area * T::constant(count as f64);
+
+        match count > MAX_LEAF || cost < leaf_cost {
+            | true => Some((axis, split)),
+            | false => None,
+        }
+    }
+
+    fn leaf_bounds(&self, first: usize, count: usize) -> Aabb<T> {
+        let slice = &self.indices[first..first + count];
+        let seed = self.primitives[slice[0]].bounds();
+
+        slice.iter().skip(1).fold(seed, |acc, &i| acc.union(&self.primitives[i].bounds()))
+    }
+
+    fn walk(
+        &self,
+        mut visit_node: impl FnMut(&Aabb<T>) -> bool,
+        mut visit_primitive: impl FnMut(usize) -> bool,
+    ) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = self.nodes[index];
+            if !visit_node(&node.bounds) {
+                continue;
+            }
+
+            match node.is_leaf() {
+                | true if self.leaf_primitives(&node).iter().any(|&i| visit_primitive(i)) => return true,
+                | true => continue,
+                | false => stack.extend([node.first + 1, node.first]),
+            }
+        }
+
+        false
+    }
+}
+
+impl<T, P> Bvh<T, P>
+where
+    T: FloatNumber<T>,
+    P: Bounded<T> + RayCast<T>,
+{
+    pub fn nearest_hit(&self, ray: &Ray<T>) -> Option<(usize, RayHit<T>)> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(usize, RayHit<T>)> = None;
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = self.nodes[index];
+            let Some((near, _)) = node.bounds.slab(ray) else {
+                continue;
+            };
+            if best.is_some_and(|(_, hit)| near > hit.distance) {
+                continue;
+            }
+
+            if node.is_leaf() {
+                self.leaf_primitives(&node).iter().for_each(|&i| {
+                    if let Some(hit) = self.primitives[i].raycast(ray)
+                        && best.is_none_or(|(_, current)| hit.distance < current.distance)
+                    {
+                        best = Some((i, hit));
+                    }
+                });
+                continue;
+            }
+
+            let (left, right) = (node.first, node.first + 1);
+            let near_left = self.nodes[left].bounds.slab(ray).map(|(t, _)| t);
+            let near_right = self.nodes[right].bounds.slab(ray).map(|(t, _)| t);
+            match (near_left, near_right) {
+                | (Some(a), Some(b)) if b < a => stack.extend([left, right]),
+                | _ => stack.extend([right, left]),
+            }
+        }
+
+        best
+    }
+
+    pub fn any_hit(&self, ray: &Ray<T>, max_distance: T) -> bool {
+        self.walk(
+            |bounds| bounds.slab(ray).is_some_and(|(near, _)| near <= max_distance),
+            |i| self.primitives[i].raycast(ray).is_some_and(|hit| hit.distance <= max_distance),
+        )
+    }
+}
+
+impl<T, P> Bvh<T, P>
+where
+    T: FloatNumber<T>,
+    P: Bounded<T> + ClosestPoint<T>,
+{
+    pub fn nearest(&self, point: Vector3<T>, k: usize) -> Vec<(usize, T)> {
+        let mut best: Vec<(usize, T)> = Vec::with_capacity(k + 1);
+        if self.nodes.is_empty() || k == 0 {
+            return best;
+        }
+
+        let squared = |bounds: &Aabb<T>| (bounds.closest_point(point) - point).squared_length();
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = self.nodes[index];
+            if best.len() == k && best.last().is_some_and(|&(_, worst)| squared(&node.bounds) > worst * worst) {
+                continue;
+            }
+
+            if node.is_leaf() {
+                self.leaf_primitives(&node).iter().for_each(|&i| {
+                    let distance = self.primitives[i].distance(point);
+                    let slot = best.iter().take_while(|&&(_, d)| d <= distance).count();
+                    if slot < k {
+                        best.insert(slot, (i, distance));
+                        best.truncate(k);
+                    }
+                });
+                continue;
+            }
+
+            let (left, right) = (node.first, node.first + 1);
+            match squared(&self.nodes[right].bounds) < squared(&self.nodes[left].bounds) {
+                | true => stack.extend([left, right]),
+                | false => stack.extend([right, left]),
+            }
+        }
+
+        best
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PRIMITIVES */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+impl<T> Bounded<T> for Aabb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn bounds(&self) -> Aabb<T> {
+        *self
+    }
+}
+
+impl<T> Bounded<T> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn bounds(&self) -> Aabb<T> {
+        Sphere::bounds(self)
+    }
+
+    fn centroid(&self) -> Vector3<T> {
+        self.center
+    }
+}
+
+impl<T> Bounded<T> for Triangle<T>
+where
+    T: FloatNumber<T>,
+{
+    fn bounds(&self) -> Aabb<T> {
+        Triangle::bounds(self)
+    }
+
+    fn centroid(&self) -> Vector3<T> {
+        Triangle::centroid(self)
+    }
+}
+
+impl<T> Bounded<T> for Obb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn bounds(&self) -> Aabb<T> {
+        Obb::bounds(self)
+    }
+
+    fn centroid(&self) -> Vector3<T> {
+        self.center
+    }
+}
+
+impl<T> Bounded<T> for Capsule<T>
+where
+    T: FloatNumber<T>,
+{
+    fn bounds(&self) -> Aabb<T> {
+        Capsule::bounds(self)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::random::Rng;
+    use crate::math::random::Xoshiro256;
+
+    fn scene(rng: &mut Xoshiro256, count: usize) -> Vec<Sphere<f64>> {
+        (0..count)
+            .map(|_| {
+                let center = Vector3::build(rng.uniform(-20., 20.), rng.uniform(-20., 20.), rng.uniform(-20., 20.));
+                Sphere::build(center, rng.uniform(0.1, 1.5))
+            })
+            .collect()
+    }
+
+    fn rays(rng: &mut Xoshiro256, count: usize) -> Vec<Ray<f64>> {
+        (0..count)
+            .map(|_| {
+                let origin = Vector3::build(rng.uniform(-25., 25.), rng.uniform(-25., 25.), rng.uniform(-25., 25.));
+                Ray::build(origin, rng.on_unit_sphere())
+            })
+            .collect()
+    }
+
+    fn brute_hit(primitives: &[Sphere<f64>], ray: &Ray<f64>) -> Option<(usize, f64)> {
+        primitives
+            .iter()
+            .enumerate()
+            .filter_map(|(i, sphere)| sphere.raycast(ray).map(|hit| (i, hit.distance)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    #[test]
+    fn build_invariants() {
+        let mut rng = Xoshiro256::build(1);
+        let bvh = Bvh::build(scene(&mut rng, 500));
+
+        let mut seen = vec![0; 500];
+        bvh.nodes().iter().for_each(|node| match node.is_leaf() {
+            | true => bvh.leaf_primitives(node).iter().for_each(|&i| {
+                seen[i] += 1;
+                let inner = bvh.primitives()[i].bounds();
+                assert!(node.bounds.contains(inner.min) && node.bounds.contains(inner.max));
+            }),
+            | false => [node.first, node.first + 1].iter().for_each(|&child| {
+                let inner = bvh.nodes()[child].bounds;
+                assert!(node.bounds.contains(inner.min) && node.bounds.contains(inner.max));
+            }),
+        });
+        assert!(seen.iter().all(|&count| count == 1));
+        assert!(bvh.nodes().len() > 100);
+    }
+
+    #[test]
+    fn ray_queries() {
+        let mut rng = Xoshiro256::build(2);
+        let primitives = scene(&mut rng, 400);
+        let bvh = Bvh::build(primitives.clone());
+
+        rays(&mut rng, 500).iter().for_each(|ray| {
+            let expected = brute_hit(&primitives, ray);
+            let found = bvh.nearest_hit(ray).map(|(i, hit)| (i, hit.distance));
+            assert!(expected == found);
+
+            let limit = 10.;
+            let blocked = primitives.iter().any(|sphere| sphere.raycast(ray).is_some_and(|hit| hit.distance <= limit));
+            assert!(bvh.any_hit(ray, limit) == blocked);
+        });
+    }
+
+    #[test]
+    fn overlap_and_nearest() {
+        let mut rng = Xoshiro256::build(3);
+        let primitives = scene(&mut rng, 300);
+        let bvh = Bvh::build(primitives.clone());
+
+        (0..50).for_each(|_| {
+            let corner = Vector3::build(rng.uniform(-20., 15.), rng.uniform(-20., 15.), rng.uniform(-20., 15.));
+            let query = Aabb::build(corner, corner + Vector3::splat(5.));
+            let mut found = bvh.overlapping(&query);
+            found.sort();
+            let expected: Vec<usize> =
+                (0..primitives.len()).filter(|&i| primitives[i].bounds().intersects(&query)).collect();
+            assert!(found == expected);
+
+            let point = Vector3::build(rng.uniform(-20., 20.), rng.uniform(-20., 20.), rng.uniform(-20., 20.));
+            let mut brute: Vec<f64> = primitives.iter().map(|sphere| sphere.distance(point)).collect();
+            brute.sort_by(f64::total_cmp);
+            let nearest: Vec<f64> = bvh.nearest(point, 5).iter().map(|&(_, d)| d).collect();
+            assert!(nearest == brute[..5]);
+        });
+    }
+
+    #[test]
+    fn refit_after_motion() {
+        let mut rng = Xoshiro256::build(4);
+        let mut bvh = Bvh::build(scene(&mut rng, 300));
+
+        let shift = Vector3::build(3., -2., 1.);
+        bvh.primitives_mut().iter_mut().enumerate().for_each(|(i, sphere)| {
+            sphere.center += shift * (i % 3) as f64;
+        });
+        bvh.refit();
+
+        let primitives = bvh.primitives().to_vec();
+        rays(&mut rng, 300).iter().for_each(|ray| {
+            assert!(brute_hit(&primitives, ray) == bvh.nearest_hit(ray).map(|(i, hit)| (i, hit.distance)));
+        });
+    }
+}
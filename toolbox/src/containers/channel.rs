@@ -0,0 +1,395 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SPSC RING */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+struct Ring<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+unsafe impl<T> Sync for Ring<T> where T: Send {}
+
+impl<T> Ring<T> {
+    fn slot(&self, position: usize) -> *mut MaybeUninit<T> {
+        self.slots[position % self.slots.len()].get()
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let (head, tail) = (*self.head.get_mut(), *self.tail.get_mut());
+        (0..tail.wrapping_sub(head)).for_each(|offset| {
+            // SAFETY: positions between head and tail hold values nobody has consumed
+            unsafe { (*self.slot(head.wrapping_add(offset))).assume_init_drop() };
+        });
+    }
+}
+
+pub struct Producer<T> {
+    ring: Arc<Ring<T>>,
+    tail: usize,
+    cached_head: usize,
+}
+
+pub struct Consumer<T> {
+    ring: Arc<Ring<T>>,
+    head: usize,
+    cached_tail: usize,
+}
+
+pub fn spsc<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let capacity = capacity.max(1);
+    let ring = Arc::new(Ring {
+        slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+    });
+
+    let producer = Producer { ring: Arc::clone(&ring), tail: 0, cached_head: 0 };
+    let consumer = Consumer { ring, head: 0, cached_tail: 0 };
+    (producer, consumer)
+}
+
+impl<T> Producer<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.slots.len()
+    }
+
+    pub fn free(&mut self) -> usize {
+        self.cached_head = self.ring.head.load(Ordering::Acquire);
+        self.capacity() - self.tail.wrapping_sub(self.cached_head)
+    }
+
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if self.tail.wrapping_sub(self.cached_head) == self.capacity() && self.free() == 0 {
+            return Err(value);
+        }
+
+        // SAFETY: the slot at tail is outside the consumer's readable range until tail is published
+        unsafe { (*self.ring.slot(self.tail)).write(value) };
+        self.tail = self.tail.wrapping_add(1);
+        self.ring.tail.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    pub fn push_batch(&mut self, values: &mut impl Iterator<Item = T>) -> usize {
+        let room = self.free();
+        let mut written = 0;
+        while written < room {
+            let Some(value) = values.next() else {
+                break;
+            };
+
+            // SAFETY: every slot in [tail, tail + room) is free and unpublished
+            unsafe { (*self.ring.slot(self.tail.wrapping_add(written))).write(value) };
+            written += 1;
+        }
+
+        self.tail = self.tail.wrapping_add(written);
+        self.ring.tail.store(self.tail, Ordering::Release);
+        written
+    }
+}
+
+impl<T> Consumer<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.slots.len()
+    }
+
+    pub fn len(&mut self) -> usize {
+        self.cached_tail = self.ring.tail.load(Ordering::Acquire);
+        self.cached_tail.wrapping_sub(self.head)
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn try_pop(&mut self) -> Option<T> {
+        if self.head == self.cached_tail && self.is_empty() {
+            return None;
+        }
+
+        // SAFETY: the slot at head was published by the producer's release store on tail
+        let value = unsafe { (*self.ring.slot(self.head)).assume_init_read() };
+        self.head = self.head.wrapping_add(1);
+        self.ring.head.store(self.head, Ordering::Release);
+        Some(value)
+    }
+
+    pub fn pop_batch(&mut self, out: &mut [T]) -> usize {
+        let count = self.len().min(out.len());
+        out.iter_mut().take(count).enumerate().for_each(|(i, target)| {
+            // SAFETY: every slot in [head, head + count) was published and is read exactly once
+            *target = unsafe { (*self.ring.slot(self.head.wrapping_add(i))).assume_init_read() };
+        });
+
+        self.head = self.head.wrapping_add(count);
+        self.ring.head.store(self.head, Ordering::Release);
+        count
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* MPSC QUEUE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+struct Cell<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+struct Queue<T> {
+    cells: Box<[Cell<T>]>,
+    mask: usize,
+    enqueue: AtomicUsize,
+    dequeue: AtomicUsize,
+}
+
+unsafe impl<T> Sync for Queue<T> where T: Send {}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        let mut position = *self.dequeue.get_mut();
+        loop {
+            let cell = &mut self.cells[position & self.mask];
+            if *cell.sequence.get_mut() != position.wrapping_add(1) {
+                break;
+            }
+
+            // SAFETY: a sequence one past the position marks a written, unread value
+            unsafe { cell.value.get_mut().assume_init_drop() };
+            position = position.wrapping_add(1);
+        }
+    }
+}
+
+pub struct Sender<T> {
+    queue: Arc<Queue<T>>,
+}
+
+pub struct Receiver<T> {
+    queue: Arc<Queue<T>>,
+}
+
+pub fn mpsc<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    let capacity = capacity.max(2).next_power_of_two();
+    let queue = Arc::new(Queue {
+        cells: (0..capacity)
+            .map(|i| Cell {
+                sequence: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect(),
+        mask: capacity - 1,
+        enqueue: AtomicUsize::new(0),
+        dequeue: AtomicUsize::new(0),
+    });
+
+    (Sender { queue: Arc::clone(&queue) }, Receiver { queue })
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self { queue: Arc::clone(&self.queue) }
+    }
+}
+
+impl<T> Sender<T> {
+    pub fn capacity(&self) -> usize {
+        self.queue.cells.len()
+    }
+
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        let queue = &self.queue;
+        let mut position = queue.enqueue.load(Ordering::Relaxed);
+        loop {
+            let cell = &queue.cells[position & queue.mask];
+            let sequence = cell.sequence.load(Ordering::Acquire);
+
+            match sequence.wrapping_sub(position) as isize {
+                | 0 => match queue.enqueue.compare_exchange_weak(
+                    position,
+                    position.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    | Ok(_) => {
+                        // SAFETY: winning the exchange grants exclusive access to this cell
+                        unsafe { (*cell.value.get()).write(value) };
+                        cell.sequence.store(position.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    | Err(current) => position = current,
+                },
+                | lag if lag < 0 => return Err(value),
+                | _ => position = queue.enqueue.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    pub fn push_batch(&self, values: &mut impl Iterator<Item = T>) -> Result<usize, (usize, T)> {
+        let mut written = 0;
+        for value in values.by_ref() {
+            self.try_push(value).map_err(|rejected| (written, rejected))?;
+            written += 1;
+        }
+        Ok(written)
+    }
+}
+
+impl<T> Receiver<T> {
+    pub fn capacity(&self) -> usize {
+        self.queue.cells.len()
+    }
+
+    pub fn try_pop(&mut self) -> Option<T> {
+        let queue = &self.queue;
+        let position = queue.dequeue.load(Ordering::Relaxed);
+        let cell = &queue.cells[position & queue.mask];
+        if cell.sequence.load(Ordering::Acquire) != position.wrapping_add(1) {
+            return None;
+        }
+
+        // SAFETY: the acquire load above observed the producer's release of this cell
+        let value = unsafe { (*cell.value.get()).assume_init_read() };
+        cell.sequence.store(position.wrapping_add(queue.mask + 1), Ordering::Release);
+        queue.dequeue.store(position.wrapping_add(1), Ordering::Relaxed);
+        Some(value)
+    }
+
+    pub fn pop_batch(&mut self, out: &mut [T]) -> usize {
+        let mut count = 0;
+        while count < out.len() {
+            let Some(value) = self.try_pop() else {
+                break;
+            };
+
+            out[count] = value;
+            count += 1;
+        }
+        count
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn spsc_ordering() {
+        let (mut producer, mut consumer) = spsc::<u64>(64);
+        const COUNT: u64 = 200_000;
+
+        let writer = thread::spawn(move || {
+            let mut next = 0;
+            while next < COUNT {
+                let mut batch = (next..COUNT.min(next + 7)).peekable();
+                next += producer.push_batch(&mut batch) as u64;
+                if batch.peek().is_some() {
+                    thread::yield_now();
+                }
+            }
+        });
+
+        let mut expected = 0;
+        let mut scratch = [0; 5];
+        while expected < COUNT {
+            match expected % 2 == 0 {
+                | true => {
+                    let count = consumer.pop_batch(&mut scratch);
+                    if count == 0 {
+                        thread::yield_now();
+                    }
+                    scratch[..count].iter().for_each(|&value| {
+                        assert!(value == expected);
+                        expected += 1;
+                    });
+                }
+                | false => match consumer.try_pop() {
+                    | Some(value) => {
+                        assert!(value == expected);
+                        expected += 1;
+                    }
+                    | None => thread::yield_now(),
+                },
+            }
+        }
+
+        writer.join().unwrap();
+        assert!(consumer.try_pop().is_none());
+    }
+
+    #[test]
+    fn mpsc_ordering() {
+        const PRODUCERS: usize = 4;
+        const COUNT: usize = 50_000;
+        let (sender, mut receiver) = mpsc::<(usize, usize)>(100);
+        assert!(sender.capacity() == 128);
+
+        let writers: Vec<_> = (0..PRODUCERS)
+            .map(|id| {
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    (0..COUNT).for_each(|seq| {
+                        let mut item = (id, seq);
+                        while let Err(rejected) = sender.try_push(item) {
+                            item = rejected;
+                            thread::yield_now();
+                        }
+                    });
+                })
+            })
+            .collect();
+
+        let mut next = [0; PRODUCERS];
+        let mut received = 0;
+        while received < PRODUCERS * COUNT {
+            match receiver.try_pop() {
+                | Some((id, seq)) => {
+                    assert!(next[id] == seq);
+                    next[id] += 1;
+                    received += 1;
+                }
+                | None => thread::yield_now(),
+            }
+        }
+
+        writers.into_iter().for_each(|writer| writer.join().unwrap());
+        assert!(receiver.try_pop().is_none() && next.iter().all(|&n| n == COUNT));
+    }
+
+    #[test]
+    fn bounded_and_dropping() {
+        let tracker = Arc::new(());
+        {
+            let (mut producer, mut consumer) = spsc(3);
+            (0..3).for_each(|_| assert!(producer.try_push(Arc::clone(&tracker)).is_ok()));
+            assert!(producer.try_push(Arc::clone(&tracker)).is_err() && producer.free() == 0);
+            drop(consumer.try_pop());
+            assert!(Arc::strong_count(&tracker) == 3 && consumer.len() == 2);
+
+            let (sender, mut receiver) = mpsc(2);
+            let mut batch = std::iter::repeat_with(|| Arc::clone(&tracker)).take(5);
+            let Err((written, rejected)) = sender.push_batch(&mut batch) else {
+                panic!("a full queue must hand back the rejected value");
+            };
+            assert!(written == 2 && batch.count() == 2 && Arc::strong_count(&tracker) == 6);
+            drop(rejected);
+            assert!(Arc::strong_count(&tracker) == 5);
+            drop(receiver.try_pop());
+            assert!(sender.push_batch(&mut std::iter::empty()) == Ok(0));
+            assert!(sender.try_push(Arc::clone(&tracker)).is_ok() && sender.try_push(Arc::clone(&tracker)).is_err());
+        }
+        assert!(Arc::strong_count(&tracker) == 1);
+    }
+}
@@ -1,44 +1,97 @@
-use core::slice;
-use std::ops::Index;
-use std::ops::IndexMut;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::iter::Chain;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::mem::MaybeUninit;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::ptr;
+use std::slice;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* CAPACITY ERROR */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T>(pub T);
+
+impl<T> CapacityError<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+impl<T> Error for CapacityError<T> where T: fmt::Debug {}
 
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////
 /* HEAPLESS VEC */
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////
 pub struct Vec<T, const N: usize> {
-    pub data: [Option<T>; N],
-    pub length: usize,
+    data: [MaybeUninit<T>; N],
+    length: usize,
 }
 
-impl<T, const N: usize> Vec<T, N>
-where
-    T: Copy,
-{
-    pub fn new() -> Self {
-        Self::default()
+impl<T, const N: usize> Vec<T, N> {
+    pub const fn new() -> Self {
+        Self { data: [const { MaybeUninit::uninit() }; N], length: 0 }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
     }
 
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.length
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
     }
 
-    pub fn clear(&mut self) {
-        self.data.fill(None);
-        self.length = 0;
+    pub const fn is_full(&self) -> bool {
+        self.length == N
+    }
+
+    pub const fn as_slice(&self) -> &[T] {
+        // SAFETY: the first length slots are initialized
+        unsafe { slice::from_raw_parts(self.data.as_ptr() as *const T, self.length) }
+    }
+
+    pub const fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first length slots are initialized
+        unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut T, self.length) }
     }
 
     pub fn push(&mut self, value: T) -> Option<()> {
-        if !self.len() < N {
-            return None;
+        self.try_push(value).ok()
+    }
+
+    pub fn try_push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError(value));
         }
 
-        self.data[self.len()] = Some(value);
+        self.data[self.length].write(value);
         self.length += 1;
-        Some(())
+        Ok(())
+    }
+
+    // stops at the first item that does not fit and hands it back, the rest of the iterator is left untouched
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), CapacityError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        iter.into_iter().try_for_each(|item| self.try_push(item))
     }
 
     pub fn pop(&mut self) -> Option<T> {
@@ -47,137 +100,958 @@ where
         }
 
         self.length -= 1;
-        let value = self.data[self.len()];
-        self.data[self.len()] = None;
-        value
+        // SAFETY: the slot was the last initialized entry and is no longer tracked
+        Some(unsafe { self.data[self.length].assume_init_read() })
+    }
+
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), CapacityError<T>> {
+        assert!(index <= self.length, "insertion index {index} out of bounds for length {}", self.length);
+        if self.is_full() {
+            return Err(CapacityError(value));
+        }
+
+        // SAFETY: there is room for one more element and the shifted range stays within capacity
+        unsafe {
+            let base = self.data.as_mut_ptr().add(index);
+            ptr::copy(base, base.add(1), self.length - index);
+            (*base).write(value);
+        }
+        self.length += 1;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.length, "removal index {index} out of bounds for length {}", self.length);
+
+        self.length -= 1;
+        // SAFETY: index was initialized and the tail is moved down over it
+        unsafe {
+            let base = self.data.as_mut_ptr().add(index);
+            let value = (*base).assume_init_read();
+            ptr::copy(base.add(1), base, self.length - index);
+            value
+        }
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.length, "removal index {index} out of bounds for length {}", self.length);
+
+        self.length -= 1;
+        self.data.swap(index, self.length);
+        // SAFETY: the removed element now sits in the untracked slot at length
+        unsafe { self.data[self.length].assume_init_read() }
+    }
+
+    pub fn truncate(&mut self, length: usize) {
+        while self.length > length {
+            self.length -= 1;
+            // SAFETY: the slot is initialized and no longer tracked
+            unsafe { self.data[self.length].assume_init_drop() };
+        }
     }
 
-    pub fn iter<'d>(&'d self) -> Iter<'d, T, N> {
-        Iter::new(self)
+    pub fn clear(&mut self) {
+        self.truncate(0);
     }
 
-    pub fn iter_mut<'d>(&'d mut self) -> IterMut<'d, T, N> {
-        IterMut::new(self)
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        let length = self.length;
+        // a panic in keep leaks the remaining elements instead of dropping them twice
+        self.length = 0;
+
+        let mut kept = 0;
+        (0..length).for_each(|i| {
+            // SAFETY: slot i is initialized and has not been moved yet
+            let retained = keep(unsafe { self.data[i].assume_init_ref() });
+            match retained {
+                | true => {
+                    self.data.swap(kept, i);
+                    kept += 1;
+                }
+                // SAFETY: the rejected element is dropped exactly once
+                | false => unsafe { self.data[i].assume_init_drop() },
+            }
+        });
+
+        self.length = kept;
     }
 }
 
-impl<T, const N: usize> Default for Vec<T, N>
-where
-    T: Copy,
-{
+impl<T, const N: usize> Default for Vec<T, N> {
     fn default() -> Self {
-        Self { data: [None; N], length: 0 }
+        Self::new()
     }
 }
 
-impl<T, const N: usize> Index<usize> for Vec<T, N> {
-    type Output = T;
+impl<T, const N: usize> Drop for Vec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
 
-    fn index(&self, index: usize) -> &Self::Output {
-        self.data[index].as_ref().unwrap()
+impl<T, const N: usize> Deref for Vec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
     }
 }
 
-impl<T, const N: usize> IndexMut<usize> for Vec<T, N> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        self.data[index].as_mut().unwrap()
+impl<T, const N: usize> DerefMut for Vec<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
     }
 }
 
-impl<T, const N: usize> FromIterator<T> for Vec<T, N>
+impl<T, const N: usize> Clone for Vec<T, N>
 where
-    T: Copy,
+    T: Clone,
 {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for Vec<T, N>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, U, const N: usize, const M: usize> PartialEq<Vec<U, M>> for Vec<T, N>
+where
+    T: PartialEq<U>,
+{
+    fn eq(&self, other: &Vec<U, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T, const N: usize> Eq for Vec<T, N> where T: Eq {}
+
+impl<T, const N: usize> Extend<T> for Vec<T, N> {
+    fn extend<A: IntoIterator<Item = T>>(&mut self, iter: A) {
+        if self.try_extend(iter).is_err() {
+            panic!("heapless vec capacity {N} exceeded");
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for Vec<T, N> {
     fn from_iter<A: IntoIterator<Item = T>>(iter: A) -> Self {
         let mut out = Self::new();
-        iter.into_iter().for_each(|item| {
-            out.push(item);
-        });
+        out.extend(iter);
         out
     }
 }
 
-pub struct Iter<'d, T, const N: usize> {
-    vec: &'d Vec<T, N>,
-    index: usize,
+impl<'d, T, const N: usize> IntoIterator for &'d Vec<T, N> {
+    type Item = &'d T;
+
+    type IntoIter = slice::Iter<'d, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
-impl<'d, T, const N: usize> Iter<'d, T, N> {
-    pub fn new(vec: &'d Vec<T, N>) -> Self {
-        Self { vec, index: 0 }
+impl<'d, T, const N: usize> IntoIterator for &'d mut Vec<T, N> {
+    type Item = &'d mut T;
+
+    type IntoIter = slice::IterMut<'d, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
-impl<'d, T, const N: usize> IntoIterator for &'d Vec<T, N>
-where
-    T: Copy,
-{
+impl<T, const N: usize> IntoIterator for Vec<T, N> {
     type Item = T;
 
-    type IntoIter = Iter<'d, T, N>;
+    type IntoIter = IntoIter<T, N>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter::new(self)
+        let vec = ManuallyDrop::new(self);
+        // SAFETY: the storage is moved out exactly once and the vec itself is never dropped
+        let data = unsafe { ptr::read(&vec.data) };
+        IntoIter { data, front: 0, back: vec.length }
     }
 }
 
-impl<'d, T, const N: usize> Iterator for Iter<'d, T, N>
-where
-    T: Copy,
-{
+pub struct IntoIter<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    front: usize,
+    back: usize,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index >= self.vec.len() {
+        if self.front == self.back {
             return None;
         }
 
-        let value = self.vec.data[self.index];
-        self.index += 1;
-        value
+        self.front += 1;
+        // SAFETY: slots between front and back are initialized and yielded once
+        Some(unsafe { self.data[self.front - 1].assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
     }
 }
 
-pub struct IterMut<'d, T, const N: usize> {
-    data: slice::IterMut<'d, Option<T>>,
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        // SAFETY: slots between front and back are initialized and yielded once
+        Some(unsafe { self.data[self.back].assume_init_read() })
+    }
 }
 
-impl<'d, T, const N: usize> IterMut<'d, T, N>
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        (self.front..self.back).for_each(|i| {
+            // SAFETY: slots between front and back were never yielded
+            unsafe { self.data[i].assume_init_drop() };
+        });
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* HEAPLESS STRING */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Default, Clone)]
+pub struct String<const N: usize> {
+    bytes: Vec<u8, N>,
+}
+
+impl<const N: usize> String<N> {
+    pub const fn new() -> Self {
+        Self { bytes: Vec::new() }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub const fn as_str(&self) -> &str {
+        // SAFETY: only whole strings and encoded chars are ever appended
+        unsafe { str::from_utf8_unchecked(self.bytes.as_slice()) }
+    }
+
+    pub const fn as_mut_str(&mut self) -> &mut str {
+        // SAFETY: only whole strings and encoded chars are ever appended
+        unsafe { str::from_utf8_unchecked_mut(self.bytes.as_mut_slice()) }
+    }
+
+    pub fn push_str<'s>(&mut self, text: &'s str) -> Result<(), CapacityError<&'s str>> {
+        if self.len() + text.len() > N {
+            return Err(CapacityError(text));
+        }
+
+        self.bytes.extend(text.bytes());
+        Ok(())
+    }
+
+    pub fn push(&mut self, c: char) -> Result<(), CapacityError<char>> {
+        let mut buffer = [0; 4];
+        self.push_str(c.encode_utf8(&mut buffer)).map_err(|_| CapacityError(c))
+    }
+
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        self.bytes.truncate(self.len() - c.len_utf8());
+        Some(c)
+    }
+
+    pub fn truncate(&mut self, length: usize) {
+        if length < self.len() {
+            assert!(self.as_str().is_char_boundary(length), "truncation inside a char boundary");
+            self.bytes.truncate(length);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+}
+
+impl<const N: usize> Deref for String<N> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> DerefMut for String<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_str()
+    }
+}
+
+impl<'s, const N: usize> TryFrom<&'s str> for String<N> {
+    type Error = CapacityError<&'s str>;
+
+    fn try_from(text: &'s str) -> Result<Self, Self::Error> {
+        let mut out = Self::new();
+        out.push_str(text)?;
+        Ok(out)
+    }
+}
+
+impl<const N: usize> fmt::Write for String<N> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.push_str(text).map_err(|_| fmt::Error)
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize> fmt::Display for String<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for String<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<String<M>> for String<N> {
+    fn eq(&self, other: &String<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<str> for String<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for String<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> Eq for String<N> {}
+
+impl<const N: usize> Hash for String<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* HEAPLESS DEQUE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Deque<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    head: usize,
+    length: usize,
+}
+
+impl<T, const N: usize> Deque<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            data: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            length: 0,
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.length == N
+    }
+
+    pub fn push_back(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError(value));
+        }
+
+        let slot = self.physical(self.length);
+        self.data[slot].write(value);
+        self.length += 1;
+        Ok(())
+    }
+
+    pub fn push_front(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError(value));
+        }
+
+        self.head = self.physical(N - 1);
+        self.data[self.head].write(value);
+        self.length += 1;
+        Ok(())
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let slot = self.head;
+        self.head = self.physical(1);
+        self.length -= 1;
+        // SAFETY: slot held the front entry and is no longer tracked
+        Some(unsafe { self.data[slot].assume_init_read() })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.length -= 1;
+        let slot = self.physical(self.length);
+        // SAFETY: slot held the back entry and is no longer tracked
+        Some(unsafe { self.data[slot].assume_init_read() })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.length {
+            return None;
+        }
+
+        // SAFETY: logical indices below length map onto initialized slots
+        Some(unsafe { self.data[self.physical(index)].assume_init_ref() })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.length {
+            return None;
+        }
+
+        let slot = self.physical(index);
+        // SAFETY: logical indices below length map onto initialized slots
+        Some(unsafe { self.data[slot].assume_init_mut() })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.length.wrapping_sub(1))
+    }
+
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let end = self.head + self.length;
+        let (first, second) = match end <= N {
+            | true => (self.head..end, 0..0),
+            | false => (self.head..N, 0..end - N),
+        };
+
+        // SAFETY: both ranges cover exactly the initialized slots
+        unsafe {
+            (
+                &*(&self.data[first] as *const [MaybeUninit<T>] as *const [T]),
+                &*(&self.data[second] as *const [MaybeUninit<T>] as *const [T]),
+            )
+        }
+    }
+
+    pub fn iter(&self) -> Chain<slice::Iter<'_, T>, slice::Iter<'_, T>> {
+        let (first, second) = self.as_slices();
+        first.iter().chain(second)
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_back().is_some() {}
+        self.head = 0;
+    }
+
+    fn physical(&self, offset: usize) -> usize {
+        match N {
+            | 0 => 0,
+            | _ => (self.head + offset) % N,
+        }
+    }
+}
+
+impl<T, const N: usize> Default for Deque<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for Deque<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Clone for Deque<T, N>
 where
-    T: Copy,
+    T: Clone,
 {
-    pub fn new(vec: &'d mut Vec<T, N>) -> Self {
-        Self { data: vec.data[..].iter_mut() }
+    fn clone(&self) -> Self {
+        let mut out = Self::new();
+        self.iter().for_each(|value| _ = out.push_back(value.clone()));
+        out
     }
 }
 
-impl<'d, T, const N: usize> IntoIterator for &'d mut Vec<T, N>
+impl<T, const N: usize> fmt::Debug for Deque<T, N>
 where
-    T: Copy,
+    T: fmt::Debug,
 {
-    type Item = &'d mut T;
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'d, T, const N: usize> IntoIterator for &'d Deque<T, N> {
+    type Item = &'d T;
 
-    type IntoIter = IterMut<'d, T, N>;
+    type IntoIter = Chain<slice::Iter<'d, T>, slice::Iter<'d, T>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        IterMut::new(self)
+        self.iter()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* HEAPLESS INDEX MAP */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        bytes.iter().for_each(|&byte| self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3));
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Position {
+    index: usize,
+    hash: usize,
+}
+
+#[derive(Clone)]
+pub struct FnvIndexMap<K, V, const N: usize> {
+    entries: Vec<(K, V), N>,
+    slots: [Option<Position>; N],
+}
+
+impl<K, V, const N: usize> FnvIndexMap<K, V, N> {
+    pub const fn new() -> Self {
+        Self { entries: Vec::new(), slots: [None; N] }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.slots = [None; N];
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&K, &mut V)> {
+        self.entries.iter_mut().map(|(key, value)| (&*key, value))
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    fn distance(from: usize, to: usize) -> usize {
+        (to + N - from) % N
     }
 }
 
-impl<'d, T, const N: usize> Iterator for IterMut<'d, T, N>
+impl<K, V, const N: usize> FnvIndexMap<K, V, N>
 where
-    T: Copy,
+    K: Hash + Eq,
 {
-    type Item = &'d mut T;
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), CapacityError<(K, V)>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        iter.into_iter().try_for_each(|(key, value)| self.insert(key, value).map(|_| ()))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        for slot in self.data.by_ref() {
-            if let Some(value) = slot.as_mut() {
-                return Some(value);
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, CapacityError<(K, V)>> {
+        let hash = Self::hash(&key);
+        match self.find(hash, &key) {
+            | Ok(slot) => {
+                let index = self.slots[slot].expect("found slot is occupied").index;
+                Ok(Some(std::mem::replace(&mut self.entries[index].1, value)))
+            }
+            | Err(Some(slot)) if !self.entries.is_full() => {
+                self.slots[slot] = Some(Position { index: self.entries.len(), hash });
+                _ = self.entries.try_push((key, value));
+                Ok(None)
+            }
+            | Err(_) => Err(CapacityError((key, value))),
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.index_of(key)?;
+        Some(&self.entries[index].1)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.index_of(key)?;
+        Some(&mut self.entries[index].1)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index_of(key).is_some()
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.find(Self::hash(key), key).ok()?;
+        let removed = self.slots[slot].take()?;
+        self.close_gap(slot);
+
+        let (_, value) = self.entries.swap_remove(removed.index);
+        if removed.index < self.entries.len() {
+            let moved = self.entries.len();
+            let start = Self::hash(&self.entries[removed.index].0) % N;
+            let slot = (0..N)
+                .map(|step| (start + step) % N)
+                .find(|&slot| self.slots[slot].is_some_and(|position| position.index == moved));
+            if let Some(position) = slot.and_then(|slot| self.slots[slot].as_mut()) {
+                position.index = removed.index;
+            }
+        }
+        Some(value)
+    }
+
+    fn hash<Q>(key: &Q) -> usize
+    where
+        Q: Hash + ?Sized,
+    {
+        let mut hasher = FnvHasher::default();
+        key.hash(&mut hasher);
+        hasher.finish() as usize
+    }
+
+    fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.find(Self::hash(key), key).ok()?;
+        self.slots[slot].map(|position| position.index)
+    }
+
+    fn find<Q>(&self, hash: usize, key: &Q) -> Result<usize, Option<usize>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        for step in 0..N {
+            let slot = (hash % N + step) % N;
+            match self.slots[slot] {
+                | None => return Err(Some(slot)),
+                | Some(position) if position.hash == hash && self.entries[position.index].0.borrow() == key => {
+                    return Ok(slot);
+                }
+                | Some(_) => {}
+            }
+        }
+        Err(None)
+    }
+
+    fn close_gap(&mut self, mut hole: usize) {
+        let mut slot = hole;
+        for _ in 1..N {
+            slot = (slot + 1) % N;
+            let Some(position) = self.slots[slot] else {
+                break;
+            };
+
+            if Self::distance(position.hash % N, slot) >= Self::distance(hole, slot) {
+                self.slots[hole] = self.slots[slot].take();
+                hole = slot;
+            }
+        }
+    }
+}
+
+impl<K, V, const N: usize> Default for FnvIndexMap<K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const N: usize> fmt::Debug for FnvIndexMap<K, V, N>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, const N: usize> Extend<(K, V)> for FnvIndexMap<K, V, N>
+where
+    K: Hash + Eq,
+{
+    fn extend<A: IntoIterator<Item = (K, V)>>(&mut self, iter: A) {
+        if self.try_extend(iter).is_err() {
+            panic!("heapless index map capacity {N} exceeded");
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* HEAPLESS BINARY HEAP */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub trait Kind {
+    fn ordering() -> Ordering;
+}
+
+pub struct Max;
+
+pub struct Min;
+
+impl Kind for Max {
+    fn ordering() -> Ordering {
+        Ordering::Greater
+    }
+}
+
+impl Kind for Min {
+    fn ordering() -> Ordering {
+        Ordering::Less
+    }
+}
+
+pub struct BinaryHeap<T, const N: usize, K = Max> {
+    data: Vec<T, N>,
+    kind: PhantomData<K>,
+}
+
+impl<T, const N: usize, K> BinaryHeap<T, N, K> {
+    pub const fn new() -> Self {
+        Self { data: Vec::new(), kind: PhantomData }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    pub fn into_vec(self) -> Vec<T, N> {
+        self.data
+    }
+}
+
+impl<T, const N: usize, K> BinaryHeap<T, N, K>
+where
+    T: Ord,
+    K: Kind,
+{
+    pub fn push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        self.data.try_push(value)?;
+        self.sift_up(self.data.len() - 1);
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.data.len().checked_sub(1)?;
+        self.data.swap(0, last);
+        let top = self.data.pop();
+        self.sift_down(0);
+        top
+    }
+
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), CapacityError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        iter.into_iter().try_for_each(|value| self.push(value))
+    }
+
+    // like std this is the reverse of pop order, so max heaps come out ascending and min heaps descending
+    pub fn into_sorted_vec(mut self) -> Vec<T, N> {
+        let mut out = Vec::new();
+        while let Some(value) = self.pop() {
+            _ = out.try_push(value);
+        }
+        out.reverse();
+        out
+    }
+
+    fn outranks(&self, a: usize, b: usize) -> bool {
+        self.data[a].cmp(&self.data[b]) == K::ordering()
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if !self.outranks(index, parent) {
+                break;
+            }
+
+            self.data.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let (left, right) = (2 * index + 1, 2 * index + 2);
+            if left >= self.data.len() {
+                break;
+            }
+
+            let child = match right < self.data.len() && self.outranks(right, left) {
+                | true => right,
+                | false => left,
+            };
+            if !self.outranks(child, index) {
+                break;
             }
+
+            self.data.swap(index, child);
+            index = child;
         }
+    }
+}
+
+impl<T, const N: usize, K> Default for BinaryHeap<T, N, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, K> Clone for BinaryHeap<T, N, K>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self { data: self.data.clone(), kind: PhantomData }
+    }
+}
 
-        None
+impl<T, const N: usize, K> fmt::Debug for BinaryHeap<T, N, K>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize, K> Extend<T> for BinaryHeap<T, N, K>
+where
+    T: Ord,
+    K: Kind,
+{
+    fn extend<A: IntoIterator<Item = T>>(&mut self, iter: A) {
+        if self.try_extend(iter).is_err() {
+            panic!("heapless binary heap capacity {N} exceeded");
+        }
     }
 }
 
@@ -187,6 +1061,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fmt::Write;
+    use std::rc::Rc;
+    use std::string::String;
 
     #[test]
     fn vec_iter() {
@@ -229,4 +1106,128 @@ mod tests {
         vec[0] = 100;
         assert!(vec[0] == 100);
     }
+
+    #[test]
+    fn vec_editing() {
+        const EMPTY: Vec<String, 4> = Vec::new();
+        assert!(EMPTY.is_empty() && EMPTY.capacity() == 4);
+
+        let mut vec: Vec<String, 4> = ["a", "b", "c"].map(String::from).into_iter().collect();
+        assert!(vec.insert(1, "x".into()).is_ok() && vec.is_full());
+        assert!(vec.try_push("y".into()) == Err(CapacityError("y".into())) && vec.insert(0, "z".into()).is_err());
+        assert!(vec == ["a", "x", "b", "c"].map(String::from).into_iter().collect::<Vec<_, 8>>());
+
+        assert!(vec.remove(0) == "a" && vec.swap_remove(0) == "x" && vec.as_slice() == ["c", "b"]);
+        assert!(vec.try_extend(["d", "e", "f"].map(String::from)) == Err(CapacityError("f".into())));
+        assert!(vec.len() == 4 && format!("{:?}", vec) == r#"["c", "b", "d", "e"]"#);
+
+        vec.retain(|value| value != "b");
+        vec.truncate(2);
+        assert!(vec.clone() == vec && vec.as_slice() == ["c", "d"] && vec.last().unwrap() == "d");
+    }
+
+    #[test]
+    fn vec_drops() {
+        let tracker = Rc::new(());
+        {
+            let mut vec = Vec::<_, 6>::new();
+            let rejected = vec.try_extend(std::iter::repeat_with(|| Rc::clone(&tracker)).take(10));
+            assert!(Rc::strong_count(&tracker) == 8);
+            drop(rejected.unwrap_err().into_inner());
+            assert!(Rc::strong_count(&tracker) == 7 && vec.is_full());
+
+            let mut flip = false;
+            vec.retain(|_| {
+                flip = !flip;
+                flip
+            });
+            drop(vec.remove(0));
+            assert!(Rc::strong_count(&tracker) == 3);
+
+            let copy = vec.clone();
+            let mut iter = copy.into_iter();
+            drop(iter.next());
+            assert!(Rc::strong_count(&tracker) == 4);
+        }
+        assert!(Rc::strong_count(&tracker) == 1);
+
+        let overflow = std::panic::catch_unwind(|| (0..4).collect::<Vec<i32, 3>>());
+        assert!(overflow.is_err() && (0..3).collect::<Vec<i32, 3>>().as_slice() == [0, 1, 2]);
+    }
+
+    #[test]
+    fn string_write() {
+        let mut text = super::String::<8>::new();
+        let (count, unit) = (12, "ab");
+        assert!(write!(text, "{count}-{unit}").is_ok() && text == "12-ab");
+        assert!(text.push_str("long") == Err(CapacityError("long")) && text.push('é').is_ok());
+        assert!(write!(text, "!!").is_err() && text.len() == 7 && text.pop() == Some('é'));
+
+        let copy = super::String::<16>::try_from("12-ab").unwrap();
+        assert!(copy == text && format!("{:?}", copy) == "\"12-ab\"" && copy.starts_with("12"));
+        assert!(super::String::<2>::try_from("abc").is_err());
+    }
+
+    #[test]
+    fn deque_ring() {
+        let mut deque = Deque::<String, 3>::new();
+        assert!(deque.push_back("b".into()).is_ok() && deque.push_front("a".into()).is_ok());
+        assert!(deque.push_back("c".into()).is_ok() && deque.push_front("z".into()).is_err());
+        assert!(deque.as_slices() == (&["a".to_string()][..], &["b".to_string(), "c".to_string()][..]));
+
+        assert!(deque.pop_front().unwrap() == "a" && deque.push_back("d".into()).is_ok());
+        assert!(deque.iter().eq(["b", "c", "d"].iter()) && deque.back().unwrap() == "d");
+        assert!(format!("{:?}", deque.clone()) == r#"["b", "c", "d"]"#);
+        assert!(deque.pop_back().unwrap() == "d" && deque.get(1).unwrap() == "c" && deque.get(2).is_none());
+    }
+
+    #[test]
+    fn index_map_probing() {
+        let mut map = FnvIndexMap::<u32, u32, 16>::new();
+        let mut reference = std::collections::HashMap::new();
+        let mut state = 7u32;
+        for _ in 0..400 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let key = (state >> 16) % 24;
+            match state % 3 {
+                | 0 => assert!(map.remove(&key) == reference.remove(&key)),
+                | _ => match map.insert(key, state) {
+                    | Ok(previous) => assert!(previous == reference.insert(key, state)),
+                    | Err(_) => assert!(map.len() == 16 && !reference.contains_key(&key)),
+                },
+            }
+            assert!(map.len() == reference.len());
+            assert!(reference.iter().all(|(key, value)| map.get(key) == Some(value)));
+        }
+
+        let mut names = FnvIndexMap::<std::string::String, u8, 4>::new();
+        names.extend([("one".into(), 1), ("two".into(), 2), ("three".into(), 3)]);
+        assert!(names.get("two") == Some(&2) && names.remove("one") == Some(1) && !names.contains_key("one"));
+        assert!(names.keys().map(|key| key.as_str()).collect::<std::vec::Vec<_>>() == ["three", "two"]);
+        let rejected = names.try_extend([
+            ("two".into(), 2),
+            ("four".into(), 4),
+            ("five".into(), 5),
+            ("six".into(), 6),
+        ]);
+        assert!(rejected.is_err_and(|error| error.into_inner() == ("six".into(), 6)) && names.len() == 4);
+    }
+
+    #[test]
+    fn heap_ordering() {
+        let mut max = BinaryHeap::<i32, 8>::new();
+        let mut min = BinaryHeap::<i32, 8, Min>::new();
+        let values = [5, 1, 8, 3, 9, 2, 7, 4];
+        max.extend(values);
+        min.extend(values);
+        assert!(max.push(10) == Err(CapacityError(10)) && max.peek() == Some(&9) && min.peek() == Some(&1));
+
+        assert!(max.pop() == Some(9) && min.pop() == Some(1));
+        assert!(max.try_extend([6, 11]) == Err(CapacityError(11)) && max.peek() == Some(&8));
+        assert!(max.into_sorted_vec().as_slice() == [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(min.into_sorted_vec().as_slice() == [9, 8, 7, 5, 4, 3, 2]);
+
+        let overflow = std::panic::catch_unwind(|| BinaryHeap::<i32, 2>::new().extend([1, 2, 3]));
+        assert!(overflow.is_err());
+    }
 }
@@ -1,3 +1,7 @@
 pub mod heapless;
+pub mod arena;
 pub mod buffer;
+pub mod bvh;
+pub mod channel;
 pub mod rolling_history;
+pub mod spatial;
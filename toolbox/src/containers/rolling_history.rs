@@ -1,42 +1,353 @@
-use std::collections::LinkedList;
-use std::ops::Deref;
-use std::ops::DerefMut;
+use std::fmt;
+use std::iter::Chain;
+use std::mem::MaybeUninit;
+use std::ops::Index;
+use std::ops::IndexMut;
+use std::slice;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    #[default]
+    Overwrite,
+    Reject,
+}
+
+pub type Iter<'d, T> = Chain<slice::Iter<'d, T>, slice::Iter<'d, T>>;
+pub type IterMut<'d, T> = Chain<slice::IterMut<'d, T>, slice::IterMut<'d, T>>;
 
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////
 /* ROLLING HISTORY */
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////
 pub struct RollingHistory<T> {
-    pub log: LinkedList<T>,
-    pub len: usize,
+    data: Box<[MaybeUninit<T>]>,
+    head: usize,
+    length: usize,
+    pub mode: Overflow,
 }
 
 impl<T> RollingHistory<T> {
-    pub fn build(length: usize) -> Self {
-        Self { log: LinkedList::new(), len: length }
+    pub fn build(capacity: usize) -> Self {
+        Self::with_mode(capacity, Overflow::Overwrite)
+    }
+
+    pub fn with_mode(capacity: usize, mode: Overflow) -> Self {
+        let data = (0..capacity).map(|_| MaybeUninit::uninit()).collect();
+        Self { data, head: 0, length: 0, mode }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.length == self.capacity()
     }
 
     pub fn push(&mut self, state: T) -> Option<()> {
-        if self.log.len() > self.len {
-            self.log.pop_front();
-            self.log.push_back(state);
+        match self.push_back(state) {
+            | Ok(None) => Some(()),
+            | _ => None,
+        }
+    }
+
+    pub fn push_back(&mut self, value: T) -> Result<Option<T>, T> {
+        if !self.is_full() {
+            let slot = self.physical(self.length);
+            self.data[slot].write(value);
+            self.length += 1;
+            return Ok(None);
+        }
+
+        match (self.mode, self.capacity()) {
+            | (Overflow::Reject, _) => Err(value),
+            | (Overflow::Overwrite, 0) => Ok(Some(value)),
+            | (Overflow::Overwrite, _) => {
+                let evicted = std::mem::replace(&mut self.data[self.head], MaybeUninit::new(value));
+                self.head = self.physical(1);
+                // SAFETY: the history is full so the slot at head was initialized
+                Ok(Some(unsafe { evicted.assume_init() }))
+            }
+        }
+    }
+
+    pub fn push_front(&mut self, value: T) -> Result<Option<T>, T> {
+        if !self.is_full() {
+            self.head = self.physical(self.capacity() - 1);
+            self.data[self.head].write(value);
+            self.length += 1;
+            return Ok(None);
+        }
+
+        match (self.mode, self.capacity()) {
+            | (Overflow::Reject, _) => Err(value),
+            | (Overflow::Overwrite, 0) => Ok(Some(value)),
+            | (Overflow::Overwrite, capacity) => {
+                self.head = self.physical(capacity - 1);
+                let evicted = std::mem::replace(&mut self.data[self.head], MaybeUninit::new(value));
+                // SAFETY: the history is full so every slot, including the new head, was initialized
+                Ok(Some(unsafe { evicted.assume_init() }))
+            }
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
             return None;
         }
 
-        self.log.push_back(state);
-        Some(())
+        let slot = self.head;
+        self.head = self.physical(1);
+        self.length -= 1;
+        // SAFETY: slot was the oldest live entry and is no longer tracked
+        Some(unsafe { self.data[slot].assume_init_read() })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.length -= 1;
+        let slot = self.physical(self.length);
+        // SAFETY: slot was the newest live entry and is no longer tracked
+        Some(unsafe { self.data[slot].assume_init_read() })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.oldest(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.newest(0)
+    }
+
+    pub fn oldest(&self, index: usize) -> Option<&T> {
+        if index >= self.length {
+            return None;
+        }
+
+        // SAFETY: logical indices below length map onto initialized slots
+        Some(unsafe { self.data[self.physical(index)].assume_init_ref() })
+    }
+
+    pub fn oldest_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.length {
+            return None;
+        }
+
+        let slot = self.physical(index);
+        // SAFETY: logical indices below length map onto initialized slots
+        Some(unsafe { self.data[slot].assume_init_mut() })
+    }
+
+    pub fn newest(&self, index: usize) -> Option<&T> {
+        match index < self.length {
+            | true => self.oldest(self.length - 1 - index),
+            | false => None,
+        }
+    }
+
+    pub fn newest_mut(&mut self, index: usize) -> Option<&mut T> {
+        match index < self.length {
+            | true => self.oldest_mut(self.length - 1 - index),
+            | false => None,
+        }
+    }
+
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (first, second) = self.ranges();
+        // SAFETY: both ranges cover exactly the initialized slots
+        unsafe {
+            (
+                &*(&self.data[first] as *const [MaybeUninit<T>] as *const [T]),
+                &*(&self.data[second] as *const [MaybeUninit<T>] as *const [T]),
+            )
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (first, second) = self.ranges();
+        let (lower, upper) = self.data.split_at_mut(first.start);
+        let (upper, lower) = (&mut upper[..first.len()], &mut lower[second]);
+        // SAFETY: both ranges cover exactly the initialized slots and never overlap
+        unsafe {
+            (&mut *(upper as *mut [MaybeUninit<T>] as *mut [T]), &mut *(lower as *mut [MaybeUninit<T>] as *mut [T]))
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (first, second) = self.as_slices();
+        first.iter().chain(second)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (first, second) = self.as_mut_slices();
+        first.iter_mut().chain(second)
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_back().is_some() {}
+        self.head = 0;
+    }
+
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|item| item == value)
+    }
+
+    // kept for callers written against the old LinkedList backed history
+    #[deprecated(note = "the history is no longer a LinkedList, use iter() instead")]
+    pub fn log(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    fn physical(&self, offset: usize) -> usize {
+        match self.capacity() {
+            | 0 => 0,
+            | capacity => (self.head + offset) % capacity,
+        }
+    }
+
+    fn ranges(&self) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let end = self.head + self.length;
+        match end <= self.capacity() {
+            | true => (self.head..end, 0..0),
+            | false => (self.head..self.capacity(), 0..end - self.capacity()),
+        }
+    }
+}
+
+impl<T> Drop for RollingHistory<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Clone for RollingHistory<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut out = Self::with_mode(self.capacity(), self.mode);
+        self.iter().for_each(|value| _ = out.push_back(value.clone()));
+        out
+    }
+}
+
+impl<T> fmt::Debug for RollingHistory<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
     }
 }
 
-impl<T> Deref for RollingHistory<T> {
-    type Target = LinkedList<T>;
+impl<T> Index<usize> for RollingHistory<T> {
+    type Output = T;
 
-    fn deref(&self) -> &Self::Target {
-        &self.log
+    fn index(&self, index: usize) -> &Self::Output {
+        self.oldest(index).expect("rolling history index out of bounds")
     }
 }
 
-impl<T> DerefMut for RollingHistory<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.log
+impl<T> IndexMut<usize> for RollingHistory<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.oldest_mut(index).expect("rolling history index out of bounds")
+    }
+}
+
+impl<'d, T> IntoIterator for &'d RollingHistory<T> {
+    type Item = &'d T;
+    type IntoIter = Iter<'d, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'d, T> IntoIterator for &'d mut RollingHistory<T> {
+    type Item = &'d mut T;
+    type IntoIter = IterMut<'d, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> Extend<T> for RollingHistory<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| _ = self.push_back(value));
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn exact_capacity() {
+        let mut history = RollingHistory::build(3);
+        assert!(history.push(1) == Some(()) && history.push(2) == Some(()) && history.push(3) == Some(()));
+        assert!(history.push(4).is_none() && history.len() == 3);
+        assert!(history.iter().copied().collect::<Vec<_>>() == vec![2, 3, 4]);
+        assert!(history.front() == Some(&2) && history.back() == Some(&4));
+        assert!(history.newest(1) == Some(&3) && history.oldest(1) == Some(&3) && history[2] == 4);
+
+        assert!(history.push_back(5) == Ok(Some(2)));
+        assert!(history.as_slices() == (&[3][..], &[4, 5][..]));
+        assert!(history.iter().rev().copied().collect::<Vec<_>>() == vec![5, 4, 3]);
+
+        assert!(history.push_front(9) == Ok(Some(5)));
+        assert!(history.iter().copied().collect::<Vec<_>>() == vec![9, 3, 4]);
+        assert!(history.pop_back() == Some(4) && history.pop_front() == Some(9) && history.len() == 1);
+
+        let mut rejecting = RollingHistory::with_mode(2, Overflow::Reject);
+        rejecting.extend([1, 2]);
+        assert!(rejecting.push_back(3) == Err(3) && rejecting.push_front(0) == Err(0));
+        rejecting.iter_mut().for_each(|value| *value *= 10);
+        assert!(format!("{:?}", rejecting) == "[10, 20]");
+
+        let mut empty = RollingHistory::build(0);
+        assert!(empty.push_back(1) == Ok(Some(1)) && empty.is_empty() && empty.back().is_none());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn legacy_api() {
+        let mut history: RollingHistory<u32> = RollingHistory::build(2);
+        let pushed: Vec<Option<()>> = (1..=4).map(|value| history.push(value)).collect();
+        assert!(pushed == vec![Some(()), Some(()), None, None]);
+        assert!(history.len() == 2 && history.iter().copied().collect::<Vec<_>>() == vec![3, 4]);
+        assert!(history.log().eq(history.iter()) && history.contains(&4) && !history.contains(&1));
+    }
+
+    #[test]
+    fn drops_live_entries() {
+        let tracker = Rc::new(());
+        {
+            let mut history = RollingHistory::build(4);
+            (0..10).for_each(|_| _ = history.push_back(Rc::clone(&tracker)));
+            assert!(Rc::strong_count(&tracker) == 5);
+
+            history.pop_front();
+            let copy = history.clone();
+            assert!(Rc::strong_count(&tracker) == 7 && copy.len() == 3);
+        }
+        assert!(Rc::strong_count(&tracker) == 1);
     }
 }
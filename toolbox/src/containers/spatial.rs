@@ -0,0 +1,711 @@
+use std::collections::HashMap;
+
+use super::arena::Arena;
+use super::arena::Handle;
+use super::arena::SecondaryMap;
+use crate::math::traits::FloatNumber;
+use crate::math::vector::Vector2;
+use crate::math::vector::Vector3;
+
+pub trait SpatialPoint<T>
+where
+    Self: Copy + PartialEq,
+    T: FloatNumber<T>,
+{
+    const DIM: usize;
+
+    fn coord(&self, axis: usize) -> T;
+
+    fn from_fn(f: impl Fn(usize) -> T) -> Self;
+
+    fn squared_distance(&self, other: &Self) -> T {
+        (0..Self::DIM).fold(T::zero(), |sum, axis| {
+            let delta = self.coord(axis) - other.coord(axis);
+            sum + delta * delta
+        })
+    }
+}
+
+impl<T> SpatialPoint<T> for Vector2<T>
+where
+    T: FloatNumber<T>,
+{
+    const DIM: usize = 2;
+
+    fn coord(&self, axis: usize) -> T {
+        self.array()[axis]
+    }
+
+    fn from_fn(f: impl Fn(usize) -> T) -> Self {
+        Vector2::build(f(0), f(1))
+    }
+}
+
+impl<T> SpatialPoint<T> for Vector3<T>
+where
+    T: FloatNumber<T>,
+{
+    const DIM: usize = 3;
+
+    fn coord(&self, axis: usize) -> T {
+        self.array()[axis]
+    }
+
+    fn from_fn(f: impl Fn(usize) -> T) -> Self {
+        Vector3::build(f(0), f(1), f(2))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SPATIAL HASH GRID */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+type Cell = [i64; 3];
+
+#[derive(Debug, Clone)]
+pub struct HashGrid<T, P, V> {
+    pub cell_size: T,
+    cells: HashMap<Cell, Vec<Handle<V>>>,
+    values: Arena<V>,
+    positions: SecondaryMap<V, P>,
+}
+
+impl<T, P, V> HashGrid<T, P, V>
+where
+    T: FloatNumber<T>,
+    P: SpatialPoint<T>,
+{
+    pub fn build(cell_size: T) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            values: Arena::new(),
+            positions: SecondaryMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, handle: Handle<V>) -> Option<&V> {
+        self.values.get(handle)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<V>) -> Option<&mut V> {
+        self.values.get_mut(handle)
+    }
+
+    pub fn position(&self, handle: Handle<V>) -> Option<P> {
+        self.positions.get(handle).copied()
+    }
+
+    pub fn cell(&self, position: P) -> Cell {
+        let mut cell = [0; 3];
+        (0..P::DIM).for_each(|axis| cell[axis] = (position.coord(axis) / self.cell_size).floor().to_f64() as i64);
+        cell
+    }
+
+    pub fn insert(&mut self, position: P, value: V) -> Handle<V> {
+        let handle = self.values.insert(value);
+        _ = self.positions.insert(handle, position);
+        self.cells.entry(self.cell(position)).or_default().push(handle);
+        handle
+    }
+
+    pub fn remove(&mut self, handle: Handle<V>) -> Option<V> {
+        let value = self.values.remove(handle)?;
+        if let Some(position) = self.positions.remove(handle) {
+            self.unlink(self.cell(position), handle);
+        }
+        Some(value)
+    }
+
+    pub fn update(&mut self, handle: Handle<V>, position: P) -> bool {
+        let Some(&previous) = self.positions.get(handle) else {
+            return false;
+        };
+
+        let (from, to) = (self.cell(previous), self.cell(position));
+        if from != to {
+            self.unlink(from, handle);
+            self.cells.entry(to).or_default().push(handle);
+        }
+        self.positions[handle] = position;
+        true
+    }
+
+    pub fn cell_items(&self, cell: Cell) -> &[Handle<V>] {
+        self.cells.get(&cell).map_or(&[], |items| items.as_slice())
+    }
+
+    pub fn query_radius(&self, center: P, radius: T) -> Vec<Handle<V>> {
+        let reach = P::from_fn(|_| radius);
+        let low = self.cell(P::from_fn(|axis| center.coord(axis) - reach.coord(axis)));
+        let high = self.cell(P::from_fn(|axis| center.coord(axis) + reach.coord(axis)));
+        let limit = radius * radius;
+
+        let mut out = Vec::new();
+        (low[0]..=high[0]).for_each(|x| {
+            (low[1]..=high[1]).for_each(|y| {
+                (low[2]..=high[2]).for_each(|z| {
+                    out.extend(self.cell_items([x, y, z]).iter().filter(|&&handle| {
+                        self.positions.get(handle).is_some_and(|position| position.squared_distance(&center) <= limit)
+                    }));
+                });
+            });
+        });
+        out
+    }
+
+    fn unlink(&mut self, cell: Cell, handle: Handle<V>) {
+        if let Some(items) = self.cells.get_mut(&cell) {
+            items.retain(|&item| item != handle);
+            if items.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* LOOSE TREE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub type Quadtree<T, V> = LooseTree<T, Vector2<T>, V>;
+pub type Octree<T, V> = LooseTree<T, Vector3<T>, V>;
+
+#[derive(Debug, Clone)]
+struct TreeNode<P, T, V> {
+    center: P,
+    half: T,
+    children: Option<usize>,
+    items: Vec<Handle<V>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TreeEntry<P, T> {
+    position: P,
+    radius: T,
+    node: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct LooseTree<T, P, V> {
+    pub max_depth: usize,
+    nodes: Vec<TreeNode<P, T, V>>,
+    values: Arena<V>,
+    entries: SecondaryMap<V, TreeEntry<P, T>>,
+}
+
+impl<T, P, V> LooseTree<T, P, V>
+where
+    T: FloatNumber<T>,
+    P: SpatialPoint<T>,
+{
+    pub fn build(center: P, half_size: T, max_depth: usize) -> Self {
+        let root = TreeNode { center, half: half_size, children: None, items: Vec::new() };
+        Self {
+            max_depth,
+            nodes: vec![root],
+            values: Arena::new(),
+            entries: SecondaryMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, handle: Handle<V>) -> Option<&V> {
+        self.values.get(handle)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<V>) -> Option<&mut V> {
+        self.values.get_mut(handle)
+    }
+
+    pub fn position(&self, handle: Handle<V>) -> Option<P> {
+        self.entries.get(handle).map(|entry| entry.position)
+    }
+
+    pub fn insert(&mut self, position: P, radius: T, value: V) -> Handle<V> {
+        let node = self.locate(position, radius);
+        let handle = self.values.insert(value);
+        _ = self.entries.insert(handle, TreeEntry { position, radius, node });
+        self.nodes[node].items.push(handle);
+        handle
+    }
+
+    pub fn remove(&mut self, handle: Handle<V>) -> Option<V> {
+        let value = self.values.remove(handle)?;
+        if let Some(entry) = self.entries.remove(handle) {
+            self.nodes[entry.node].items.retain(|&item| item != handle);
+        }
+        Some(value)
+    }
+
+    pub fn update(&mut self, handle: Handle<V>, position: P, radius: T) -> bool {
+        let Some(previous) = self.entries.get(handle).map(|entry| entry.node) else {
+            return false;
+        };
+
+        let node = self.locate(position, radius);
+        if node != previous {
+            self.nodes[previous].items.retain(|&item| item != handle);
+            self.nodes[node].items.push(handle);
+        }
+        self.entries[handle] = TreeEntry { position, radius, node };
+        true
+    }
+
+    pub fn query_radius(&self, center: P, radius: T) -> Vec<Handle<V>> {
+        self.query(
+            |node_center, reach| {
+                let squared = (0..P::DIM).fold(T::zero(), |sum, axis| {
+                    let offset = (center.coord(axis) - node_center.coord(axis)).abs() - reach;
+                    match offset > T::zero() {
+                        | true => sum + offset * offset,
+                        | false => sum,
+                    }
+                });
+                squared <= radius * radius
+            },
+            |entry| {
+                let reach = radius + entry.radius;
+                entry.position.squared_distance(&center) <= reach * reach
+            },
+        )
+    }
+
+    pub fn query_region(&self, min: P, max: P) -> Vec<Handle<V>> {
+        let overlaps = |center: P, reach: T| {
+            (0..P::DIM).all(|axis| {
+                center.coord(axis) + reach >= min.coord(axis) && center.coord(axis) - reach <= max.coord(axis)
+            })
+        };
+
+        self.query(overlaps, |entry| overlaps(entry.position, entry.radius))
+    }
+
+    fn query(
+        &self,
+        visit_node: impl Fn(P, T) -> bool,
+        visit_entry: impl Fn(&TreeEntry<P, T>) -> bool,
+    ) -> Vec<Handle<V>> {
+        let mut out = Vec::new();
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let reach = match index {
+                | 0 => T::constant(f64::INFINITY),
+                | _ => node.half * T::constant(2.),
+            };
+            if !visit_node(node.center, reach) {
+                continue;
+            }
+
+            out.extend(node.items.iter().filter(|&&handle| self.entries.get(handle).is_some_and(&visit_entry)));
+            if let Some(first) = node.children {
+                stack.extend(first..first + (1 << P::DIM));
+            }
+        }
+        out
+    }
+
+    fn locate(&mut self, position: P, radius: T) -> usize {
+        let mut index = 0;
+        for _ in 0..self.max_depth {
+            let node = &self.nodes[index];
+            let child_half = node.half * T::constant(0.5);
+            let inside = (0..P::DIM).all(|axis| (position.coord(axis) - node.center.coord(axis)).abs() <= node.half);
+            if radius > child_half || !inside {
+                break;
+            }
+
+            let first = match node.children {
+                | Some(first) => first,
+                | None => self.split(index),
+            };
+            let octant = (0..P::DIM)
+                .filter(|&axis| position.coord(axis) >= self.nodes[index].center.coord(axis))
+                .fold(0, |acc, axis| acc | (1 << axis));
+            index = first + octant;
+        }
+        index
+    }
+
+    fn split(&mut self, index: usize) -> usize {
+        let (center, half) = (self.nodes[index].center, self.nodes[index].half);
+        let child_half = half * T::constant(0.5);
+        let first = self.nodes.len();
+
+        (0..1usize << P::DIM).for_each(|octant| {
+            let center = P::from_fn(|axis| match octant & (1 << axis) {
+                | 0 => center.coord(axis) - child_half,
+                | _ => center.coord(axis) + child_half,
+            });
+            self.nodes.push(TreeNode { center, half: child_half, children: None, items: Vec::new() });
+        });
+
+        self.nodes[index].children = Some(first);
+        first
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* KD TREE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy)]
+struct KdEntry<P> {
+    position: P,
+    pending: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct KdTree<T, P, V> {
+    tree: Vec<(P, Handle<V>)>,
+    pending: Vec<Handle<V>>,
+    tombstones: usize,
+    values: Arena<V>,
+    entries: SecondaryMap<V, KdEntry<P>>,
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<T, P, V> KdTree<T, P, V>
+where
+    T: FloatNumber<T>,
+    P: SpatialPoint<T>,
+{
+    pub fn new() -> Self {
+        Self {
+            tree: Vec::new(),
+            pending: Vec::new(),
+            tombstones: 0,
+            values: Arena::new(),
+            entries: SecondaryMap::new(),
+            marker: std::marker::PhantomData,
+        }
+    }
+
+    pub fn build(items: impl IntoIterator<Item = (P, V)>) -> (Self, Vec<Handle<V>>) {
+        let mut out = Self::new();
+        let handles = items
+            .into_iter()
+            .map(|(position, value)| {
+                let handle = out.values.insert(value);
+                _ = out.entries.insert(handle, KdEntry { position, pending: false });
+                handle
+            })
+            .collect();
+        out.rebuild();
+        (out, handles)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, handle: Handle<V>) -> Option<&V> {
+        self.values.get(handle)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<V>) -> Option<&mut V> {
+        self.values.get_mut(handle)
+    }
+
+    pub fn position(&self, handle: Handle<V>) -> Option<P> {
+        self.entries.get(handle).map(|entry| entry.position)
+    }
+
+    pub fn insert(&mut self, position: P, value: V) -> Handle<V> {
+        let handle = self.values.insert(value);
+        _ = self.entries.insert(handle, KdEntry { position, pending: true });
+        self.pending.push(handle);
+        self.maintain();
+        handle
+    }
+
+    // the stale tree or pending record is left behind and skipped until the next rebuild
+    pub fn remove(&mut self, handle: Handle<V>) -> Option<V> {
+        let value = self.values.remove(handle)?;
+        self.entries.remove(handle);
+        self.tombstones += 1;
+        self.maintain();
+        Some(value)
+    }
+
+    pub fn update(&mut self, handle: Handle<V>, position: P) -> bool {
+        let Some(entry) = self.entries.get_mut(handle) else {
+            return false;
+        };
+
+        entry.position = position;
+        if !entry.pending {
+            entry.pending = true;
+            self.pending.push(handle);
+            self.tombstones += 1;
+        }
+        self.maintain();
+        true
+    }
+
+    pub fn rebuild(&mut self) {
+        self.tree.clear();
+        self.pending.clear();
+        self.tombstones = 0;
+        self.tree.extend(self.entries.iter_mut().map(|(handle, entry)| {
+            entry.pending = false;
+            (entry.position, handle)
+        }));
+
+        let tree = &mut self.tree;
+        Self::partition(tree, 0);
+    }
+
+    pub fn query_radius(&self, center: P, radius: T) -> Vec<Handle<V>> {
+        let limit = radius * radius;
+        let mut out = Vec::new();
+
+        let mut visit = |handle: Handle<V>, position: P| {
+            if position.squared_distance(&center) <= limit {
+                out.push(handle);
+            }
+        };
+        self.pending.iter().for_each(|&handle| {
+            if let Some(entry) = self.entries.get(handle) {
+                visit(handle, entry.position);
+            }
+        });
+        self.descend(&self.tree, 0, center, &|| limit, &mut visit);
+
+        out
+    }
+
+    pub fn nearest(&self, center: P, k: usize) -> Vec<(Handle<V>, T)> {
+        let mut best: Vec<(Handle<V>, T)> = Vec::with_capacity(k + 1);
+        if k == 0 {
+            return best;
+        }
+
+        let consider = |best: &mut Vec<(Handle<V>, T)>, handle: Handle<V>, position: P| {
+            let distance = position.squared_distance(&center);
+            let slot = best.iter().take_while(|&&(_, d)| d <= distance).count();
+            if slot < k {
+                best.insert(slot, (handle, distance));
+                best.truncate(k);
+            }
+        };
+        self.pending.iter().for_each(|&handle| {
+            if let Some(entry) = self.entries.get(handle) {
+                consider(&mut best, handle, entry.position);
+            }
+        });
+
+        let best = std::cell::RefCell::new(best);
+        self.descend(
+            &self.tree,
+            0,
+            center,
+            &|| {
+                let best = best.borrow();
+                match best.len() < k {
+                    | true => T::constant(f64::INFINITY),
+                    | false => best[k - 1].1,
+                }
+            },
+            &mut |handle, position| consider(&mut best.borrow_mut(), handle, position),
+        );
+
+        best.into_inner().into_iter().map(|(handle, squared)| (handle, squared.sqrt())).collect()
+    }
+
+    fn maintain(&mut self) {
+        let pending = self.pending.len() > 16 && self.pending.len() * 4 > self.values.len();
+        let stale = self.tombstones > 16 && self.tombstones * 4 > self.tree.len();
+        if pending || stale {
+            self.rebuild();
+        }
+    }
+
+    fn partition(items: &mut [(P, Handle<V>)], depth: usize) {
+        if items.len() <= 1 {
+            return;
+        }
+
+        let axis = depth % P::DIM;
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |a, b| {
+            a.0.coord(axis).partial_cmp(&b.0.coord(axis)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let (left, right) = items.split_at_mut(mid);
+        Self::partition(left, depth + 1);
+        Self::partition(&mut right[1..], depth + 1);
+    }
+
+    fn descend(
+        &self,
+        items: &[(P, Handle<V>)],
+        depth: usize,
+        center: P,
+        limit: &dyn Fn() -> T,
+        visit: &mut dyn FnMut(Handle<V>, P),
+    ) {
+        if items.is_empty() {
+            return;
+        }
+
+        let axis = depth % P::DIM;
+        let mid = items.len() / 2;
+        let (position, handle) = items[mid];
+        if self.entries.get(handle).is_some_and(|entry| !entry.pending) {
+            visit(handle, position);
+        }
+
+        let delta = center.coord(axis) - position.coord(axis);
+        let (near, far) = match delta < T::zero() {
+            | true => (&items[..mid], &items[mid + 1..]),
+            | false => (&items[mid + 1..], &items[..mid]),
+        };
+
+        self.descend(near, depth + 1, center, limit, visit);
+        if delta * delta <= limit() {
+            self.descend(far, depth + 1, center, limit, visit);
+        }
+    }
+}
+
+impl<T, P, V> Default for KdTree<T, P, V>
+where
+    T: FloatNumber<T>,
+    P: SpatialPoint<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::random::Rng;
+    use crate::math::random::Xoshiro256;
+
+    fn cloud(rng: &mut Xoshiro256, count: usize) -> Vec<Vector3<f64>> {
+        (0..count)
+            .map(|_| Vector3::build(rng.uniform(-10., 10.), rng.uniform(-10., 10.), rng.uniform(-10., 10.)))
+            .collect()
+    }
+
+    fn brute<V>(points: &[(Handle<V>, Vector3<f64>)], center: Vector3<f64>, radius: f64) -> Vec<Handle<V>> {
+        let mut out: Vec<Handle<V>> = points
+            .iter()
+            .filter(|(_, p)| p.squared_distance(&center) <= radius * radius)
+            .map(|&(h, _)| h)
+            .collect();
+        out.sort_by_key(|h| h.index());
+        out
+    }
+
+    fn sorted<V>(mut handles: Vec<Handle<V>>) -> Vec<Handle<V>> {
+        handles.sort_by_key(|h| h.index());
+        handles
+    }
+
+    #[test]
+    fn hash_grid() {
+        let mut rng = Xoshiro256::build(1);
+        let mut grid = HashGrid::build(1.5);
+        let mut live: Vec<(Handle<usize>, Vector3<f64>)> =
+            cloud(&mut rng, 400).into_iter().enumerate().map(|(i, p)| (grid.insert(p, i), p)).collect();
+
+        let stale = live[0].0;
+        live.drain(..100).for_each(|(handle, _)| assert!(grid.remove(handle).is_some()));
+        live.iter_mut().take(100).for_each(|(handle, position)| {
+            *position = cloud(&mut rng, 1)[0];
+            assert!(grid.update(*handle, *position));
+        });
+        assert!(grid.len() == 300);
+
+        (0..40).for_each(|_| {
+            let center = cloud(&mut rng, 1)[0];
+            assert!(sorted(grid.query_radius(center, 3.)) == brute(&live, center, 3.));
+        });
+
+        assert!(grid.get(stale).is_none() && grid.remove(stale).is_none());
+        let reused = grid.insert(Vector3::zeros(), 0);
+        assert!(reused != stale && grid.get(stale).is_none() && grid.get(reused) == Some(&0));
+    }
+
+    #[test]
+    fn loose_tree() {
+        let mut rng = Xoshiro256::build(2);
+        let mut tree = Octree::build(Vector3::zeros(), 16., 6);
+        let mut live: Vec<(Handle<()>, Vector3<f64>)> =
+            cloud(&mut rng, 400).into_iter().map(|p| (tree.insert(p, 0., ()), p)).collect();
+
+        live.drain(..50).for_each(|(handle, _)| assert!(tree.remove(handle).is_some()));
+        live.iter_mut().take(100).for_each(|(handle, position)| {
+            *position = cloud(&mut rng, 1)[0] * 1.5;
+            assert!(tree.update(*handle, *position, 0.));
+        });
+
+        (0..40).for_each(|_| {
+            let center = cloud(&mut rng, 1)[0];
+            assert!(sorted(tree.query_radius(center, 4.)) == brute(&live, center, 4.));
+        });
+
+        let mut flat = Quadtree::build(Vector2::zeros(), 8., 4);
+        let big = flat.insert(Vector2::build(1., 1.), 3., 'b');
+        let small = flat.insert(Vector2::build(-5., -5.), 0.1, 's');
+        assert!(flat.query_region(Vector2::build(3.5, 3.5), Vector2::build(5., 5.)) == vec![big]);
+        assert!(flat.query_region(Vector2::build(-6., -6.), Vector2::build(-4., -4.)) == vec![small]);
+        assert!(flat.get(small) == Some(&'s'));
+    }
+
+    #[test]
+    fn kd_tree() {
+        let mut rng = Xoshiro256::build(3);
+        let points = cloud(&mut rng, 500);
+        let (mut tree, handles) = KdTree::build(points.iter().map(|&p| (p, ())));
+        let mut live: Vec<(Handle<()>, Vector3<f64>)> = handles.into_iter().zip(points).collect();
+
+        live.drain(..60).for_each(|(handle, _)| assert!(tree.remove(handle).is_some()));
+        live.iter_mut().skip(200).take(30).for_each(|(handle, position)| {
+            *position = cloud(&mut rng, 1)[0];
+            assert!(tree.update(*handle, *position));
+        });
+        live.extend(cloud(&mut rng, 10).into_iter().map(|p| (tree.insert(p, ()), p)));
+
+        (0..40).for_each(|_| {
+            let center = cloud(&mut rng, 1)[0];
+            assert!(sorted(tree.query_radius(center, 3.)) == brute(&live, center, 3.));
+
+            let mut expected: Vec<f64> = live.iter().map(|(_, p)| p.squared_distance(&center).sqrt()).collect();
+            expected.sort_by(f64::total_cmp);
+            let found: Vec<f64> = tree.nearest(center, 6).iter().map(|&(_, d)| d).collect();
+            assert!(found == expected[..6]);
+        });
+
+        // removals alone leave tombstones behind until enough pile up to force a rebuild
+        assert!(tree.tombstones > 0 && tree.tree.len() == 500);
+        live.drain(..200).for_each(|(handle, _)| assert!(tree.remove(handle).is_some()));
+        assert!(tree.tree.len() < 500);
+        let center = cloud(&mut rng, 1)[0];
+        assert!(sorted(tree.query_radius(center, 3.)) == brute(&live, center, 3.));
+    }
+}
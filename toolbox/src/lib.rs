@@ -1,3 +1,4 @@
 pub mod containers;
 pub mod math;
 pub mod ptr;
+pub mod render;
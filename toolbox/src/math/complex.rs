@@ -0,0 +1,362 @@
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Div;
+use std::ops::DivAssign;
+use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+
+use super::traits::FloatNumber;
+use super::traits::One;
+use super::traits::Zero;
+
+#[macro_export]
+macro_rules! complex {
+    ($re:expr, $im:expr $(,)?) => {
+        Complex::build($re as f32, $im as f32)
+    };
+    ($type:ty; $re:expr, $im:expr $(,)?) => {
+        Complex::build($re as $type, $im as $type)
+    };
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* COMPLEX */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+impl<T> Complex<T> {
+    pub const fn build(re: T, im: T) -> Self {
+        Self { re, im }
+    }
+}
+
+impl<T> Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn zeros() -> Self {
+        Self::build(T::zero(), T::zero())
+    }
+
+    pub fn i() -> Self {
+        Self::build(T::zero(), T::one())
+    }
+
+    pub fn real(re: T) -> Self {
+        Self::build(re, T::zero())
+    }
+
+    pub fn from_polar(magnitude: T, argument: T) -> Self {
+        Self::build(magnitude * argument.cos(), magnitude * argument.sin())
+    }
+
+    pub fn to_polar(self) -> (T, T) {
+        (self.magnitude(), self.argument())
+    }
+
+    pub fn cis(argument: T) -> Self {
+        Self::from_polar(T::one(), argument)
+    }
+
+    pub fn conjugate(self) -> Self {
+        Self::build(self.re, -self.im)
+    }
+
+    pub fn squared_magnitude(self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+
+    pub fn magnitude(self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    pub fn argument(self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    pub fn reciprocal(self) -> Self {
+        let denom = self.squared_magnitude();
+        Self::build(self.re / denom, -self.im / denom)
+    }
+
+    pub fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    pub fn ln(self) -> Self {
+        Self::build(self.magnitude().ln(), self.argument())
+    }
+
+    pub fn sqrt(self) -> Self {
+        let (magnitude, argument) = self.to_polar();
+        Self::from_polar(magnitude.sqrt(), argument * T::constant(0.5))
+    }
+
+    pub fn powf(self, exponent: T) -> Self {
+        if self == Self::zeros() {
+            return Self::zeros();
+        }
+
+        let (magnitude, argument) = self.to_polar();
+        Self::from_polar(magnitude.powf(exponent), argument * exponent)
+    }
+
+    pub fn powi(self, exponent: i32) -> Self {
+        let mut base = match exponent < 0 {
+            | true => self.reciprocal(),
+            | false => self,
+        };
+        let mut power = exponent.unsigned_abs();
+        let mut out = Self::one();
+
+        while power > 0 {
+            if power & 1 == 1 {
+                out *= base;
+            }
+            base *= base;
+            power >>= 1;
+        }
+
+        out
+    }
+
+    pub fn pow(self, exponent: Self) -> Self {
+        if self == Self::zeros() {
+            return Self::zeros();
+        }
+
+        (self.ln() * exponent).exp()
+    }
+}
+
+impl<T> Zero<Complex<T>> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn zero() -> Complex<T> {
+        Complex::zeros()
+    }
+}
+
+impl<T> One<Complex<T>> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn one() -> Complex<T> {
+        Complex::real(T::one())
+    }
+}
+
+impl<T> From<T> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn from(value: T) -> Self {
+        Self::real(value)
+    }
+}
+
+impl<T> Add<Self> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self::build(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl<T> AddAssign<Self> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn add_assign(&mut self, other: Self) {
+        self.re += other.re;
+        self.im += other.im;
+    }
+}
+
+impl<T> Sub<Self> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self::build(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl<T> SubAssign<Self> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn sub_assign(&mut self, other: Self) {
+        self.re -= other.re;
+        self.im -= other.im;
+    }
+}
+
+impl<T> Mul<Self> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Self::build(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+}
+
+impl<T> MulAssign<Self> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<T> Mul<T> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self::Output {
+        Self::build(self.re * scalar, self.im * scalar)
+    }
+}
+
+impl<T> MulAssign<T> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn mul_assign(&mut self, scalar: T) {
+        self.re *= scalar;
+        self.im *= scalar;
+    }
+}
+
+impl<T> Div<Self> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self::Output {
+        let denom = other.squared_magnitude();
+
+        Self::build(
+            (self.re * other.re + self.im * other.im) / denom,
+            (self.im * other.re - self.re * other.im) / denom,
+        )
+    }
+}
+
+impl<T> DivAssign<Self> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl<T> Div<T> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    type Output = Self;
+
+    fn div(self, scalar: T) -> Self::Output {
+        Self::build(self.re / scalar, self.im / scalar)
+    }
+}
+
+impl<T> DivAssign<T> for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    fn div_assign(&mut self, scalar: T) {
+        self.re /= scalar;
+        self.im /= scalar;
+    }
+}
+
+impl<T> Neg for Complex<T>
+where
+    T: FloatNumber<T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::build(-self.re, -self.im)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::matrix::MatrixN;
+    use crate::math::vector::BasicVectorOps;
+    use crate::math::vector::VectorN;
+
+    fn close(a: Complex<f64>, b: Complex<f64>) -> bool {
+        (a - b).magnitude() < 1e-9
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = complex!(f64; 1, 2);
+        let b = complex!(f64; 3, -1);
+        assert!(a * b == complex!(f64; 5, 5));
+        assert!(close(a * b / b, a));
+        assert!(a.conjugate() == complex!(f64; 1, -2));
+        assert!(complex!(f64; 3, 4).magnitude() == 5.);
+    }
+
+    #[test]
+    fn polar() {
+        let z = Complex::from_polar(2., std::f64::consts::FRAC_PI_2);
+        assert!(close(z, complex!(f64; 0, 2)));
+
+        let (magnitude, argument) = complex!(f64; -1, 0).to_polar();
+        assert!(magnitude == 1. && argument == std::f64::consts::PI);
+    }
+
+    #[test]
+    fn transcendental() {
+        let euler = (Complex::i() * std::f64::consts::PI).exp();
+        assert!(close(euler, complex!(f64; -1, 0)));
+
+        let z = complex!(f64; 0.5, -1.5);
+        assert!(close(z.ln().exp(), z));
+        assert!(close(z.powi(3), z * z * z));
+        assert!(close(z.powi(-2), (z * z).reciprocal()));
+        assert!(close(z.powf(2.), z * z));
+        assert!(close(z.sqrt() * z.sqrt(), z));
+        assert!(close(Complex::i().pow(Complex::i()), complex!(f64; (-std::f64::consts::FRAC_PI_2).exp(), 0)));
+    }
+
+    #[test]
+    fn inside_vector_and_matrix() {
+        let v = VectorN::build([complex!(f64; 1, 1), complex!(f64; 0, 2)]);
+        assert!(v.inner_product(v) == complex!(f64; -4, 2));
+
+        let rotate = MatrixN::build([[Complex::zeros(), -Complex::one()], [Complex::one(), Complex::zeros()]]);
+        let out = rotate * v;
+        assert!(out == VectorN::build([complex!(f64; 0, -2), complex!(f64; 1, 1)]));
+    }
+}
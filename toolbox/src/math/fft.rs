@@ -0,0 +1,423 @@
+use crate::containers::buffer::Buffer2;
+
+use super::complex::Complex;
+use super::traits::FloatNumber;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* FFT */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn fft<T>(data: &mut [Complex<T>])
+where
+    T: FloatNumber<T>,
+{
+    transform(data, false);
+}
+
+pub fn ifft<T>(data: &mut [Complex<T>])
+where
+    T: FloatNumber<T>,
+{
+    transform(data, true);
+
+    let scale = T::one() / T::constant(data.len() as f64);
+    data.iter_mut().for_each(|value| *value *= scale);
+}
+
+pub fn rfft<T>(input: &[T]) -> Vec<Complex<T>>
+where
+    T: FloatNumber<T>,
+{
+    let n = input.len();
+    if n < 2 || !n.is_multiple_of(2) {
+        let mut data: Vec<Complex<T>> = input.iter().map(|&value| Complex::real(value)).collect();
+        fft(&mut data);
+        data.truncate(n / 2 + 1);
+        return data;
+    }
+
+    // pack even/odd samples into one half-length complex transform, then untangle the two spectra
+    let half = n / 2;
+    let mut packed: Vec<Complex<T>> = input.chunks_exact(2).map(|pair| Complex::build(pair[0], pair[1])).collect();
+    fft(&mut packed);
+
+    let one_2 = T::constant(0.5);
+    let angle = -T::constant(std::f64::consts::TAU / n as f64);
+
+    (0..=half)
+        .map(|k| {
+            let z = packed[k % half];
+            let z_mirror = packed[(half - k) % half].conjugate();
+            let even = (z + z_mirror) * one_2;
+            let odd = (z - z_mirror) * Complex::build(T::zero(), -one_2);
+
+            even + Complex::cis(angle * T::constant(k as f64)) * odd
+        })
+        .collect()
+}
+
+pub fn irfft<T>(spectrum: &[Complex<T>], n: usize) -> Vec<T>
+where
+    T: FloatNumber<T>,
+{
+    let mut data = vec![Complex::zeros(); n];
+
+    (0..n).for_each(|k| {
+        data[k] = match k < spectrum.len() {
+            | true => spectrum[k],
+            | false => spectrum.get(n - k).map_or(Complex::zeros(), |value| value.conjugate()),
+        };
+    });
+    ifft(&mut data);
+
+    data.iter().map(|value| value.re).collect()
+}
+
+pub fn frequencies<T>(n: usize, sample_rate: T) -> Vec<T>
+where
+    T: FloatNumber<T>,
+{
+    let resolution = sample_rate / T::constant(n as f64);
+
+    (0..=n / 2).map(|k| resolution * T::constant(k as f64)).collect()
+}
+
+fn transform<T>(data: &mut [Complex<T>], inverse: bool)
+where
+    T: FloatNumber<T>,
+{
+    let n = data.len();
+    if n < 2 {
+        return;
+    }
+
+    match n.is_power_of_two() {
+        | true => radix2(data, inverse),
+        | false => bluestein(data, inverse),
+    }
+}
+
+fn radix2<T>(data: &mut [Complex<T>], inverse: bool)
+where
+    T: FloatNumber<T>,
+{
+    let n = data.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = match inverse {
+        | true => 1.,
+        | false => -1.,
+    };
+    let twiddles: Vec<Complex<T>> = (0..n / 2)
+        .map(|k| Complex::cis(T::constant(sign * std::f64::consts::TAU * k as f64 / n as f64)))
+        .collect();
+
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let stride = n / len;
+
+        for start in (0..n).step_by(len) {
+            for k in 0..half {
+                let even = data[start + k];
+                let odd = data[start + k + half] * twiddles[k * stride];
+                data[start + k] = even + odd;
+                data[start + k + half] = even - odd;
+            }
+        }
+
+        len <<= 1;
+    }
+}
+
+fn bluestein<T>(data: &mut [Complex<T>], inverse: bool)
+where
+    T: FloatNumber<T>,
+{
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+
+    let sign = match inverse {
+        | true => 1.,
+        | false => -1.,
+    };
+    // k^2 is reduced mod 2n before scaling so the chirp stays accurate for long inputs
+    let chirp: Vec<Complex<T>> = (0..n)
+        .map(|k| {
+            let k2 = (k as u128 * k as u128 % (2 * n as u128)) as f64;
+            Complex::cis(T::constant(sign * std::f64::consts::PI * k2 / n as f64))
+        })
+        .collect();
+
+    let mut a = vec![Complex::zeros(); m];
+    let mut b = vec![Complex::zeros(); m];
+    (0..n).for_each(|k| {
+        a[k] = data[k] * chirp[k];
+        b[k] = chirp[k].conjugate();
+        if k > 0 {
+            b[m - k] = chirp[k].conjugate();
+        }
+    });
+
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    a.iter_mut().zip(b.iter()).for_each(|(x, y)| *x *= *y);
+    radix2(&mut a, true);
+
+    let scale = T::one() / T::constant(m as f64);
+    (0..n).for_each(|k| {
+        data[k] = a[k] * chirp[k] * scale;
+    });
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* WINDOWS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl Window {
+    pub fn coefficients<T>(self, n: usize) -> Vec<T>
+    where
+        T: FloatNumber<T>,
+    {
+        match n {
+            | 0 => return Vec::new(),
+            | 1 => return vec![T::one()],
+            | _ => {}
+        }
+
+        let denom = (n - 1) as f64;
+        (0..n)
+            .map(|i| {
+                let phase = std::f64::consts::TAU * i as f64 / denom;
+                let value = match self {
+                    | Window::Rectangular => 1.,
+                    | Window::Hann => 0.5 - 0.5 * phase.cos(),
+                    | Window::Hamming => 0.54 - 0.46 * phase.cos(),
+                    | Window::Blackman => 0.42 - 0.5 * phase.cos() + 0.08 * (2. * phase).cos(),
+                };
+
+                T::constant(value)
+            })
+            .collect()
+    }
+
+    pub fn apply<T>(self, data: &mut [T])
+    where
+        T: FloatNumber<T>,
+    {
+        let coefficients = self.coefficients::<T>(data.len());
+        data.iter_mut().zip(coefficients).for_each(|(value, coeff)| *value *= coeff);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SPECTRAL ANALYSIS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn welch<T>(signal: &[T], segment: usize, overlap: usize, window: Window, sample_rate: T) -> Vec<T>
+where
+    T: FloatNumber<T>,
+{
+    let bins = segment / 2 + 1;
+    let mut psd = vec![T::zero(); bins];
+    if segment == 0 || overlap >= segment || signal.len() < segment {
+        return psd;
+    }
+
+    let coefficients = window.coefficients::<T>(segment);
+    let mut power = T::zero();
+    coefficients.iter().for_each(|&coeff| power += coeff * coeff);
+    let scale = T::one() / (sample_rate * power);
+
+    let mut count = 0;
+    for start in (0..=signal.len() - segment).step_by(segment - overlap) {
+        let frame: Vec<T> = signal[start..start + segment].iter().zip(&coefficients).map(|(&x, &w)| x * w).collect();
+
+        rfft(&frame).iter().enumerate().for_each(|(k, value)| {
+            psd[k] += value.squared_magnitude() * scale;
+        });
+        count += 1;
+    }
+
+    let count = T::constant(count as f64);
+    let two = T::constant(2.);
+    psd.iter_mut().enumerate().for_each(|(k, value)| {
+        *value /= count;
+        if k != 0 && !(segment.is_multiple_of(2) && k == bins - 1) {
+            *value *= two;
+        }
+    });
+
+    psd
+}
+
+pub fn spectrogram<T>(signal: &[T], segment: usize, hop: usize, window: Window) -> Buffer2<T>
+where
+    T: FloatNumber<T>,
+{
+    let bins = segment / 2 + 1;
+    let frames = match segment > 0 && hop > 0 && signal.len() >= segment {
+        | true => (signal.len() - segment) / hop + 1,
+        | false => 0,
+    };
+    let mut out = Buffer2::new(frames, bins, T::zero());
+    if frames == 0 {
+        return out;
+    }
+
+    let coefficients = window.coefficients::<T>(segment);
+    (0..frames).for_each(|frame| {
+        let start = frame * hop;
+        let samples: Vec<T> = signal[start..start + segment].iter().zip(&coefficients).map(|(&x, &w)| x * w).collect();
+
+        rfft(&samples).iter().enumerate().for_each(|(bin, value)| {
+            out.set_unchecked(frame, bin, value.squared_magnitude());
+        });
+    });
+
+    out
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* CONVOLUTION */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn convolve<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: FloatNumber<T>,
+{
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let len = a.len() + b.len() - 1;
+    let size = len.next_power_of_two();
+
+    let mut fa = vec![Complex::zeros(); size];
+    let mut fb = vec![Complex::zeros(); size];
+    a.iter().enumerate().for_each(|(i, &value)| fa[i] = Complex::real(value));
+    b.iter().enumerate().for_each(|(i, &value)| fb[i] = Complex::real(value));
+
+    fft(&mut fa);
+    fft(&mut fb);
+    fa.iter_mut().zip(fb.iter()).for_each(|(x, y)| *x *= *y);
+    ifft(&mut fa);
+
+    fa.iter().take(len).map(|value| value.re).collect()
+}
+
+// output index `i` holds lag `i - (b.len() - 1)`, i.e. sum of a[n + lag] * b[n]
+pub fn correlate<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: FloatNumber<T>,
+{
+    let reversed: Vec<T> = b.iter().rev().copied().collect();
+    convolve(a, &reversed)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dft(data: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let n = data.len();
+        (0..n)
+            .map(|k| {
+                let mut sum = Complex::zeros();
+                data.iter().enumerate().for_each(|(j, &value)| {
+                    sum += value * Complex::cis(-std::f64::consts::TAU * (k * j) as f64 / n as f64);
+                });
+                sum
+            })
+            .collect()
+    }
+
+    fn signal(n: usize) -> Vec<Complex<f64>> {
+        (0..n).map(|i| Complex::build((i as f64 * 0.37).sin() + 0.2, (i as f64 * 1.3).cos())).collect()
+    }
+
+    #[test]
+    fn matches_dft() {
+        for n in [1, 2, 8, 64, 3, 7, 12, 100] {
+            let input = signal(n);
+            let mut output = input.clone();
+            fft(&mut output);
+            dft(&input).iter().zip(&output).for_each(|(a, b)| assert!((*a - *b).magnitude() < 1e-9));
+
+            ifft(&mut output);
+            input.iter().zip(&output).for_each(|(a, b)| assert!((*a - *b).magnitude() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn real_input() {
+        for n in [16, 30, 9] {
+            let input: Vec<f64> = (0..n).map(|i| (i as f64 * 0.9).sin() * i as f64).collect();
+            let spectrum = rfft(&input);
+            let full = dft(&input.iter().map(|&x| Complex::real(x)).collect::<Vec<_>>());
+            assert!(spectrum.len() == n / 2 + 1);
+            spectrum.iter().zip(&full).for_each(|(a, b)| assert!((*a - *b).magnitude() < 1e-9));
+
+            let restored = irfft(&spectrum, n);
+            input.iter().zip(&restored).for_each(|(a, b)| assert!((a - b).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn convolution() {
+        let a = [1., 2., 3.];
+        let b = [0., 1., 0.5];
+        let out = convolve(&a, &b);
+        let expected = [0., 1., 2.5, 4., 1.5];
+        out.iter().zip(expected).for_each(|(x, y)| assert!((x - y).abs() < 1e-12));
+
+        let lags = correlate(&a, &a);
+        assert!((lags[2] - 14.).abs() < 1e-12);
+        assert!((lags[1] - 8.).abs() < 1e-12 && (lags[3] - 8.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn welch_peak() {
+        let sample_rate = 1000.;
+        let tone = 125.;
+        let signal: Vec<f64> =
+            (0..4096).map(|i| (std::f64::consts::TAU * tone * i as f64 / sample_rate).sin()).collect();
+
+        let psd = welch(&signal, 256, 128, Window::Hann, sample_rate);
+        let freqs = frequencies(256, sample_rate);
+        let peak = (0..psd.len()).max_by(|&a, &b| psd[a].partial_cmp(&psd[b]).unwrap()).unwrap();
+        assert!(freqs[peak] == tone);
+
+        let mut total = 0.;
+        psd.iter().for_each(|value| total += value * sample_rate / 256.);
+        assert!((total - 0.5).abs() < 0.01);
+
+        let image = spectrogram(&signal, 256, 128, Window::Hann);
+        assert!(image.width == 31 && image.height == 129);
+
+        let empty = spectrogram(&signal, 0, 128, Window::Hann);
+        assert!(empty.width == 0 && empty.height == 1);
+        assert!(Window::Blackman.coefficients::<f64>(0).is_empty() && Window::Hann.coefficients::<f64>(1) == [1.]);
+        Window::Hamming.apply::<f64>(&mut []);
+    }
+}
@@ -0,0 +1,505 @@
+use std::cmp::Ordering;
+
+use crate::containers::rolling_history::RollingHistory;
+
+use super::complex::Complex;
+use super::fft::Window;
+use super::traits::FloatNumber;
+
+pub trait Filter<T>
+where
+    Self: Sized,
+{
+    fn process(&mut self, sample: T) -> T;
+
+    fn reset(&mut self);
+
+    fn process_slice(&mut self, input: &[T]) -> Vec<T>
+    where
+        T: Copy,
+    {
+        input.iter().map(|&sample| self.process(sample)).collect()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* FIR */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone)]
+pub struct Fir<T> {
+    taps: Vec<T>,
+    history: Vec<T>,
+    head: usize,
+}
+
+impl<T> Fir<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(taps: Vec<T>) -> Self {
+        let history = vec![T::zero(); taps.len()];
+        Self { taps, history, head: 0 }
+    }
+
+    pub fn lowpass(cutoff: T, sample_rate: T, length: usize, window: Window) -> Self {
+        Self::build(windowed_sinc(cutoff / sample_rate, length.max(1), window))
+    }
+
+    pub fn highpass(cutoff: T, sample_rate: T, length: usize, window: Window) -> Self {
+        let length = length | 1;
+        let mut taps = windowed_sinc(cutoff / sample_rate, length, window);
+
+        taps.iter_mut().for_each(|tap| *tap = -*tap);
+        taps[length / 2] += T::one();
+
+        Self::build(taps)
+    }
+
+    pub fn bandpass(low: T, high: T, sample_rate: T, length: usize, window: Window) -> Self {
+        let length = length.max(1);
+        let upper = windowed_sinc(high / sample_rate, length, window);
+        let lower = windowed_sinc(low / sample_rate, length, window);
+
+        Self::build(upper.iter().zip(lower).map(|(&a, b)| a - b).collect())
+    }
+
+    pub fn taps(&self) -> &[T] {
+        &self.taps
+    }
+}
+
+impl<T> Filter<T> for Fir<T>
+where
+    T: FloatNumber<T>,
+{
+    fn process(&mut self, sample: T) -> T {
+        let len = self.taps.len();
+        if len == 0 {
+            return T::zero();
+        }
+
+        self.history[self.head] = sample;
+
+        let mut out = T::zero();
+        (0..len).for_each(|i| {
+            out += self.taps[i] * self.history[(self.head + len - i) % len];
+        });
+
+        self.head = (self.head + 1) % len;
+        out
+    }
+
+    fn reset(&mut self) {
+        self.history.fill(T::zero());
+        self.head = 0;
+    }
+}
+
+fn windowed_sinc<T>(normalized_cutoff: T, length: usize, window: Window) -> Vec<T>
+where
+    T: FloatNumber<T>,
+{
+    let fc = normalized_cutoff;
+    let two = T::constant(2.);
+    let center = T::constant((length as f64 - 1.) / 2.);
+    let pi = T::constant(std::f64::consts::PI);
+
+    let mut taps: Vec<T> = window
+        .coefficients::<T>(length)
+        .iter()
+        .enumerate()
+        .map(|(i, &w)| {
+            let x = T::constant(i as f64) - center;
+            let sinc = match x == T::zero() {
+                | true => two * fc,
+                | false => (two * pi * fc * x).sin() / (pi * x),
+            };
+
+            sinc * w
+        })
+        .collect();
+
+    let mut sum = T::zero();
+    taps.iter().for_each(|&tap| sum += tap);
+    taps.iter_mut().for_each(|tap| *tap /= sum);
+
+    taps
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BIQUAD */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad<T> {
+    pub b0: T,
+    pub b1: T,
+    pub b2: T,
+    pub a1: T,
+    pub a2: T,
+    z1: T,
+    z2: T,
+}
+
+impl<T> Biquad<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(b: [T; 3], a: [T; 3]) -> Self {
+        let a0 = a[0];
+
+        Self {
+            b0: b[0] / a0,
+            b1: b[1] / a0,
+            b2: b[2] / a0,
+            a1: a[1] / a0,
+            a2: a[2] / a0,
+            z1: T::zero(),
+            z2: T::zero(),
+        }
+    }
+
+    pub fn lowpass(cutoff: T, sample_rate: T, q: T) -> Self {
+        let (cos, alpha) = Self::prewarp(cutoff, sample_rate, q);
+        let one = T::one();
+        let one_2 = T::constant(0.5);
+
+        Self::build(
+            [(one - cos) * one_2, one - cos, (one - cos) * one_2],
+            [one + alpha, T::constant(-2.) * cos, one - alpha],
+        )
+    }
+
+    pub fn highpass(cutoff: T, sample_rate: T, q: T) -> Self {
+        let (cos, alpha) = Self::prewarp(cutoff, sample_rate, q);
+        let one = T::one();
+        let one_2 = T::constant(0.5);
+
+        Self::build(
+            [(one + cos) * one_2, -(one + cos), (one + cos) * one_2],
+            [one + alpha, T::constant(-2.) * cos, one - alpha],
+        )
+    }
+
+    pub fn bandpass(center: T, sample_rate: T, q: T) -> Self {
+        let (cos, alpha) = Self::prewarp(center, sample_rate, q);
+        let one = T::one();
+
+        Self::build([alpha, T::zero(), -alpha], [one + alpha, T::constant(-2.) * cos, one - alpha])
+    }
+
+    pub fn notch(center: T, sample_rate: T, q: T) -> Self {
+        let (cos, alpha) = Self::prewarp(center, sample_rate, q);
+        let one = T::one();
+        let two_cos = T::constant(-2.) * cos;
+
+        Self::build([one, two_cos, one], [one + alpha, two_cos, one - alpha])
+    }
+
+    pub fn frequency_response(&self, frequency: T, sample_rate: T) -> Complex<T> {
+        let omega = T::constant(std::f64::consts::TAU) * frequency / sample_rate;
+        let z1 = Complex::cis(-omega);
+        let z2 = z1 * z1;
+
+        let numerator = Complex::real(self.b0) + z1 * self.b1 + z2 * self.b2;
+        let denominator = Complex::real(T::one()) + z1 * self.a1 + z2 * self.a2;
+
+        numerator / denominator
+    }
+
+    fn prewarp(frequency: T, sample_rate: T, q: T) -> (T, T) {
+        let omega = T::constant(std::f64::consts::TAU) * frequency / sample_rate;
+
+        (omega.cos(), omega.sin() / (T::constant(2.) * q))
+    }
+}
+
+impl<T> Filter<T> for Biquad<T>
+where
+    T: FloatNumber<T>,
+{
+    fn process(&mut self, sample: T) -> T {
+        let out = self.b0 * sample + self.z1;
+        self.z1 = self.b1 * sample - self.a1 * out + self.z2;
+        self.z2 = self.b2 * sample - self.a2 * out;
+
+        out
+    }
+
+    fn reset(&mut self) {
+        self.z1 = T::zero();
+        self.z2 = T::zero();
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* MOVING AVERAGES */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ema<T> {
+    pub alpha: T,
+    state: Option<T>,
+}
+
+impl<T> Ema<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(alpha: T) -> Self {
+        Self { alpha, state: None }
+    }
+
+    pub fn from_time_constant(tau: T, dt: T) -> Self {
+        Self::build(dt / (tau + dt))
+    }
+
+    pub fn value(&self) -> Option<T> {
+        self.state
+    }
+}
+
+impl<T> Filter<T> for Ema<T>
+where
+    T: FloatNumber<T>,
+{
+    fn process(&mut self, sample: T) -> T {
+        let out = match self.state {
+            | Some(prev) => prev + (sample - prev) * self.alpha,
+            | None => sample,
+        };
+
+        self.state = Some(out);
+        out
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sma<T> {
+    window: RollingHistory<T>,
+    sum: T,
+}
+
+impl<T> Sma<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(length: usize) -> Self {
+        Self { window: RollingHistory::build(length.max(1)), sum: T::zero() }
+    }
+}
+
+impl<T> Filter<T> for Sma<T>
+where
+    T: FloatNumber<T>,
+{
+    fn process(&mut self, sample: T) -> T {
+        if let Ok(Some(oldest)) = self.window.push_back(sample) {
+            self.sum -= oldest;
+        }
+
+        self.sum += sample;
+        self.sum / T::constant(self.window.len() as f64)
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = T::zero();
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* MEDIAN */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone)]
+pub struct Median<T> {
+    window: RollingHistory<T>,
+    sorted: Vec<T>,
+}
+
+impl<T> Median<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(length: usize) -> Self {
+        Self {
+            window: RollingHistory::build(length.max(1)),
+            sorted: Vec::with_capacity(length),
+        }
+    }
+
+    fn search(&self, value: T) -> usize {
+        self.sorted.partition_point(|probe| probe.partial_cmp(&value) == Some(Ordering::Less))
+    }
+}
+
+impl<T> Filter<T> for Median<T>
+where
+    T: FloatNumber<T>,
+{
+    fn process(&mut self, sample: T) -> T {
+        if let Ok(Some(oldest)) = self.window.push_back(sample) {
+            let index = self.search(oldest);
+            self.sorted.remove(index);
+        }
+
+        let index = self.search(sample);
+        self.sorted.insert(index, sample);
+
+        let mid = self.sorted.len() / 2;
+        match self.sorted.len() % 2 == 1 {
+            | true => self.sorted[mid],
+            | false => (self.sorted[mid - 1] + self.sorted[mid]) * T::constant(0.5),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sorted.clear();
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* ONE EURO */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OneEuro<T> {
+    pub min_cutoff: T,
+    pub beta: T,
+    pub derivative_cutoff: T,
+    pub dt: T,
+    value: Option<T>,
+    derivative: T,
+}
+
+impl<T> OneEuro<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(min_cutoff: T, beta: T, derivative_cutoff: T, dt: T) -> Self {
+        Self {
+            min_cutoff,
+            beta,
+            derivative_cutoff,
+            dt,
+            value: None,
+            derivative: T::zero(),
+        }
+    }
+
+    pub fn process_timed(&mut self, sample: T, dt: T) -> T {
+        let Some(prev) = self.value else {
+            self.value = Some(sample);
+            return sample;
+        };
+
+        let derivative = (sample - prev) / dt;
+        self.derivative = Self::smooth(self.derivative, derivative, Self::alpha(self.derivative_cutoff, dt));
+
+        let cutoff = self.min_cutoff + self.beta * self.derivative.abs();
+        let out = Self::smooth(prev, sample, Self::alpha(cutoff, dt));
+
+        self.value = Some(out);
+        out
+    }
+
+    fn alpha(cutoff: T, dt: T) -> T {
+        let tau = T::one() / (T::constant(std::f64::consts::TAU) * cutoff);
+        T::one() / (T::one() + tau / dt)
+    }
+
+    fn smooth(prev: T, sample: T, alpha: T) -> T {
+        prev + (sample - prev) * alpha
+    }
+}
+
+impl<T> Filter<T> for OneEuro<T>
+where
+    T: FloatNumber<T>,
+{
+    fn process(&mut self, sample: T) -> T {
+        self.process_timed(sample, self.dt)
+    }
+
+    fn reset(&mut self) {
+        self.value = None;
+        self.derivative = T::zero();
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(frequency: f64, sample_rate: f64, n: usize) -> Vec<f64> {
+        (0..n).map(|i| (std::f64::consts::TAU * frequency * i as f64 / sample_rate).sin()).collect()
+    }
+
+    fn peak(signal: &[f64]) -> f64 {
+        signal.iter().skip(signal.len() / 2).fold(0., |acc, x| x.abs().max(acc))
+    }
+
+    #[test]
+    fn fir_lowpass() {
+        let mut fir = Fir::lowpass(100., 1000., 63, Window::Blackman);
+        let pass = peak(&fir.process_slice(&tone(20., 1000., 1000)));
+        fir.reset();
+        let stop = peak(&fir.process_slice(&tone(300., 1000., 1000)));
+        assert!(pass > 0.95 && stop < 0.01);
+
+        let mut high = Fir::highpass(100., 1000., 63, Window::Blackman);
+        assert!(peak(&high.process_slice(&tone(20., 1000., 1000))) < 0.01);
+
+        let degenerate = [
+            Fir::lowpass(100., 1000., 0, Window::Hann),
+            Fir::highpass(100., 1000., 0, Window::Hann),
+            Fir::bandpass(50., 150., 1000., 0, Window::Hann),
+        ];
+        assert!(degenerate.iter().all(|fir| fir.taps().len() == 1) && degenerate[0].taps() == [1.]);
+    }
+
+    #[test]
+    fn biquad_response() {
+        let notch = Biquad::notch(50., 1000., 5.);
+        assert!(notch.frequency_response(50., 1000.).magnitude() < 1e-9);
+        assert!((notch.frequency_response(0., 1000.).magnitude() - 1.).abs() < 1e-9);
+
+        let mut lowpass = Biquad::lowpass(50., 1000., std::f64::consts::FRAC_1_SQRT_2);
+        let gain = lowpass.frequency_response(50., 1000.).magnitude();
+        assert!((gain - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-9);
+
+        let out = lowpass.process_slice(&tone(400., 1000., 1000));
+        assert!(peak(&out) < 0.05);
+
+        let bandpass = Biquad::bandpass(100., 1000., 2.);
+        assert!((bandpass.frequency_response(100., 1000.).magnitude() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn averages() {
+        let mut sma = Sma::build(3);
+        let out = sma.process_slice(&[3., 6., 9., 12.]);
+        assert!(out == vec![3., 4.5, 6., 9.]);
+
+        let mut ema = Ema::build(0.5);
+        let out = ema.process_slice(&[2., 4., 4.]);
+        assert!(out == vec![2., 3., 3.5]);
+
+        let mut median = Median::build(3);
+        let out = median.process_slice(&[1., 100., 2., 3., -50., 4.]);
+        assert!(out == vec![1., 50.5, 2., 3., 2., 3.]);
+    }
+
+    #[test]
+    fn one_euro_tracks() {
+        let mut filter = OneEuro::build(1., 0.1, 1., 0.01);
+        let mut last = 0.;
+        (0..500).for_each(|_| last = filter.process(5.));
+        assert!((last - 5.).abs() < 1e-9);
+    }
+}
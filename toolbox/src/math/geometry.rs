@@ -0,0 +1,926 @@
+use super::traits::FloatNumber;
+use super::vector::BasicVectorOps;
+use super::vector::FloatVectorOps;
+use super::vector::Vector3;
+
+pub trait Intersects<Rhs> {
+    fn intersects(&self, other: &Rhs) -> bool;
+}
+
+pub trait RayCast<T> {
+    fn raycast(&self, ray: &Ray<T>) -> Option<RayHit<T>>;
+}
+
+pub trait ClosestPoint<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T>;
+
+    fn distance(&self, point: Vector3<T>) -> T {
+        (self.closest_point(point) - point).length()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit<T> {
+    pub distance: T,
+    pub point: Vector3<T>,
+    pub normal: Vector3<T>,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* RAY */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Ray<T> {
+    pub origin: Vector3<T>,
+    pub direction: Vector3<T>,
+}
+
+impl<T> Ray<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(origin: Vector3<T>, direction: Vector3<T>) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn at(&self, distance: T) -> Vector3<T> {
+        self.origin + self.direction * distance
+    }
+
+    fn hit(&self, distance: T, normal: Vector3<T>) -> RayHit<T> {
+        RayHit { distance, point: self.at(distance), normal }
+    }
+}
+
+impl<T> ClosestPoint<T> for Ray<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T> {
+        let length = self.direction.squared_length();
+        if length == T::zero() {
+            return self.origin;
+        }
+
+        let t = max((point - self.origin) * self.direction / length, T::zero());
+        self.at(t)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PLANE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Plane<T> {
+    pub normal: Vector3<T>,
+    pub offset: T,
+}
+
+impl<T> Plane<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(normal: Vector3<T>, offset: T) -> Self {
+        Self { normal, offset }
+    }
+
+    pub fn from_point(normal: Vector3<T>, point: Vector3<T>) -> Self {
+        let normal = normal.normalize();
+        Self { normal, offset: normal * point }
+    }
+
+    pub fn from_points(a: Vector3<T>, b: Vector3<T>, c: Vector3<T>) -> Self {
+        Self::from_point((b - a) % (c - a), a)
+    }
+
+    pub fn signed_distance(&self, point: Vector3<T>) -> T {
+        self.normal * point - self.offset
+    }
+}
+
+impl<T> ClosestPoint<T> for Plane<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T> {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    fn distance(&self, point: Vector3<T>) -> T {
+        self.signed_distance(point).abs()
+    }
+}
+
+impl<T> RayCast<T> for Plane<T>
+where
+    T: FloatNumber<T>,
+{
+    fn raycast(&self, ray: &Ray<T>) -> Option<RayHit<T>> {
+        let denom = self.normal * ray.direction;
+        if denom == T::zero() {
+            return None;
+        }
+
+        let t = (self.offset - self.normal * ray.origin) / denom;
+        if t < T::zero() {
+            return None;
+        }
+
+        let normal = match denom < T::zero() {
+            | true => self.normal,
+            | false => -self.normal,
+        };
+        Some(ray.hit(t, normal))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SPHERE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Sphere<T> {
+    pub center: Vector3<T>,
+    pub radius: T,
+}
+
+impl<T> Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(center: Vector3<T>, radius: T) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains(&self, point: Vector3<T>) -> bool {
+        (point - self.center).squared_length() <= self.radius * self.radius
+    }
+
+    pub fn bounds(&self) -> Aabb<T> {
+        let extent = Vector3::splat(self.radius);
+        Aabb::build(self.center - extent, self.center + extent)
+    }
+}
+
+impl<T> ClosestPoint<T> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T> {
+        let offset = point - self.center;
+        let length = offset.length();
+        if length <= self.radius {
+            return point;
+        }
+
+        self.center + offset * (self.radius / length)
+    }
+}
+
+impl<T> RayCast<T> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn raycast(&self, ray: &Ray<T>) -> Option<RayHit<T>> {
+        let offset = ray.origin - self.center;
+        let a = ray.direction.squared_length();
+        let half_b = offset * ray.direction;
+        let c = offset.squared_length() - self.radius * self.radius;
+
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < T::zero() || a == T::zero() {
+            return None;
+        }
+
+        let root = discriminant.sqrt();
+        let near = (-half_b - root) / a;
+        let far = (-half_b + root) / a;
+        let t = match near >= T::zero() {
+            | true => near,
+            | false if far >= T::zero() => far,
+            | false => return None,
+        };
+
+        let point = ray.at(t);
+        Some(RayHit {
+            distance: t,
+            point,
+            normal: (point - self.center) / self.radius,
+        })
+    }
+}
+
+impl<T> Intersects<Sphere<T>> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Sphere<T>) -> bool {
+        let reach = self.radius + other.radius;
+        (other.center - self.center).squared_length() <= reach * reach
+    }
+}
+
+impl<T> Intersects<Aabb<T>> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Aabb<T>) -> bool {
+        (other.closest_point(self.center) - self.center).squared_length() <= self.radius * self.radius
+    }
+}
+
+impl<T> Intersects<Obb<T>> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Obb<T>) -> bool {
+        (other.closest_point(self.center) - self.center).squared_length() <= self.radius * self.radius
+    }
+}
+
+impl<T> Intersects<Plane<T>> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Plane<T>) -> bool {
+        other.signed_distance(self.center).abs() <= self.radius
+    }
+}
+
+impl<T> Intersects<Triangle<T>> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Triangle<T>) -> bool {
+        (other.closest_point(self.center) - self.center).squared_length() <= self.radius * self.radius
+    }
+}
+
+impl<T> Intersects<Capsule<T>> for Sphere<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Capsule<T>) -> bool {
+        let reach = self.radius + other.radius;
+        (other.segment.closest_point(self.center) - self.center).squared_length() <= reach * reach
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* AABB */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Aabb<T> {
+    pub min: Vector3<T>,
+    pub max: Vector3<T>,
+}
+
+impl<T> Aabb<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(min: Vector3<T>, max: Vector3<T>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_points(points: &[Vector3<T>]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+
+        Some(rest.iter().fold(Self::build(*first, *first), |bounds, &point| bounds.expand(point)))
+    }
+
+    pub fn center(&self) -> Vector3<T> {
+        (self.min + self.max) * T::constant(0.5)
+    }
+
+    pub fn half_extents(&self) -> Vector3<T> {
+        (self.max - self.min) * T::constant(0.5)
+    }
+
+    pub fn surface_area(&self) -> T {
+        let size = self.max - self.min;
+        T::constant(2.) * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    pub fn contains(&self, point: Vector3<T>) -> bool {
+        let (p, lo, hi) = (point.array(), self.min.array(), self.max.array());
+        (0..3).all(|i| lo[i] <= p[i] && p[i] <= hi[i])
+    }
+
+    pub fn expand(&self, point: Vector3<T>) -> Self {
+        Self::build(componentwise(self.min, point, min), componentwise(self.max, point, max))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::build(componentwise(self.min, other.min, min), componentwise(self.max, other.max, max))
+    }
+
+    pub fn slab(&self, ray: &Ray<T>) -> Option<(T, T)> {
+        let (origin, direction) = (ray.origin.array(), ray.direction.array());
+        let (lo, hi) = (self.min.array(), self.max.array());
+
+        let mut near = T::zero();
+        let mut far = T::constant(f64::INFINITY);
+        for i in 0..3 {
+            if direction[i] == T::zero() {
+                if origin[i] < lo[i] || origin[i] > hi[i] {
+                    return None;
+                }
+                continue;
+            }
+
+            let inverse = T::one() / direction[i];
+            let a = (lo[i] - origin[i]) * inverse;
+            let b = (hi[i] - origin[i]) * inverse;
+            near = max(near, min(a, b));
+            far = min(far, max(a, b));
+            if near > far {
+                return None;
+            }
+        }
+
+        Some((near, far))
+    }
+
+    // first boundary crossing, the exit when the origin lies strictly inside, the same as Sphere
+    fn crossing(&self, ray: &Ray<T>) -> Option<T> {
+        let (near, far) = self.slab(ray)?;
+        let (origin, lo, hi) = (ray.origin.array(), self.min.array(), self.max.array());
+        match (0..3).all(|i| lo[i] < origin[i] && origin[i] < hi[i]) {
+            | true if far < T::constant(f64::INFINITY) => Some(far),
+            | true => None,
+            | false => Some(near),
+        }
+    }
+}
+
+impl<T> ClosestPoint<T> for Aabb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T> {
+        componentwise(componentwise(point, self.min, max), self.max, min)
+    }
+}
+
+impl<T> RayCast<T> for Aabb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn raycast(&self, ray: &Ray<T>) -> Option<RayHit<T>> {
+        let distance = self.crossing(ray)?;
+        let point = ray.at(distance);
+
+        Some(RayHit {
+            distance,
+            point,
+            normal: box_normal(point - self.center(), self.half_extents()),
+        })
+    }
+}
+
+impl<T> Intersects<Aabb<T>> for Aabb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Aabb<T>) -> bool {
+        let (a_lo, a_hi) = (self.min.array(), self.max.array());
+        let (b_lo, b_hi) = (other.min.array(), other.max.array());
+
+        (0..3).all(|i| a_lo[i] <= b_hi[i] && b_lo[i] <= a_hi[i])
+    }
+}
+
+impl<T> Intersects<Sphere<T>> for Aabb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Sphere<T>) -> bool {
+        other.intersects(self)
+    }
+}
+
+impl<T> Intersects<Plane<T>> for Aabb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Plane<T>) -> bool {
+        let extent = self.half_extents();
+        let normal = other.normal;
+        let reach = extent.x * normal.x.abs() + extent.y * normal.y.abs() + extent.z * normal.z.abs();
+
+        other.signed_distance(self.center()).abs() <= reach
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* OBB */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Obb<T> {
+    pub center: Vector3<T>,
+    pub axes: [Vector3<T>; 3],
+    pub half_extents: Vector3<T>,
+}
+
+impl<T> Obb<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(center: Vector3<T>, axes: [Vector3<T>; 3], half_extents: Vector3<T>) -> Self {
+        Self { center, axes, half_extents }
+    }
+
+    pub fn from_aabb(aabb: &Aabb<T>) -> Self {
+        let (zero, one) = (T::zero(), T::one());
+        let axes = [
+            Vector3::build(one, zero, zero),
+            Vector3::build(zero, one, zero),
+            Vector3::build(zero, zero, one),
+        ];
+
+        Self::build(aabb.center(), axes, aabb.half_extents())
+    }
+
+    pub fn to_local(&self, point: Vector3<T>) -> Vector3<T> {
+        let offset = point - self.center;
+        Vector3::build(offset * self.axes[0], offset * self.axes[1], offset * self.axes[2])
+    }
+
+    pub fn to_world(&self, local: Vector3<T>) -> Vector3<T> {
+        self.center + self.axes[0] * local.x + self.axes[1] * local.y + self.axes[2] * local.z
+    }
+
+    pub fn contains(&self, point: Vector3<T>) -> bool {
+        let local = self.to_local(point);
+        Aabb::build(-self.half_extents, self.half_extents).contains(local)
+    }
+
+    pub fn bounds(&self) -> Aabb<T> {
+        let h = self.half_extents;
+        let reach = |i: usize| {
+            h.x * self.axes[0].array()[i].abs()
+                + h.y * self.axes[1].array()[i].abs()
+                + h.z * self.axes[2].array()[i].abs()
+        };
+        let extent = Vector3::build(reach(0), reach(1), reach(2));
+
+        Aabb::build(self.center - extent, self.center + extent)
+    }
+}
+
+impl<T> ClosestPoint<T> for Obb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T> {
+        let local = self.to_local(point);
+        let clamped = componentwise(componentwise(local, -self.half_extents, max), self.half_extents, min);
+
+        self.to_world(clamped)
+    }
+}
+
+impl<T> RayCast<T> for Obb<T>
+where
+    T: FloatNumber<T>,
+{
+    fn raycast(&self, ray: &Ray<T>) -> Option<RayHit<T>> {
+        let direction =
+            Vector3::build(ray.direction * self.axes[0], ray.direction * self.axes[1], ray.direction * self.axes[2]);
+        let local = Ray::build(self.to_local(ray.origin), direction);
+
+        let distance = Aabb::build(-self.half_extents, self.half_extents).crossing(&local)?;
+        let normal = box_normal(local.at(distance), self.half_extents);
+        let normal = self.axes[0] * normal.x + self.axes[1] * normal.y + self.axes[2] * normal.z;
+
+        Some(ray.hit(distance, normal))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SEGMENT */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Segment<T> {
+    pub start: Vector3<T>,
+    pub end: Vector3<T>,
+}
+
+impl<T> Segment<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(start: Vector3<T>, end: Vector3<T>) -> Self {
+        Self { start, end }
+    }
+
+    pub fn length(&self) -> T {
+        (self.end - self.start).length()
+    }
+
+    pub fn at(&self, t: T) -> Vector3<T> {
+        self.start + (self.end - self.start) * t
+    }
+
+    pub fn parameter(&self, point: Vector3<T>) -> T {
+        let direction = self.end - self.start;
+        let length = direction.squared_length();
+        if length == T::zero() {
+            return T::zero();
+        }
+
+        clamp((point - self.start) * direction / length, T::zero(), T::one())
+    }
+
+    pub fn closest_points(&self, other: &Self) -> (Vector3<T>, Vector3<T>) {
+        let (d1, d2) = (self.end - self.start, other.end - other.start);
+        let r = self.start - other.start;
+        let (a, e, f) = (d1.squared_length(), d2.squared_length(), d2 * r);
+        let (zero, one) = (T::zero(), T::one());
+
+        if a == zero && e == zero {
+            return (self.start, other.start);
+        }
+        if a == zero {
+            return (self.start, other.at(clamp(f / e, zero, one)));
+        }
+
+        let c = d1 * r;
+        if e == zero {
+            return (self.at(clamp(-c / a, zero, one)), other.start);
+        }
+
+        let b = d1 * d2;
+        let denom = a * e - b * b;
+        let mut s = match denom != zero {
+            | true => clamp((b * f - c * e) / denom, zero, one),
+            | false => zero,
+        };
+        let mut t = (b * s + f) / e;
+
+        if t < zero {
+            t = zero;
+            s = clamp(-c / a, zero, one);
+        } else if t > one {
+            t = one;
+            s = clamp((b - c) / a, zero, one);
+        }
+
+        (self.at(s), other.at(t))
+    }
+}
+
+impl<T> ClosestPoint<T> for Segment<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T> {
+        self.at(self.parameter(point))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* CAPSULE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Capsule<T> {
+    pub segment: Segment<T>,
+    pub radius: T,
+}
+
+impl<T> Capsule<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(start: Vector3<T>, end: Vector3<T>, radius: T) -> Self {
+        Self { segment: Segment::build(start, end), radius }
+    }
+
+    pub fn contains(&self, point: Vector3<T>) -> bool {
+        (self.segment.closest_point(point) - point).squared_length() <= self.radius * self.radius
+    }
+
+    pub fn bounds(&self) -> Aabb<T> {
+        let extent = Vector3::splat(self.radius);
+        let (a, b) = (self.segment.start, self.segment.end);
+
+        Aabb::build(componentwise(a, b, min) - extent, componentwise(a, b, max) + extent)
+    }
+}
+
+impl<T> ClosestPoint<T> for Capsule<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T> {
+        Sphere::build(self.segment.closest_point(point), self.radius).closest_point(point)
+    }
+}
+
+impl<T> Intersects<Capsule<T>> for Capsule<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Capsule<T>) -> bool {
+        let (a, b) = self.segment.closest_points(&other.segment);
+        let reach = self.radius + other.radius;
+
+        (b - a).squared_length() <= reach * reach
+    }
+}
+
+impl<T> Intersects<Sphere<T>> for Capsule<T>
+where
+    T: FloatNumber<T>,
+{
+    fn intersects(&self, other: &Sphere<T>) -> bool {
+        other.intersects(self)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TRIANGLE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Triangle<T> {
+    pub a: Vector3<T>,
+    pub b: Vector3<T>,
+    pub c: Vector3<T>,
+}
+
+impl<T> Triangle<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(a: Vector3<T>, b: Vector3<T>, c: Vector3<T>) -> Self {
+        Self { a, b, c }
+    }
+
+    pub fn normal(&self) -> Vector3<T> {
+        ((self.b - self.a) % (self.c - self.a)).normalize()
+    }
+
+    pub fn area(&self) -> T {
+        ((self.b - self.a) % (self.c - self.a)).length() * T::constant(0.5)
+    }
+
+    pub fn centroid(&self) -> Vector3<T> {
+        (self.a + self.b + self.c) / T::constant(3.)
+    }
+
+    pub fn bounds(&self) -> Aabb<T> {
+        Aabb::build(self.a, self.a).expand(self.b).expand(self.c)
+    }
+
+    pub fn barycentric(&self, point: Vector3<T>) -> Option<Vector3<T>> {
+        let (v0, v1, v2) = (self.b - self.a, self.c - self.a, point - self.a);
+        let (d00, d01, d11) = (v0 * v0, v0 * v1, v1 * v1);
+        let (d20, d21) = (v2 * v0, v2 * v1);
+
+        let denom = d00 * d11 - d01 * d01;
+        if denom == T::zero() {
+            return None;
+        }
+
+        let v = (d11 * d20 - d01 * d21) / denom;
+        let w = (d00 * d21 - d01 * d20) / denom;
+        Some(Vector3::build(T::one() - v - w, v, w))
+    }
+
+    pub fn interpolate(&self, weights: Vector3<T>) -> Vector3<T> {
+        self.a * weights.x + self.b * weights.y + self.c * weights.z
+    }
+}
+
+impl<T> ClosestPoint<T> for Triangle<T>
+where
+    T: FloatNumber<T>,
+{
+    fn closest_point(&self, point: Vector3<T>) -> Vector3<T> {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let (ab, ac, ap) = (b - a, c - a, point - a);
+        let zero = T::zero();
+
+        let (d1, d2) = (ab * ap, ac * ap);
+        if d1 <= zero && d2 <= zero {
+            return a;
+        }
+
+        let bp = point - b;
+        let (d3, d4) = (ab * bp, ac * bp);
+        if d3 >= zero && d4 <= d3 {
+            return b;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= zero && d1 >= zero && d3 <= zero {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = point - c;
+        let (d5, d6) = (ab * cp, ac * cp);
+        if d6 >= zero && d5 <= d6 {
+            return c;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= zero && d2 >= zero && d6 <= zero {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= zero && (d4 - d3) >= zero && (d5 - d6) >= zero {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        let denom = T::one() / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+}
+
+impl<T> RayCast<T> for Triangle<T>
+where
+    T: FloatNumber<T>,
+{
+    fn raycast(&self, ray: &Ray<T>) -> Option<RayHit<T>> {
+        let (edge1, edge2) = (self.b - self.a, self.c - self.a);
+        let p = ray.direction % edge2;
+        let det = edge1 * p;
+
+        // det scales with the edge and direction lengths, so near parallel rays and slivers are judged relative
+        let tolerance = T::epsilon() * edge1.length() * edge2.length() * ray.direction.length();
+        if det.abs() <= tolerance {
+            return None;
+        }
+
+        let inverse = T::one() / det;
+        let s = ray.origin - self.a;
+        let u = s * p * inverse;
+        if u < T::zero() || u > T::one() {
+            return None;
+        }
+
+        let q = s % edge1;
+        let v = ray.direction * q * inverse;
+        if v < T::zero() || u + v > T::one() {
+            return None;
+        }
+
+        let t = edge2 * q * inverse;
+        if t < T::zero() {
+            return None;
+        }
+
+        let normal = (edge1 % edge2).normalize();
+        let normal = match det > T::zero() {
+            | true => normal,
+            | false => -normal,
+        };
+        Some(ray.hit(t, normal))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* UTILS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+fn min<T>(a: T, b: T) -> T
+where
+    T: PartialOrd,
+{
+    if b < a { b } else { a }
+}
+
+fn max<T>(a: T, b: T) -> T
+where
+    T: PartialOrd,
+{
+    if b > a { b } else { a }
+}
+
+fn clamp<T>(value: T, low: T, high: T) -> T
+where
+    T: PartialOrd,
+{
+    max(low, min(value, high))
+}
+
+fn componentwise<T>(a: Vector3<T>, b: Vector3<T>, op: fn(T, T) -> T) -> Vector3<T> {
+    Vector3::build(op(a.x, b.x), op(a.y, b.y), op(a.z, b.z))
+}
+
+fn box_normal<T>(local: Vector3<T>, half_extents: Vector3<T>) -> Vector3<T>
+where
+    T: FloatNumber<T>,
+{
+    let (p, h) = (local.array(), half_extents.array());
+    let axis = (0..3)
+        .map(|i| {
+            let scale = match h[i] == T::zero() {
+                | true => T::constant(f64::INFINITY),
+                | false => p[i].abs() / h[i],
+            };
+            (i, scale)
+        })
+        .fold((0, T::constant(f64::NEG_INFINITY)), |best, next| if next.1 > best.1 { next } else { best })
+        .0;
+
+    let mut normal = [T::zero(); 3];
+    normal[axis] = match p[axis] < T::zero() {
+        | true => -T::one(),
+        | false => T::one(),
+    };
+    Vector3::build(normal[0], normal[1], normal[2])
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64, z: f64) -> Vector3<f64> {
+        Vector3::build(x, y, z)
+    }
+
+    fn close(a: Vector3<f64>, b: Vector3<f64>) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn ray_queries() {
+        let triangle = Triangle::build(v(0., 0., 0.), v(1., 0., 0.), v(0., 1., 0.));
+        let hit = triangle.raycast(&Ray::build(v(0.25, 0.25, 2.), v(0., 0., -1.))).unwrap();
+        assert!(hit.distance == 2. && close(hit.point, v(0.25, 0.25, 0.)) && close(hit.normal, v(0., 0., 1.)));
+        assert!(triangle.raycast(&Ray::build(v(0.75, 0.75, 2.), v(0., 0., -1.))).is_none());
+        assert!(triangle.raycast(&Ray::build(v(0.25, 0.25, 2.), v(0., 0., 1.))).is_none());
+        assert!(triangle.raycast(&Ray::build(v(0.25, 0.25, 0.), v(1., 1e-17, 0.))).is_none());
+        let sliver = Triangle::build(v(0., 0., 0.), v(1e8, 0., 0.), v(2e8, 1e-9, 0.));
+        assert!(sliver.raycast(&Ray::build(v(1e8, 0., 1.), v(0., 0., -1.))).is_none());
+        let tiny = Triangle::build(v(0., 0., 0.), v(1e-9, 0., 0.), v(0., 1e-9, 0.));
+        assert!(tiny.raycast(&Ray::build(v(2e-10, 2e-10, 1.), v(0., 0., -1.))).is_some());
+
+        let aabb = Aabb::build(v(-1., -1., -1.), v(1., 1., 1.));
+        let hit = aabb.raycast(&Ray::build(v(-5., 0.5, 0.), v(1., 0., 0.))).unwrap();
+        assert!(hit.distance == 4. && close(hit.normal, v(-1., 0., 0.)));
+        assert!(aabb.slab(&Ray::build(v(0., 0., 0.), v(0., 1., 0.))) == Some((0., 1.)));
+        assert!(aabb.raycast(&Ray::build(v(-5., 2., 0.), v(1., 0., 0.))).is_none());
+        let hit = aabb.raycast(&Ray::build(v(0., 0.5, 0.), v(2., 0., 0.))).unwrap();
+        assert!(hit.distance == 0.5 && close(hit.point, v(1., 0.5, 0.)) && close(hit.normal, v(1., 0., 0.)));
+        assert!(aabb.raycast(&Ray::build(v(-1., 0., 0.), v(1., 0., 0.))).unwrap().distance == 0.);
+
+        let sphere = Sphere::build(v(0., 0., 5.), 1.);
+        let hit = sphere.raycast(&Ray::build(v(0., 0., 0.), v(0., 0., 1.))).unwrap();
+        assert!(hit.distance == 4. && close(hit.normal, v(0., 0., -1.)));
+        let hit = sphere.raycast(&Ray::build(v(0., 0., 5.), v(0., 0., 1.))).unwrap();
+        assert!(hit.distance == 1. && close(hit.normal, v(0., 0., 1.)));
+
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        let obb = Obb::build(v(0., 0., 0.), [v(half, half, 0.), v(-half, half, 0.), v(0., 0., 1.)], v(1., 1., 1.));
+        let hit = obb.raycast(&Ray::build(v(-5., 0., 0.), v(1., 0., 0.))).unwrap();
+        assert!((hit.distance - (5. - 2f64.sqrt())).abs() < 1e-9);
+        let hit = obb.raycast(&Ray::build(v(0., 0., 0.), v(0., 0., 1.))).unwrap();
+        assert!((hit.distance - 1.).abs() < 1e-9 && close(hit.normal, v(0., 0., 1.)));
+    }
+
+    #[test]
+    fn overlap_queries() {
+        let a = Sphere::build(v(0., 0., 0.), 1.);
+        assert!(a.intersects(&Sphere::build(v(1.5, 0., 0.), 0.5)));
+        assert!(!a.intersects(&Sphere::build(v(1.6, 0., 0.), 0.5)));
+
+        let boxed = Aabb::build(v(0., 0., 0.), v(1., 1., 1.));
+        assert!(boxed.intersects(&Aabb::build(v(1., 1., 1.), v(2., 2., 2.))));
+        assert!(!boxed.intersects(&Aabb::build(v(1.1, 0., 0.), v(2., 1., 1.))));
+        assert!(boxed.intersects(&Sphere::build(v(1.5, 0.5, 0.5), 0.6)));
+        assert!(!boxed.intersects(&Sphere::build(v(1.5, 1.5, 1.5), 0.6)));
+
+        let first = Capsule::build(v(0., 0., 0.), v(0., 4., 0.), 0.5);
+        assert!(first.intersects(&Capsule::build(v(-2., 2., 0.9), v(2., 2., 0.9), 0.5)));
+        assert!(!first.intersects(&Capsule::build(v(-2., 2., 1.1), v(2., 2., 1.1), 0.5)));
+    }
+
+    #[test]
+    fn closest_points() {
+        let triangle = Triangle::build(v(0., 0., 0.), v(2., 0., 0.), v(0., 2., 0.));
+        assert!(close(triangle.closest_point(v(0.5, 0.5, 3.)), v(0.5, 0.5, 0.)));
+        assert!(close(triangle.closest_point(v(-1., -1., 0.)), v(0., 0., 0.)));
+        assert!(close(triangle.closest_point(v(1., -1., 0.)), v(1., 0., 0.)));
+        assert!(close(triangle.closest_point(v(2., 2., 0.)), v(1., 1., 0.)));
+
+        let weights = triangle.barycentric(v(0.5, 0.5, 0.)).unwrap();
+        assert!(close(weights, v(0.5, 0.25, 0.25)));
+        assert!(close(triangle.interpolate(weights), v(0.5, 0.5, 0.)));
+
+        let segment = Segment::build(v(0., 0., 0.), v(0., 0., 10.));
+        assert!(close(segment.closest_point(v(1., 0., 5.)), v(0., 0., 5.)));
+        assert!(close(segment.closest_point(v(1., 0., 15.)), v(0., 0., 10.)));
+        assert!((segment.distance(v(3., 4., -1.)) - 26f64.sqrt()).abs() < 1e-9);
+
+        let (p, q) = segment.closest_points(&Segment::build(v(-1., 2., 3.), v(1., 2., 3.)));
+        assert!(close(p, v(0., 0., 3.)) && close(q, v(0., 2., 3.)));
+    }
+}
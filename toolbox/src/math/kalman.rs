@@ -0,0 +1,345 @@
+use super::matrix::FloatMatrixOps;
+use super::matrix::MatrixMxN;
+use super::traits::FloatNumber;
+use super::vector::VectorN;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* LINEAR KALMAN FILTER */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy)]
+pub struct KalmanFilter<T, const N: usize, const M: usize> {
+    pub state: VectorN<T, N>,
+    pub covariance: MatrixMxN<T, N, N>,
+    pub transition: MatrixMxN<T, N, N>,
+    pub observation: MatrixMxN<T, M, N>,
+    pub process_noise: MatrixMxN<T, N, N>,
+    pub measurement_noise: MatrixMxN<T, M, M>,
+}
+
+impl<T, const N: usize, const M: usize> KalmanFilter<T, N, M>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(
+        state: VectorN<T, N>,
+        covariance: MatrixMxN<T, N, N>,
+        transition: MatrixMxN<T, N, N>,
+        observation: MatrixMxN<T, M, N>,
+        process_noise: MatrixMxN<T, N, N>,
+        measurement_noise: MatrixMxN<T, M, M>,
+    ) -> Self {
+        Self {
+            state,
+            covariance,
+            transition,
+            observation,
+            process_noise,
+            measurement_noise,
+        }
+    }
+
+    pub fn predict(&mut self) {
+        let f = self.transition;
+
+        self.state = f * self.state;
+        self.covariance = f * self.covariance * f.transpose() + self.process_noise;
+    }
+
+    pub fn predict_with_control<const C: usize>(&mut self, control: VectorN<T, C>, input: MatrixMxN<T, N, C>) {
+        self.predict();
+        self.state += input * control;
+    }
+
+    pub fn update(&mut self, measurement: VectorN<T, M>) -> Option<()> {
+        let h = self.observation;
+        let innovation = measurement - h * self.state;
+
+        (self.state, self.covariance) =
+            joseph_update(self.state, self.covariance, h, self.measurement_noise, innovation)?;
+        Some(())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* EXTENDED KALMAN FILTER */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy)]
+pub struct ExtendedKalmanFilter<T, const N: usize, const M: usize> {
+    pub state: VectorN<T, N>,
+    pub covariance: MatrixMxN<T, N, N>,
+    pub process_noise: MatrixMxN<T, N, N>,
+    pub measurement_noise: MatrixMxN<T, M, M>,
+}
+
+impl<T, const N: usize, const M: usize> ExtendedKalmanFilter<T, N, M>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(
+        state: VectorN<T, N>,
+        covariance: MatrixMxN<T, N, N>,
+        process_noise: MatrixMxN<T, N, N>,
+        measurement_noise: MatrixMxN<T, M, M>,
+    ) -> Self {
+        Self { state, covariance, process_noise, measurement_noise }
+    }
+
+    pub fn predict<F, J>(&mut self, dynamics: F, jacobian: J)
+    where
+        F: Fn(VectorN<T, N>) -> VectorN<T, N>,
+        J: Fn(VectorN<T, N>) -> MatrixMxN<T, N, N>,
+    {
+        let f = jacobian(self.state);
+
+        self.state = dynamics(self.state);
+        self.covariance = f * self.covariance * f.transpose() + self.process_noise;
+    }
+
+    pub fn update<H, J>(&mut self, measurement: VectorN<T, M>, model: H, jacobian: J) -> Option<()>
+    where
+        H: Fn(VectorN<T, N>) -> VectorN<T, M>,
+        J: Fn(VectorN<T, N>) -> MatrixMxN<T, M, N>,
+    {
+        let h = jacobian(self.state);
+        let innovation = measurement - model(self.state);
+
+        (self.state, self.covariance) =
+            joseph_update(self.state, self.covariance, h, self.measurement_noise, innovation)?;
+        Some(())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* UNSCENTED KALMAN FILTER */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy)]
+pub struct UnscentedKalmanFilter<T, const N: usize, const M: usize> {
+    pub state: VectorN<T, N>,
+    pub covariance: MatrixMxN<T, N, N>,
+    pub process_noise: MatrixMxN<T, N, N>,
+    pub measurement_noise: MatrixMxN<T, M, M>,
+    pub alpha: T,
+    pub beta: T,
+    pub kappa: T,
+}
+
+pub struct SigmaPoints<T, const N: usize> {
+    pub points: Vec<VectorN<T, N>>,
+    pub mean_weights: Vec<T>,
+    pub covariance_weights: Vec<T>,
+}
+
+impl<T, const N: usize, const M: usize> UnscentedKalmanFilter<T, N, M>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(
+        state: VectorN<T, N>,
+        covariance: MatrixMxN<T, N, N>,
+        process_noise: MatrixMxN<T, N, N>,
+        measurement_noise: MatrixMxN<T, M, M>,
+    ) -> Self {
+        Self {
+            state,
+            covariance,
+            process_noise,
+            measurement_noise,
+            // alpha = 1 keeps the sigma points at sqrt(n) deviations with a zero centre mean weight, smaller
+            // values shrink the spread but blow the weights up by 1 / alpha^2 and cost precision
+            alpha: T::one(),
+            beta: T::constant(2.),
+            kappa: T::zero(),
+        }
+    }
+
+    pub fn sigma_points(&self) -> Option<SigmaPoints<T, N>> {
+        let n = T::constant(N as f64);
+        let lambda = self.alpha * self.alpha * (n + self.kappa) - n;
+        let spread = (self.covariance * (n + lambda)).cholesky()?.transpose();
+
+        let mut points = Vec::with_capacity(2 * N + 1);
+        points.push(self.state);
+        (0..N).for_each(|i| points.push(self.state + VectorN::build(spread.inner[i])));
+        (0..N).for_each(|i| points.push(self.state - VectorN::build(spread.inner[i])));
+
+        let weight = T::one() / (T::constant(2.) * (n + lambda));
+        let mut mean_weights = vec![weight; 2 * N + 1];
+        let mut covariance_weights = vec![weight; 2 * N + 1];
+        mean_weights[0] = lambda / (n + lambda);
+        covariance_weights[0] = mean_weights[0] + T::one() - self.alpha * self.alpha + self.beta;
+
+        Some(SigmaPoints { points, mean_weights, covariance_weights })
+    }
+
+    pub fn predict<F>(&mut self, dynamics: F) -> Option<()>
+    where
+        F: Fn(VectorN<T, N>) -> VectorN<T, N>,
+    {
+        let sigma = self.sigma_points()?;
+        let propagated: Vec<VectorN<T, N>> = sigma.points.iter().map(|&point| dynamics(point)).collect();
+
+        let mean = weighted_mean(&propagated, &sigma.mean_weights);
+        let mut covariance = self.process_noise;
+        propagated.iter().zip(&sigma.covariance_weights).for_each(|(&point, &weight)| {
+            let delta = point - mean;
+            covariance = covariance + MatrixMxN::outer(delta, delta) * weight;
+        });
+
+        self.state = mean;
+        self.covariance = covariance;
+        Some(())
+    }
+
+    pub fn update<H>(&mut self, measurement: VectorN<T, M>, model: H) -> Option<()>
+    where
+        H: Fn(VectorN<T, N>) -> VectorN<T, M>,
+    {
+        let sigma = self.sigma_points()?;
+        let projected: Vec<VectorN<T, M>> = sigma.points.iter().map(|&point| model(point)).collect();
+        let predicted = weighted_mean(&projected, &sigma.mean_weights);
+
+        let mut innovation_covariance = self.measurement_noise;
+        let mut cross_covariance = MatrixMxN::<T, N, M>::zeros();
+        (0..sigma.points.len()).for_each(|i| {
+            let weight = sigma.covariance_weights[i];
+            let dz = projected[i] - predicted;
+            let dx = sigma.points[i] - self.state;
+
+            innovation_covariance = innovation_covariance + MatrixMxN::outer(dz, dz) * weight;
+            cross_covariance = cross_covariance + MatrixMxN::outer(dx, dz) * weight;
+        });
+
+        let gain = cross_covariance * innovation_covariance.inverse()?;
+        let covariance = self.covariance - gain * innovation_covariance * gain.transpose();
+
+        self.state += gain * (measurement - predicted);
+        self.covariance = symmetrize(covariance);
+        Some(())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* KALMAN UTILS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+fn joseph_update<T, const N: usize, const M: usize>(
+    state: VectorN<T, N>,
+    covariance: MatrixMxN<T, N, N>,
+    observation: MatrixMxN<T, M, N>,
+    noise: MatrixMxN<T, M, M>,
+    innovation: VectorN<T, M>,
+) -> Option<(VectorN<T, N>, MatrixMxN<T, N, N>)>
+where
+    T: FloatNumber<T>,
+{
+    let h = observation;
+    let innovation_covariance = h * covariance * h.transpose() + noise;
+    let gain = covariance * h.transpose() * innovation_covariance.inverse()?;
+
+    let residual = MatrixMxN::identity() - gain * h;
+    let covariance = residual * covariance * residual.transpose() + gain * noise * gain.transpose();
+
+    Some((state + gain * innovation, symmetrize(covariance)))
+}
+
+fn symmetrize<T, const N: usize>(matrix: MatrixMxN<T, N, N>) -> MatrixMxN<T, N, N>
+where
+    T: FloatNumber<T>,
+{
+    (matrix + matrix.transpose()) * T::constant(0.5)
+}
+
+fn weighted_mean<T, const N: usize>(points: &[VectorN<T, N>], weights: &[T]) -> VectorN<T, N>
+where
+    T: FloatNumber<T>,
+{
+    let mut mean = VectorN::zeros();
+    points.iter().zip(weights).for_each(|(&point, &weight)| mean += point * weight);
+
+    mean
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 0.1;
+
+    fn truth(step: usize) -> VectorN<f64, 2> {
+        let t = step as f64 * DT;
+        VectorN::build([1. + 2. * t, 2.])
+    }
+
+    fn transition() -> MatrixMxN<f64, 2, 2> {
+        MatrixMxN::build([[1., DT], [0., 1.]])
+    }
+
+    #[test]
+    fn linear_tracks_constant_velocity() {
+        let mut filter = KalmanFilter::build(
+            VectorN::zeros(),
+            MatrixMxN::identity() * 10.,
+            transition(),
+            MatrixMxN::build([[1., 0.]]),
+            MatrixMxN::identity() * 1e-6,
+            MatrixMxN::build([[0.01]]),
+        );
+
+        (1..200).for_each(|step| {
+            filter.predict();
+            filter.update(VectorN::build([truth(step).inner[0]])).unwrap();
+        });
+
+        let error = filter.state - truth(199);
+        assert!(error.inner[0].abs() < 1e-3 && error.inner[1].abs() < 1e-2);
+        assert!(filter.covariance.inner[0][1] == filter.covariance.inner[1][0]);
+    }
+
+    #[test]
+    fn extended_tracks_range() {
+        let range = |x: VectorN<f64, 2>| VectorN::build([((x.inner[0] + 20.).powi(2) + 9.).sqrt()]);
+        let range_jacobian = |x: VectorN<f64, 2>| MatrixMxN::build([[(x.inner[0] + 20.) / range(x).inner[0], 0.]]);
+
+        let mut filter = ExtendedKalmanFilter::build(
+            VectorN::build([0.5, 0.]),
+            MatrixMxN::identity() * 10.,
+            MatrixMxN::identity() * 1e-6,
+            MatrixMxN::build([[0.01]]),
+        );
+
+        (1..200).for_each(|step| {
+            filter.predict(|x| transition() * x, |_| transition());
+            filter.update(range(truth(step)), range, range_jacobian).unwrap();
+        });
+
+        let error = filter.state - truth(199);
+        assert!(error.inner[0].abs() < 1e-2 && error.inner[1].abs() < 1e-2);
+    }
+
+    #[test]
+    fn unscented_tracks_range() {
+        let range = |x: VectorN<f64, 2>| VectorN::build([((x.inner[0] + 20.).powi(2) + 9.).sqrt()]);
+
+        let mut filter = UnscentedKalmanFilter::build(
+            VectorN::build([0.5, 0.]),
+            MatrixMxN::identity() * 10.,
+            MatrixMxN::identity() * 1e-6,
+            MatrixMxN::build([[0.01]]),
+        );
+
+        let sigma = filter.sigma_points().unwrap();
+        let mut total = 0.;
+        sigma.mean_weights.iter().for_each(|weight| total += weight);
+        assert!(sigma.points.len() == 5 && (total - 1.).abs() < 1e-12);
+
+        (1..200).for_each(|step| {
+            filter.predict(|x| transition() * x).unwrap();
+            filter.update(range(truth(step)), range).unwrap();
+        });
+
+        let error = filter.state - truth(199);
+        assert!(error.inner[0].abs() < 1e-2 && error.inner[1].abs() < 1e-2);
+    }
+}
@@ -783,14 +783,12 @@ where
     }
 
     fn transpose(&self) -> Self {
+        #![allow(clippy::needless_range_loop)]
+
         let mut m = self.inner;
 
         for i in 0..Self::DIM {
-            for j in 0..Self::DIM {
-                if i == j {
-                    continue;
-                }
-
+            for j in (i + 1)..Self::DIM {
                 (m[i][j], m[j][i]) = (m[j][i], m[i][j]);
             }
         }
@@ -1058,14 +1056,12 @@ where
     }
 
     fn transpose(&self) -> Self {
+        #![allow(clippy::needless_range_loop)]
+
         let mut m = self.inner;
 
         for i in 0..Self::DIM {
-            for j in 0..Self::DIM {
-                if i == j {
-                    continue;
-                }
-
+            for j in (i + 1)..Self::DIM {
                 (m[i][j], m[j][i]) = (m[j][i], m[i][j]);
             }
         }
@@ -1289,6 +1285,8 @@ where
     T: Numeric<T>,
 {
     fn determinant(&self) -> T {
+        #![allow(clippy::needless_range_loop)]
+
         let m = self.inner;
         let mut out = T::zero();
 
@@ -1300,13 +1298,11 @@ where
     }
 
     fn transpose(&self) -> Self {
+        #![allow(clippy::needless_range_loop)]
+
         let mut m = self.inner;
         for i in 0..N {
-            for j in 0..N {
-                if i == j {
-                    continue;
-                }
-
+            for j in (i + 1)..N {
                 (m[i][j], m[j][i]) = (m[j][i], m[i][j]);
             }
         }
@@ -1377,15 +1373,7 @@ where
     T: FloatNumber<T>,
 {
     fn inverse(&self) -> Option<Self> {
-        let det = self.determinant();
-        if det == T::zero() {
-            return None;
-        }
-
-        let inv_det = T::one() / det;
-        let adjugate = self.cofactor_matrix().transpose();
-
-        Some(adjugate * inv_det)
+        gauss_jordan(self.inner).map(Self::build)
     }
 }
 
@@ -1475,6 +1463,122 @@ where
     pub const fn splat(value: T) -> Self {
         Self::build([[value; N]; M])
     }
+
+    pub fn identity() -> Self {
+        let mut inner = Self::zeros().inner;
+
+        (0..M.min(N)).for_each(|i| {
+            inner[i][i] = T::one();
+        });
+
+        Self::build(inner)
+    }
+
+    pub fn transpose(&self) -> MatrixMxN<T, N, M> {
+        let mut inner = MatrixMxN::zeros().inner;
+
+        (0..M).for_each(|i| {
+            (0..N).for_each(|j| {
+                inner[j][i] = self.inner[i][j];
+            });
+        });
+
+        MatrixMxN::build(inner)
+    }
+}
+
+impl<T, const M: usize, const N: usize> MatrixMxN<T, M, N>
+where
+    T: Numeric<T>,
+{
+    pub fn outer(a: VectorN<T, M>, b: VectorN<T, N>) -> Self {
+        let mut inner = Self::zeros().inner;
+
+        (0..M).for_each(|i| {
+            (0..N).for_each(|j| {
+                inner[i][j] = a.inner[i] * b.inner[j];
+            });
+        });
+
+        Self::build(inner)
+    }
+}
+
+impl<T, const N: usize> MatrixMxN<T, N, N>
+where
+    T: FloatNumber<T>,
+{
+    pub fn cholesky(&self) -> Option<Self> {
+        let m = self.inner;
+        let mut lower = Self::zeros().inner;
+
+        for i in 0..N {
+            for j in 0..=i {
+                let mut sum = m[i][j];
+                (0..j).for_each(|k| {
+                    sum -= lower[i][k] * lower[j][k];
+                });
+
+                if i == j {
+                    if sum <= T::zero() {
+                        return None;
+                    }
+                    lower[i][i] = sum.sqrt();
+                } else {
+                    lower[i][j] = sum / lower[j][j];
+                }
+            }
+        }
+
+        Some(Self::build(lower))
+    }
+}
+
+impl<T, const N: usize> FloatMatrixOps<T> for MatrixMxN<T, N, N>
+where
+    T: FloatNumber<T>,
+{
+    fn inverse(&self) -> Option<Self> {
+        gauss_jordan(self.inner).map(Self::build)
+    }
+}
+
+impl<T, const M: usize, const N: usize> Add<Self> for MatrixMxN<T, M, N>
+where
+    T: Numeric<T>,
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        let mut inner = self.inner;
+
+        (0..M).for_each(|i| {
+            (0..N).for_each(|j| {
+                inner[i][j] += other.inner[i][j];
+            });
+        });
+
+        Self::build(inner)
+    }
+}
+
+impl<T, const M: usize, const N: usize> Sub<Self> for MatrixMxN<T, M, N>
+where
+    T: Numeric<T>,
+{
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        let mut inner = self.inner;
+
+        (0..M).for_each(|i| {
+            (0..N).for_each(|j| {
+                inner[i][j] -= other.inner[i][j];
+            });
+        });
+
+        Self::build(inner)
+    }
 }
 
 impl<T, const M: usize, const N: usize> Mul<T> for MatrixMxN<T, M, N>
@@ -1538,6 +1642,62 @@ where
     }
 }
 
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* MATRIX UTILS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+fn gauss_jordan<T, const N: usize>(matrix: [[T; N]; N]) -> Option<[[T; N]; N]>
+where
+    T: FloatNumber<T>,
+{
+    let mut m = matrix;
+    let mut inverse = MatrixN::<T, N>::identity().inner;
+
+    // pivots lost in the rounding noise of the largest entry mean the matrix is numerically singular
+    let mut largest = T::zero();
+    matrix.iter().flatten().for_each(|&x| {
+        if x.abs() > largest {
+            largest = x.abs();
+        }
+    });
+    let tolerance = T::epsilon() * largest * T::constant(N as f64);
+
+    for col in 0..N {
+        let mut pivot = col;
+        (col + 1..N).for_each(|row| {
+            if m[row][col].abs() > m[pivot][col].abs() {
+                pivot = row;
+            }
+        });
+
+        if m[pivot][col].abs() <= tolerance {
+            return None;
+        }
+
+        m.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let scale = T::one() / m[col][col];
+        (0..N).for_each(|j| {
+            m[col][j] *= scale;
+            inverse[col][j] *= scale;
+        });
+
+        for row in 0..N {
+            let factor = m[row][col];
+            if row == col || factor == T::zero() {
+                continue;
+            }
+
+            (0..N).for_each(|j| {
+                m[row][j] -= factor * m[col][j];
+                inverse[row][j] -= factor * inverse[col][j];
+            });
+        }
+    }
+
+    Some(inverse)
+}
+
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////
 /* TEST */
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -1561,4 +1721,47 @@ mod tests {
         let inverse = matrix.inverse().unwrap();
         assert!(inverse == Matrix2::build([[-2., 1.], [3. / 2., -1. / 2.]]));
     }
+
+    #[test]
+    fn transpose() {
+        let matrix = Matrix4::build(std::array::from_fn(|i| std::array::from_fn(|j| (i * 4 + j) as i32)));
+        assert!(matrix.transpose().inner == std::array::from_fn(|i| std::array::from_fn(|j| (j * 4 + i) as i32)));
+
+        let matrix = Matrix5::build(std::array::from_fn(|i| std::array::from_fn(|j| (i * 5 + j) as i32)));
+        assert!(matrix.transpose().inner == std::array::from_fn(|i| std::array::from_fn(|j| (j * 5 + i) as i32)));
+        assert!(matrix.transpose().transpose() == matrix);
+
+        let matrix = MatrixN::<i32, 6>::build(std::array::from_fn(|i| std::array::from_fn(|j| (i * 6 + j) as i32)));
+        assert!(matrix.transpose().inner[1][4] == 25 && matrix.transpose().inner[4][1] == 10);
+        assert!(matrix.transpose().transpose() == matrix);
+    }
+
+    #[test]
+    fn pivoted_inverse() {
+        let matrix = MatrixN::<f64, 3>::build([[0., 2., 1.], [1., 0., 0.], [3., 1., 2.]]);
+        let product = matrix * matrix.inverse().unwrap();
+        (0..3).for_each(|i| {
+            (0..3).for_each(|j| {
+                let expected = if i == j { 1. } else { 0. };
+                assert!((product.inner[i][j] - expected).abs() < 1e-12);
+            });
+        });
+
+        let singular = MatrixMxN::<f64, 2, 2>::build([[1., 2.], [2., 4.]]);
+        assert!(singular.inverse().is_none());
+        let rounded = MatrixN::<f64, 3>::build([[0.1, 0.2, 0.3], [0.4, 0.5, 0.6], [0.7, 0.8, 0.9]]);
+        assert!(rounded.inverse().is_none());
+        let tiny = MatrixN::<f64, 3>::build([[0., 2., 1.], [1., 0., 0.], [3., 1., 2.]]) * 1e-200;
+        let product = tiny * tiny.inverse().unwrap();
+        assert!((product.inner[0][0] - 1.).abs() < 1e-12 && product.inner[2][1].abs() < 1e-12);
+    }
+
+    #[test]
+    fn cholesky() {
+        let matrix = MatrixMxN::<f64, 2, 2>::build([[4., 2.], [2., 3.]]);
+        let lower = matrix.cholesky().unwrap();
+        assert!(lower.inner == [[2., 0.], [1., 2f64.sqrt()]]);
+        assert!((lower * lower.transpose() - matrix).inner.iter().flatten().all(|x| x.abs() < 1e-12));
+        assert!(MatrixMxN::<f64, 2, 2>::build([[1., 2.], [2., 1.]]).cholesky().is_none());
+    }
 }
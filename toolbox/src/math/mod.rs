@@ -1,5 +1,14 @@
+pub mod complex;
+pub mod fft;
+pub mod filter;
+pub mod geometry;
 pub mod integration;
 pub mod interp;
+pub mod kalman;
 pub mod matrix;
+pub mod noise;
+pub mod polygon;
+pub mod random;
+pub mod stats;
 pub mod traits;
 pub mod vector;
@@ -0,0 +1,716 @@
+use std::ops::Add;
+use std::ops::Mul;
+
+use crate::containers::buffer::Buffer2;
+use crate::containers::buffer::Buffer3;
+
+use super::random::Rng;
+use super::random::SplitMix64;
+use super::random::Xoshiro256;
+use super::vector::Vector2;
+use super::vector::Vector3;
+use super::vector::Vector4;
+
+pub trait Noise<V> {
+    fn sample(&self, point: V) -> f32;
+}
+
+macro_rules! impl_noise {
+    ($type:ty, $func:ident) => {
+        impl Noise<f32> for $type {
+            fn sample(&self, point: f32) -> f32 {
+                self.$func([point])
+            }
+        }
+
+        impl Noise<Vector2<f32>> for $type {
+            fn sample(&self, point: Vector2<f32>) -> f32 {
+                self.$func(point.array())
+            }
+        }
+
+        impl Noise<Vector3<f32>> for $type {
+            fn sample(&self, point: Vector3<f32>) -> f32 {
+                self.$func(point.array())
+            }
+        }
+
+        impl Noise<Vector4<f32>> for $type {
+            fn sample(&self, point: Vector4<f32>) -> f32 {
+                self.$func(point.array())
+            }
+        }
+    };
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* LATTICE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Lattice {
+    perm: [u8; 256],
+    period: i32,
+}
+
+impl Lattice {
+    fn build(seed: u64, period: u32) -> Self {
+        let mut perm = [0; 256];
+        perm.iter_mut().enumerate().for_each(|(i, value)| *value = i as u8);
+        Xoshiro256::build(seed).shuffle(&mut perm);
+
+        // a period of 0 leaves the lattice non-periodic beyond the 256 cell permutation table
+        let period = match period {
+            | 0 => 256,
+            | _ => period.min(256),
+        };
+        Self { perm, period: period as i32 }
+    }
+
+    fn hash<const D: usize>(&self, cell: [i32; D]) -> usize {
+        let mut out = 0;
+        cell.iter().for_each(|&coord| {
+            out = self.perm[(out + coord.rem_euclid(self.period) as usize) & 255] as usize;
+        });
+
+        out
+    }
+
+    // 1d: magnitudes 1..8, 2d: the eight compass directions, 3d/4d: edges of the hypercube
+    fn gradient<const D: usize>(hash: usize, offset: [f32; D]) -> f32 {
+        if D == 1 {
+            let magnitude = ((hash & 7) + 1) as f32;
+            return match hash & 8 == 0 {
+                | true => magnitude * offset[0],
+                | false => -magnitude * offset[0],
+            };
+        }
+
+        let skip = match D {
+            | 2 => (hash >> 2) & 3,
+            | _ => (hash >> D) % D,
+        };
+
+        let mut out = 0.;
+        (0..D).for_each(|i| {
+            if D > 2 && i == skip || D == 2 && skip == 1 + i {
+                return;
+            }
+            match hash & (1 << i) == 0 {
+                | true => out += offset[i],
+                | false => out -= offset[i],
+            }
+        });
+
+        out
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PERLIN */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Perlin {
+    lattice: Lattice,
+}
+
+impl Perlin {
+    pub fn build(seed: u64) -> Self {
+        Self::periodic(seed, 0)
+    }
+
+    pub fn periodic(seed: u64, period: u32) -> Self {
+        Self { lattice: Lattice::build(seed, period) }
+    }
+
+    fn evaluate<const D: usize>(&self, point: [f32; D]) -> f32 {
+        let cell = point.map(|x| x.floor() as i32);
+        let fract: [f32; D] = std::array::from_fn(|i| point[i] - point[i].floor());
+        let fade = fract.map(|t| t * t * t * (t * (t * 6. - 15.) + 10.));
+
+        let mut out = 0.;
+        (0..1 << D).for_each(|corner: usize| {
+            let mut weight = 1.;
+            let mut lattice = cell;
+            let mut offset = fract;
+
+            (0..D).for_each(|i| match corner & (1 << i) == 0 {
+                | true => weight *= 1. - fade[i],
+                | false => {
+                    weight *= fade[i];
+                    lattice[i] += 1;
+                    offset[i] -= 1.;
+                }
+            });
+
+            out += weight * Lattice::gradient(self.lattice.hash(lattice), offset);
+        });
+
+        let scale = match D {
+            | 1 => 0.25,
+            | 4 => 0.9,
+            | _ => 1.,
+        };
+        (out * scale).clamp(-1., 1.)
+    }
+}
+
+impl_noise!(Perlin, evaluate);
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* OPENSIMPLEX */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// OpenSimplex2: simplex lattice in 1d/2d, rotated body-centred cubic lattice in 3d and five staggered
+// simplex lattice copies in 4d, which avoids the axis-aligned artifacts of classic simplex noise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenSimplex {
+    lattice: Lattice,
+}
+
+impl OpenSimplex {
+    // cos of 7.5, 22.5, .., 82.5 degrees, the 24 directions of the 2d gradient set
+    const DIRECTIONS: [f32; 6] = [
+        0.991_444_9,
+        0.923_879_5,
+        0.793_353_3,
+        0.608_761_4,
+        0.382_683_4,
+        0.130_526_2,
+    ];
+
+    pub fn build(seed: u64) -> Self {
+        Self { lattice: Lattice::build(seed, 0) }
+    }
+
+    fn gradient<const D: usize>(hash: usize, offset: [f32; D]) -> f32 {
+        if D != 2 {
+            return Lattice::gradient(hash, offset);
+        }
+
+        let index = hash % 24;
+        let (c, s) = (Self::DIRECTIONS[index % 6], Self::DIRECTIONS[5 - index % 6]);
+        let (x, y) = match index / 6 {
+            | 0 => (c, s),
+            | 1 => (-s, c),
+            | 2 => (-c, -s),
+            | _ => (s, -c),
+        };
+        x * offset[0] + y * offset[1]
+    }
+
+    fn contribution<const D: usize, const H: usize>(&self, radius: f32, cell: [i32; H], offset: [f32; D]) -> f32 {
+        let mut falloff = radius;
+        offset.iter().for_each(|&x| falloff -= x * x);
+        if falloff <= 0. {
+            return 0.;
+        }
+
+        falloff *= falloff;
+        falloff * falloff * Self::gradient(self.lattice.hash(cell), offset)
+    }
+
+    fn evaluate<const D: usize>(&self, point: [f32; D]) -> f32 {
+        let (out, scale) = match D {
+            | 1 => (self.simplex(point), 0.395),
+            | 2 => (self.simplex(point), 99.),
+            | 3 => (self.cubic(std::array::from_fn(|i| point[i])), 32.),
+            | _ => (self.staggered(std::array::from_fn(|i| point[i])), 27.),
+        };
+
+        (out * scale).clamp(-1., 1.)
+    }
+
+    fn simplex<const D: usize>(&self, point: [f32; D]) -> f32 {
+        let n = D as f32;
+        let (skew, unskew) = (((n + 1.).sqrt() - 1.) / n, (1. - 1. / (n + 1.).sqrt()) / n);
+        let radius = match D {
+            | 1 => 1.,
+            | _ => 0.5,
+        };
+
+        let mut sum = 0.;
+        point.iter().for_each(|&x| sum += x);
+        let cell = point.map(|x| (x + sum * skew).floor() as i32);
+
+        let mut cell_sum = 0.;
+        cell.iter().for_each(|&c| cell_sum += c as f32);
+        let origin: [f32; D] = std::array::from_fn(|i| point[i] - (cell[i] as f32 - cell_sum * unskew));
+
+        // walk the simplex corners from the largest to smallest offset axis
+        let mut order: [usize; D] = std::array::from_fn(|i| i);
+        order.sort_by(|&a, &b| origin[b].total_cmp(&origin[a]));
+
+        let mut out = 0.;
+        let mut step = [0; D];
+        (0..=D).for_each(|k| {
+            if k > 0 {
+                step[order[k - 1]] = 1;
+            }
+
+            let offset: [f32; D] = std::array::from_fn(|i| origin[i] - step[i] as f32 + k as f32 * unskew);
+            let lattice: [i32; D] = std::array::from_fn(|i| cell[i] + step[i]);
+            out += self.contribution(radius, lattice, offset);
+        });
+
+        out
+    }
+
+    fn cubic(&self, point: [f32; 3]) -> f32 {
+        // rotate so the lattice main diagonal lies along (1, 1, 1)
+        let r = (point[0] + point[1] + point[2]) * (2. / 3.);
+        let rotated = point.map(|x| r - x);
+
+        let mut cell = rotated.map(|x| x.round() as i32);
+        let mut offset: [f32; 3] = std::array::from_fn(|i| rotated[i] - cell[i] as f32);
+        let mut toward = offset.map(|x| if x < 0. { -1 } else { 1 });
+
+        let mut out = 0.;
+        (0..2).for_each(|layer| {
+            // closest point of this cubic lattice and its neighbour along the dominant axis
+            let magnitude = offset.map(f32::abs);
+            let axis = match magnitude {
+                | [x, y, z] if x >= y && x >= z => 0,
+                | [x, y, z] if y > x && y >= z => 1,
+                | _ => 2,
+            };
+
+            let mut neighbour = cell;
+            let mut shifted = offset;
+            neighbour[axis] += toward[axis];
+            shifted[axis] -= toward[axis] as f32;
+
+            out += self.contribution(0.6, [cell[0], cell[1], cell[2], layer], offset);
+            out += self.contribution(0.6, [neighbour[0], neighbour[1], neighbour[2], layer], shifted);
+
+            // the second lattice sits half a cell off the first, its closest point lies toward the sample
+            (0..3).for_each(|i| {
+                cell[i] += (toward[i] > 0) as i32;
+                offset[i] -= toward[i] as f32 * 0.5;
+                toward[i] = -toward[i];
+            });
+        });
+
+        out
+    }
+
+    fn staggered(&self, point: [f32; 4]) -> f32 {
+        const SKEW: f32 = -0.138_196_6;
+        const UNSKEW: f32 = 0.309_017;
+        const STEP: f32 = 0.2;
+
+        let mut sum = 0.;
+        point.iter().for_each(|&x| sum += x);
+        let skewed = point.map(|x| x + sum * SKEW);
+
+        let mut cell = skewed.map(|x| x.floor() as i32);
+        let mut inner: [f32; 4] = std::array::from_fn(|i| skewed[i] - cell[i] as f32);
+        let mut inner_sum = 0.;
+        inner.iter().for_each(|&x| inner_sum += x);
+
+        // start on the lattice copy whose base vertex is closest, then step down through the other four
+        let start = (inner_sum * 1.25) as i32;
+        inner = inner.map(|x| x - start as f32 * STEP);
+        let mut unskew = (inner_sum - start as f32 * STEP * 4.) * UNSKEW;
+
+        let mut out = 0.;
+        (0..5).for_each(|copy| {
+            let threshold = 1. - unskew / UNSKEW;
+            let axis = match inner {
+                | [x, y, z, w] if x >= y && x >= z && x >= w && x >= threshold => Some(0),
+                | [x, y, z, w] if y > x && y >= z && y >= w && y >= threshold => Some(1),
+                | [x, y, z, w] if z > x && z > y && z >= w && z >= threshold => Some(2),
+                | [x, y, z, w] if w > x && w > y && w > z && w >= threshold => Some(3),
+                | _ => None,
+            };
+            if let Some(axis) = axis {
+                cell[axis] += 1;
+                inner[axis] -= 1.;
+                unskew -= UNSKEW;
+            }
+
+            let offset = inner.map(|x| x + unskew);
+            let layer = (start - copy).rem_euclid(5);
+            out += self.contribution(0.6, [cell[0], cell[1], cell[2], cell[3], layer], offset);
+
+            inner = inner.map(|x| x + STEP);
+            unskew += STEP * 4. * UNSKEW;
+            if copy == start {
+                cell = cell.map(|c| c - 1);
+            }
+        });
+
+        out
+    }
+}
+
+impl_noise!(OpenSimplex, evaluate);
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* WORLEY */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distance {
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    F1,
+    F2,
+    F2MinusF1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Worley {
+    pub distance: Distance,
+    pub feature: Feature,
+    key: u64,
+    period: i32,
+}
+
+impl Worley {
+    pub fn build(seed: u64) -> Self {
+        Self::periodic(seed, 0)
+    }
+
+    pub fn periodic(seed: u64, period: u32) -> Self {
+        Self {
+            distance: Distance::Euclidean,
+            feature: Feature::F1,
+            key: SplitMix64::build(seed).next_u64(),
+            period: period as i32,
+        }
+    }
+
+    fn jitter<const D: usize>(&self, cell: [i32; D]) -> [f32; D] {
+        let mut hash = self.key;
+        cell.iter().for_each(|&coord| {
+            let coord = match self.period > 0 {
+                | true => coord.rem_euclid(self.period),
+                | false => coord,
+            };
+            hash = (hash ^ coord as u32 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            hash ^= hash >> 29;
+        });
+
+        std::array::from_fn(|i| ((hash >> (16 * i)) & 0xffff) as f32 / 65536.)
+    }
+
+    fn evaluate<const D: usize>(&self, point: [f32; D]) -> f32 {
+        let cell = point.map(|x| x.floor() as i32);
+        let (mut f1, mut f2) = (f32::MAX, f32::MAX);
+
+        (0..3usize.pow(D as u32)).for_each(|neighbour| {
+            let mut index = neighbour;
+            let lattice: [i32; D] = std::array::from_fn(|i| {
+                let offset = (index % 3) as i32 - 1;
+                index /= 3;
+                cell[i] + offset
+            });
+
+            let jitter = self.jitter(lattice);
+            let delta: [f32; D] = std::array::from_fn(|i| lattice[i] as f32 + jitter[i] - point[i]);
+            let distance = match self.distance {
+                | Distance::Euclidean => delta.iter().map(|x| x * x).sum::<f32>().sqrt(),
+                | Distance::Manhattan => delta.iter().map(|x| x.abs()).sum(),
+                | Distance::Chebyshev => delta.iter().fold(0., |acc: f32, x| acc.max(x.abs())),
+            };
+
+            if distance < f1 {
+                (f1, f2) = (distance, f1);
+            } else if distance < f2 {
+                f2 = distance;
+            }
+        });
+
+        match self.feature {
+            | Feature::F1 => f1,
+            | Feature::F2 => f2,
+            | Feature::F2MinusF1 => f2 - f1,
+        }
+    }
+}
+
+impl_noise!(Worley, evaluate);
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* FRACTAL COMBINATORS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fractal<N> {
+    pub noise: N,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl<N> Fractal<N> {
+    pub fn build(noise: N, octaves: u32) -> Self {
+        Self { noise, octaves, lacunarity: 2., gain: 0.5 }
+    }
+
+    fn accumulate<V, F>(&self, point: V, shape: F) -> f32
+    where
+        N: Noise<V>,
+        V: Mul<f32, Output = V> + Copy,
+        F: Fn(f32) -> f32,
+    {
+        let (mut out, mut total) = (0., 0.);
+        let (mut amplitude, mut frequency) = (1., 1.);
+
+        (0..self.octaves).for_each(|_| {
+            out += amplitude * shape(self.noise.sample(point * frequency));
+            total += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+        });
+
+        match total > 0. {
+            | true => out / total,
+            | false => 0.,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fbm<N>(pub Fractal<N>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ridged<N>(pub Fractal<N>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Turbulence<N>(pub Fractal<N>);
+
+impl<N, V> Noise<V> for Fbm<N>
+where
+    N: Noise<V>,
+    V: Mul<f32, Output = V> + Copy,
+{
+    fn sample(&self, point: V) -> f32 {
+        self.0.accumulate(point, |value| value)
+    }
+}
+
+impl<N, V> Noise<V> for Ridged<N>
+where
+    N: Noise<V>,
+    V: Mul<f32, Output = V> + Copy,
+{
+    fn sample(&self, point: V) -> f32 {
+        self.0.accumulate(point, |value| {
+            let ridge = 1. - value.abs();
+            ridge * ridge
+        })
+    }
+}
+
+impl<N, V> Noise<V> for Turbulence<N>
+where
+    N: Noise<V>,
+    V: Mul<f32, Output = V> + Copy,
+{
+    fn sample(&self, point: V) -> f32 {
+        self.0.accumulate(point, |value| value.abs())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* DOMAIN WARP */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Warp<N, W> {
+    pub noise: N,
+    pub warp: W,
+    pub strength: f32,
+}
+
+impl<N, W> Warp<N, W> {
+    const SHIFTS: [f32; 3] = [0., 5.2, 9.7];
+
+    pub fn build(noise: N, warp: W, strength: f32) -> Self {
+        Self { noise, warp, strength }
+    }
+
+    fn displace<V>(&self, point: V, shifted: [V; 3], axes: usize) -> [f32; 3]
+    where
+        W: Noise<V>,
+        V: Add<Output = V> + Copy,
+    {
+        std::array::from_fn(|i| match i < axes {
+            | true => self.warp.sample(point + shifted[i]) * self.strength,
+            | false => 0.,
+        })
+    }
+}
+
+impl<N, W> Noise<Vector2<f32>> for Warp<N, W>
+where
+    N: Noise<Vector2<f32>>,
+    W: Noise<Vector2<f32>>,
+{
+    fn sample(&self, point: Vector2<f32>) -> f32 {
+        let shifts = Self::SHIFTS.map(Vector2::splat);
+        let [dx, dy, _] = self.displace(point, shifts, 2);
+
+        self.noise.sample(point + Vector2::build(dx, dy))
+    }
+}
+
+impl<N, W> Noise<Vector3<f32>> for Warp<N, W>
+where
+    N: Noise<Vector3<f32>>,
+    W: Noise<Vector3<f32>>,
+{
+    fn sample(&self, point: Vector3<f32>) -> f32 {
+        let shifts = Self::SHIFTS.map(Vector3::splat);
+        let [dx, dy, dz] = self.displace(point, shifts, 3);
+
+        self.noise.sample(point + Vector3::build(dx, dy, dz))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* BUFFER FILL */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// `frequency` is the number of noise cells spanned along each axis; the result tiles seamlessly when it
+// matches the period of a periodic Perlin or Worley source and fractal lacunarity is an integer
+pub fn fill_buffer2<N>(buffer: &mut Buffer2<f32>, noise: &N, frequency: f32)
+where
+    N: Noise<Vector2<f32>>,
+{
+    let scale = Vector2::build(frequency / buffer.width as f32, frequency / buffer.height as f32);
+
+    (0..buffer.height).for_each(|y| {
+        (0..buffer.width).for_each(|x| {
+            let point = Vector2::build(x as f32 * scale.x, y as f32 * scale.y);
+            buffer.set_unchecked(x, y, noise.sample(point));
+        });
+    });
+}
+
+pub fn fill_buffer3<N>(buffer: &mut Buffer3<f32>, noise: &N, frequency: f32)
+where
+    N: Noise<Vector3<f32>>,
+{
+    let scale = Vector3::build(
+        frequency / buffer.width as f32,
+        frequency / buffer.height as f32,
+        frequency / buffer.depth as f32,
+    );
+
+    (0..buffer.depth).for_each(|z| {
+        (0..buffer.height).for_each(|y| {
+            (0..buffer.width).for_each(|x| {
+                let point = Vector3::build(x as f32 * scale.x, y as f32 * scale.y, z as f32 * scale.z);
+                buffer.set_unchecked(x, y, z, noise.sample(point));
+            });
+        });
+    });
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_range<V, N>(noise: &N, points: impl Iterator<Item = V>)
+    where
+        N: Noise<V>,
+    {
+        let (mut low, mut high) = (f32::MAX, f32::MIN);
+        points.for_each(|point| {
+            let value = noise.sample(point);
+            (low, high) = (low.min(value), high.max(value));
+        });
+
+        assert!((-1. ..=1.).contains(&low) && (-1. ..=1.).contains(&high));
+        assert!(high - low > 0.5);
+    }
+
+    fn grid(n: usize) -> impl Iterator<Item = (f32, f32)> {
+        (0..n * n).map(move |i| ((i % n) as f32 * 0.173, (i / n) as f32 * 0.291))
+    }
+
+    #[test]
+    fn gradient_noise_ranges() {
+        let perlin = Perlin::build(1);
+        let simplex = OpenSimplex::build(1);
+
+        check_range(&perlin, grid(60).map(|(x, _)| x));
+        check_range(&perlin, grid(60).map(|(x, y)| Vector2::build(x, y)));
+        check_range(&perlin, grid(60).map(|(x, y)| Vector3::build(x, y, x - y)));
+        check_range(&perlin, grid(60).map(|(x, y)| Vector4::build(x, y, y * 0.5, x * 0.3)));
+        check_range(&simplex, grid(60).map(|(x, _)| x));
+        check_range(&simplex, grid(60).map(|(x, y)| Vector2::build(x, y)));
+        check_range(&simplex, grid(60).map(|(x, y)| Vector3::build(x, y, x - y)));
+        check_range(&simplex, grid(60).map(|(x, y)| Vector4::build(x, y, y * 0.5, x * 0.3)));
+
+        assert!(perlin.sample(Vector3::build(3., 4., 5.)) == 0.);
+        assert!(Perlin::periodic(1, 0) == perlin);
+        assert!(Perlin::build(1).sample(1.5) == perlin.sample(1.5));
+        assert!(Perlin::build(2).sample(Vector2::build(1.5, 0.5)) != perlin.sample(Vector2::build(1.5, 0.5)));
+    }
+
+    #[test]
+    fn opensimplex_continuity() {
+        let simplex = OpenSimplex::build(8);
+
+        // the staggered lattices must line up, so small steps only move the value a little
+        let line3 = |t: f32| simplex.sample(Vector3::build(t, t * 0.7 - 3., 1. - t * 0.4));
+        let line4 = |t: f32| simplex.sample(Vector4::build(t * 0.3, t, 2. - t * 0.8, t * 0.6));
+        (1..20_000).for_each(|i| {
+            let t = i as f32 * 1e-3;
+            assert!((line3(t) - line3(t - 1e-3)).abs() < 0.05);
+            assert!((line4(t) - line4(t - 1e-3)).abs() < 0.05);
+        });
+    }
+
+    #[test]
+    fn worley_features() {
+        let mut worley = Worley::build(9);
+        let point = Vector2::build(3.3, -7.6);
+        let f1 = worley.sample(point);
+        worley.feature = Feature::F2;
+        let f2 = worley.sample(point);
+        worley.feature = Feature::F2MinusF1;
+        assert!(f1 <= f2 && worley.sample(point) == f2 - f1);
+        assert!(f1 < 1.5);
+    }
+
+    #[test]
+    fn fractal_and_warp() {
+        let fbm = Fbm(Fractal::build(Perlin::build(4), 5));
+        let ridged = Ridged(Fractal::build(OpenSimplex::build(4), 5));
+        let turbulence = Turbulence(Fractal::build(Perlin::build(4), 5));
+        let warp = Warp::build(fbm, OpenSimplex::build(5), 0.8);
+
+        grid(30).for_each(|(x, y)| {
+            let point = Vector2::build(x, y);
+            assert!((-1. ..=1.).contains(&fbm.sample(point)));
+            assert!((0. ..=1.).contains(&ridged.sample(point)));
+            assert!((0. ..=1.).contains(&turbulence.sample(point)));
+            assert!((-1. ..=1.).contains(&warp.sample(point)));
+        });
+    }
+
+    #[test]
+    fn tileable_fill() {
+        let fbm = Fbm(Fractal::build(Perlin::periodic(3, 4), 3));
+        let mut buffer = Buffer2::new(32, 16, 0.);
+        fill_buffer2(&mut buffer, &fbm, 4.);
+
+        (0..16).for_each(|y| {
+            let wrapped = fbm.sample(Vector2::build(4., y as f32 * 4. / 16.));
+            assert!((buffer.get_unchecked(0, y) - wrapped).abs() < 1e-5);
+        });
+
+        let worley = Worley::periodic(3, 2);
+        let mut volume = Buffer3::new(8, 8, 8, 0.);
+        fill_buffer3(&mut volume, &worley, 2.);
+        let wrapped = worley.sample(Vector3::build(2., 0.25, 2.5));
+        assert!((volume.get_unchecked(0, 1, 2) - wrapped).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,607 @@
+use super::traits::FloatNumber;
+use super::vector::Vector2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    Collinear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Intersection<T> {
+    Point(Vector2<T>),
+    Overlap(Vector2<T>, Vector2<T>),
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PREDICATES */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn orientation<T>(a: Vector2<T>, b: Vector2<T>, c: Vector2<T>) -> Orientation
+where
+    T: FloatNumber<T>,
+{
+    classify((b - a) % (c - a))
+}
+
+pub fn orient2d(a: Vector2<f64>, b: Vector2<f64>, c: Vector2<f64>) -> f64 {
+    const BOUND: f64 = (3. + 16. * f64::EPSILON / 2.) * f64::EPSILON / 2.;
+
+    let left = (a.x - c.x) * (b.y - c.y);
+    let right = (a.y - c.y) * (b.x - c.x);
+    let det = left - right;
+    if det.abs() >= BOUND * (left.abs() + right.abs()) {
+        return det;
+    }
+
+    let mut expansion = Vec::with_capacity(24);
+    let terms = [
+        (a.x, b.y),
+        (-a.x, c.y),
+        (-a.y, b.x),
+        (a.y, c.x),
+        (b.x, c.y),
+        (-b.y, c.x),
+    ];
+    terms.iter().for_each(|&(p, q)| {
+        let (product, error) = two_product(p, q);
+        grow_expansion(&mut expansion, error);
+        grow_expansion(&mut expansion, product);
+    });
+
+    expansion.iter().rev().find(|&&component| component != 0.).copied().unwrap_or(0.)
+}
+
+pub fn robust_orientation(a: Vector2<f64>, b: Vector2<f64>, c: Vector2<f64>) -> Orientation {
+    classify(orient2d(a, b, c))
+}
+
+pub fn in_circumcircle<T>(a: Vector2<T>, b: Vector2<T>, c: Vector2<T>, point: Vector2<T>) -> bool
+where
+    T: FloatNumber<T>,
+{
+    let (a, b, c) = (a - point, b - point, c - point);
+    let (la, lb, lc) = (a.x * a.x + a.y * a.y, b.x * b.x + b.y * b.y, c.x * c.x + c.y * c.y);
+
+    let det = a.x * (b.y * lc - lb * c.y) - a.y * (b.x * lc - lb * c.x) + la * (b.x * c.y - b.y * c.x);
+    match orientation(a, b, c) {
+        | Orientation::CounterClockwise => det > T::zero(),
+        | Orientation::Clockwise => det < T::zero(),
+        | Orientation::Collinear => false,
+    }
+}
+
+pub fn incircle(a: Vector2<f64>, b: Vector2<f64>, c: Vector2<f64>, point: Vector2<f64>) -> f64 {
+    const BOUND: f64 = (10. + 96. * f64::EPSILON / 2.) * f64::EPSILON / 2.;
+
+    let (adx, ady, bdx, bdy) = (a.x - point.x, a.y - point.y, b.x - point.x, b.y - point.y);
+    let (cdx, cdy) = (c.x - point.x, c.y - point.y);
+    let (bdxcdy, cdxbdy, cdxady, adxcdy, adxbdy, bdxady) =
+        (bdx * cdy, cdx * bdy, cdx * ady, adx * cdy, adx * bdy, bdx * ady);
+    let (alift, blift, clift) = (adx * adx + ady * ady, bdx * bdx + bdy * bdy, cdx * cdx + cdy * cdy);
+
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    if det.abs() > BOUND * permanent {
+        return det;
+    }
+
+    // the differences themselves round, so redo everything on exact two component expansions
+    let difference = |p: f64, q: f64| {
+        let (sum, error) = two_sum(p, -q);
+        [error, sum]
+    };
+    let [adx, ady, bdx, bdy, cdx, cdy] = [
+        (a.x, point.x),
+        (a.y, point.y),
+        (b.x, point.x),
+        (b.y, point.y),
+        (c.x, point.x),
+        (c.y, point.y),
+    ]
+    .map(|(p, q)| difference(p, q));
+    let lift = |x: &[f64], y: &[f64]| sum_expansion(multiply_expansion(x, x), &multiply_expansion(y, y));
+    let cross = |x1: &[f64], y1: &[f64], x2: &[f64], y2: &[f64]| {
+        let negated: Vec<f64> = multiply_expansion(y1, x2).iter().map(|&component| -component).collect();
+        sum_expansion(multiply_expansion(x1, y2), &negated)
+    };
+
+    let mut expansion = multiply_expansion(&lift(&adx, &ady), &cross(&bdx, &bdy, &cdx, &cdy));
+    expansion = sum_expansion(expansion, &multiply_expansion(&lift(&bdx, &bdy), &cross(&cdx, &cdy, &adx, &ady)));
+    expansion = sum_expansion(expansion, &multiply_expansion(&lift(&cdx, &cdy), &cross(&adx, &ady, &bdx, &bdy)));
+
+    expansion.iter().rev().find(|&&component| component != 0.).copied().unwrap_or(0.)
+}
+
+pub fn robust_in_circumcircle(a: Vector2<f64>, b: Vector2<f64>, c: Vector2<f64>, point: Vector2<f64>) -> bool {
+    match robust_orientation(a, b, c) {
+        | Orientation::CounterClockwise => incircle(a, b, c, point) > 0.,
+        | Orientation::Clockwise => incircle(a, b, c, point) < 0.,
+        | Orientation::Collinear => false,
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* POLYGON */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn signed_area<T>(polygon: &[Vector2<T>]) -> T
+where
+    T: FloatNumber<T>,
+{
+    let twice = edges(polygon).fold(T::zero(), |sum, (a, b)| sum + a % b);
+    twice * T::constant(0.5)
+}
+
+pub fn area<T>(polygon: &[Vector2<T>]) -> T
+where
+    T: FloatNumber<T>,
+{
+    signed_area(polygon).abs()
+}
+
+pub fn winding<T>(polygon: &[Vector2<T>]) -> Orientation
+where
+    T: FloatNumber<T>,
+{
+    classify(signed_area(polygon))
+}
+
+pub fn centroid<T>(polygon: &[Vector2<T>]) -> Option<Vector2<T>>
+where
+    T: FloatNumber<T>,
+{
+    let area = signed_area(polygon);
+    if area == T::zero() {
+        return None;
+    }
+
+    let sum = edges(polygon).fold(Vector2::zeros(), |sum, (a, b)| sum + (a + b) * (a % b));
+    Some(sum / (T::constant(6.) * area))
+}
+
+pub fn contains<T>(polygon: &[Vector2<T>], point: Vector2<T>) -> bool
+where
+    T: FloatNumber<T>,
+{
+    let winding = edges(polygon).fold(0, |winding, (a, b)| {
+        let side = (b - a) % (point - a);
+        match (a.y <= point.y, b.y <= point.y) {
+            | (true, false) if side > T::zero() => winding + 1,
+            | (false, true) if side < T::zero() => winding - 1,
+            | _ => winding,
+        }
+    });
+
+    winding != 0
+}
+
+pub fn is_convex<T>(polygon: &[Vector2<T>]) -> bool
+where
+    T: FloatNumber<T>,
+{
+    let n = polygon.len();
+    let mut turn = None;
+
+    (0..n).all(|i| match orientation(polygon[i], polygon[(i + 1) % n], polygon[(i + 2) % n]) {
+        | Orientation::Collinear => true,
+        | side => *turn.get_or_insert(side) == side,
+    })
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SEGMENTS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn segment_intersection<T>(
+    a0: Vector2<T>,
+    a1: Vector2<T>,
+    b0: Vector2<T>,
+    b1: Vector2<T>,
+) -> Option<Intersection<T>>
+where
+    T: FloatNumber<T>,
+{
+    let (r, s) = (a1 - a0, b1 - b0);
+    let offset = b0 - a0;
+    let denom = r % s;
+    let (zero, one) = (T::zero(), T::one());
+
+    if denom != zero {
+        let t = offset % s / denom;
+        let u = offset % r / denom;
+        if t < zero || t > one || u < zero || u > one {
+            return None;
+        }
+
+        return Some(Intersection::Point(a0 + r * t));
+    }
+
+    if offset % r != zero {
+        return None;
+    }
+
+    let length = r * r;
+    if length == zero {
+        return match b0 == a0 || (s * s != zero && (a0 - b0) % s == zero && on_segment(b0, b1, a0)) {
+            | true => Some(Intersection::Point(a0)),
+            | false => None,
+        };
+    }
+
+    let t0 = offset * r / length;
+    let t1 = (b1 - a0) * r / length;
+    let (low, high) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+    let (low, high) = (max(low, zero), min(high, one));
+
+    match low.partial_cmp(&high) {
+        | Some(std::cmp::Ordering::Less) => Some(Intersection::Overlap(a0 + r * low, a0 + r * high)),
+        | Some(std::cmp::Ordering::Equal) => Some(Intersection::Point(a0 + r * low)),
+        | _ => None,
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* CONVEX HULL */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn convex_hull<T>(points: &[Vector2<T>]) -> Vec<Vector2<T>>
+where
+    T: FloatNumber<T>,
+{
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let mut hull: Vec<Vector2<T>> = Vec::with_capacity(sorted.len() * 2);
+    let chain = |point: Vector2<T>, floor: usize, hull: &mut Vec<Vector2<T>>| {
+        while hull.len() >= floor + 2
+            && orientation(hull[hull.len() - 2], hull[hull.len() - 1], point) != Orientation::CounterClockwise
+        {
+            hull.pop();
+        }
+        hull.push(point);
+    };
+
+    sorted.iter().for_each(|&point| chain(point, 0, &mut hull));
+    let floor = hull.len() - 1;
+    sorted.iter().rev().skip(1).for_each(|&point| chain(point, floor, &mut hull));
+
+    hull.pop();
+    hull
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TRIANGULATION */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// None when no ear can be clipped, which happens for self-intersecting or otherwise invalid polygons
+pub fn triangulate<T>(polygon: &[Vector2<T>]) -> Option<Vec<[usize; 3]>>
+where
+    T: FloatNumber<T>,
+{
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    if winding(polygon) == Orientation::Clockwise {
+        remaining.reverse();
+    }
+
+    let mut triangles = Vec::with_capacity(polygon.len().saturating_sub(2));
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (prev, curr, next) = (remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]);
+            let (a, b, c) = (polygon[prev], polygon[curr], polygon[next]);
+            if orientation(a, b, c) != Orientation::CounterClockwise {
+                return false;
+            }
+
+            remaining
+                .iter()
+                .filter(|&&other| other != prev && other != curr && other != next)
+                .all(|&other| !in_triangle(a, b, c, polygon[other]))
+        });
+
+        let i = ear?;
+        triangles.push([remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]]);
+        remaining.remove(i);
+    }
+
+    if remaining.len() == 3 {
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+    }
+    Some(triangles)
+}
+
+pub fn delaunay<T>(points: &[Vector2<T>]) -> Vec<[usize; 3]>
+where
+    T: FloatNumber<T>,
+{
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    let (mut low, mut high) = (points[0], points[0]);
+    points.iter().for_each(|point| {
+        low = Vector2::build(min(low.x, point.x), min(low.y, point.y));
+        high = Vector2::build(max(high.x, point.x), max(high.y, point.y));
+    });
+
+    let size = max(max(high.x - low.x, high.y - low.y), T::one());
+    let mid = (low + high) * T::constant(0.5);
+    let reach = size * T::constant(20.);
+
+    let mut vertices = points.to_vec();
+    vertices.push(Vector2::build(mid.x - reach, mid.y - size));
+    vertices.push(Vector2::build(mid.x + reach, mid.y - size));
+    vertices.push(Vector2::build(mid.x, mid.y + reach));
+
+    // the insertion test runs on the exact predicate, near cocircular points would otherwise tangle the mesh
+    let exact: Vec<Vector2<f64>> =
+        vertices.iter().map(|vertex| Vector2::build(vertex.x.to_f64(), vertex.y.to_f64())).collect();
+
+    let mut triangles = vec![[n, n + 1, n + 2]];
+    (0..n).for_each(|index| {
+        let point = exact[index];
+        let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) = triangles
+            .iter()
+            .partition(|&&[a, b, c]| robust_in_circumcircle(exact[a], exact[b], exact[c], point));
+
+        let mut boundary: Vec<(usize, usize)> = Vec::new();
+        bad.iter().for_each(|&[a, b, c]| {
+            [(a, b), (b, c), (c, a)].into_iter().for_each(|(u, v)| {
+                match boundary.iter().position(|&edge| edge == (v, u)) {
+                    | Some(shared) => _ = boundary.swap_remove(shared),
+                    | None => boundary.push((u, v)),
+                }
+            });
+        });
+
+        triangles = good;
+        triangles.extend(boundary.into_iter().map(|(u, v)| [u, v, index]));
+    });
+
+    triangles.retain(|triangle| triangle.iter().all(|&vertex| vertex < n));
+    triangles
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* UTILS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+fn classify<T>(value: T) -> Orientation
+where
+    T: FloatNumber<T>,
+{
+    match value {
+        | v if v > T::zero() => Orientation::CounterClockwise,
+        | v if v < T::zero() => Orientation::Clockwise,
+        | _ => Orientation::Collinear,
+    }
+}
+
+fn edges<T>(polygon: &[Vector2<T>]) -> impl Iterator<Item = (Vector2<T>, Vector2<T>)> + '_
+where
+    T: FloatNumber<T>,
+{
+    (0..polygon.len()).map(|i| (polygon[i], polygon[(i + 1) % polygon.len()]))
+}
+
+fn in_triangle<T>(a: Vector2<T>, b: Vector2<T>, c: Vector2<T>, point: Vector2<T>) -> bool
+where
+    T: FloatNumber<T>,
+{
+    let zero = T::zero();
+    (b - a) % (point - a) >= zero && (c - b) % (point - b) >= zero && (a - c) % (point - c) >= zero
+}
+
+fn on_segment<T>(start: Vector2<T>, end: Vector2<T>, point: Vector2<T>) -> bool
+where
+    T: FloatNumber<T>,
+{
+    let t = (point - start) * (end - start);
+    t >= T::zero() && t <= (end - start) * (end - start)
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let virtual_b = sum - a;
+    let virtual_a = sum - virtual_b;
+
+    (sum, (a - virtual_a) + (b - virtual_b))
+}
+
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    (product, a.mul_add(b, -product))
+}
+
+fn grow_expansion(expansion: &mut Vec<f64>, value: f64) {
+    let mut carry = value;
+    expansion.iter_mut().for_each(|component| {
+        let (sum, error) = two_sum(carry, *component);
+        *component = error;
+        carry = sum;
+    });
+    expansion.push(carry);
+}
+
+fn sum_expansion(mut expansion: Vec<f64>, other: &[f64]) -> Vec<f64> {
+    other.iter().for_each(|&value| grow_expansion(&mut expansion, value));
+    expansion.retain(|&component| component != 0.);
+    expansion
+}
+
+fn multiply_expansion(left: &[f64], right: &[f64]) -> Vec<f64> {
+    let mut expansion = Vec::with_capacity(2 * left.len() * right.len());
+    left.iter().for_each(|&p| {
+        right.iter().for_each(|&q| {
+            let (product, error) = two_product(p, q);
+            grow_expansion(&mut expansion, error);
+            grow_expansion(&mut expansion, product);
+        });
+    });
+    expansion.retain(|&component| component != 0.);
+    expansion
+}
+
+fn min<T>(a: T, b: T) -> T
+where
+    T: PartialOrd,
+{
+    if b < a { b } else { a }
+}
+
+fn max<T>(a: T, b: T) -> T
+where
+    T: PartialOrd,
+{
+    if b > a { b } else { a }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::random::Rng;
+    use crate::math::random::Xoshiro256;
+
+    fn v(x: f64, y: f64) -> Vector2<f64> {
+        Vector2::build(x, y)
+    }
+
+    #[test]
+    fn hull_and_polygon() {
+        let mut points = vec![v(0., 0.), v(4., 0.), v(4., 4.), v(0., 4.), v(2., 0.)];
+        points.extend([v(1., 1.), v(2., 3.), v(3., 2.)]);
+
+        let hull = convex_hull(&points);
+        assert!(hull == vec![v(0., 0.), v(4., 0.), v(4., 4.), v(0., 4.)]);
+        assert!(signed_area(&hull) == 16. && winding(&hull) == Orientation::CounterClockwise);
+        assert!(centroid(&hull) == Some(v(2., 2.)) && is_convex(&hull));
+
+        let shape = [v(0., 0.), v(0., 2.), v(1., 2.), v(1., 1.), v(2., 1.), v(2., 0.)];
+        assert!(area(&shape) == 3. && winding(&shape) == Orientation::Clockwise && !is_convex(&shape));
+        assert!(contains(&shape, v(0.5, 1.5)) && contains(&shape, v(1.5, 0.5)));
+        assert!(!contains(&shape, v(1.5, 1.5)) && !contains(&shape, v(-0.5, 0.5)));
+    }
+
+    #[test]
+    fn segments() {
+        let cross = segment_intersection(v(0., 0.), v(2., 2.), v(0., 2.), v(2., 0.));
+        assert!(cross == Some(Intersection::Point(v(1., 1.))));
+        assert!(segment_intersection(v(0., 0.), v(1., 0.), v(0., 1.), v(1., 1.)).is_none());
+        assert!(segment_intersection(v(0., 0.), v(1., 1.), v(2., 0.), v(1.5, 0.4)).is_none());
+
+        let overlap = segment_intersection(v(0., 0.), v(4., 0.), v(5., 0.), v(2., 0.));
+        assert!(overlap == Some(Intersection::Overlap(v(2., 0.), v(4., 0.))));
+        let touch = segment_intersection(v(0., 0.), v(2., 0.), v(2., 0.), v(3., 0.));
+        assert!(touch == Some(Intersection::Point(v(2., 0.))));
+    }
+
+    #[test]
+    fn triangulation() {
+        let shape = [
+            v(0., 0.),
+            v(3., 0.),
+            v(3., 3.),
+            v(2., 3.),
+            v(2., 1.),
+            v(1., 1.),
+            v(1., 3.),
+            v(0., 3.),
+        ];
+        let triangles = triangulate(&shape).unwrap();
+        let total: f64 = triangles.iter().map(|&[a, b, c]| area(&[shape[a], shape[b], shape[c]])).sum();
+        assert!(triangles.len() == shape.len() - 2 && (total - area(&shape)).abs() < 1e-12);
+        assert!(triangulate(&[v(0., 0.), v(1., 0.), v(2., 0.), v(3., 0.)]).is_none());
+
+        let mut rng = Xoshiro256::build(5);
+        let points: Vec<Vector2<f64>> = (0..60).map(|_| v(rng.uniform(0., 10.), rng.uniform(0., 10.))).collect();
+        let mesh = delaunay(&points);
+        assert!(mesh.len() == 2 * points.len() - 2 - convex_hull(&points).len());
+        mesh.iter().for_each(|&[a, b, c]| {
+            assert!(orientation(points[a], points[b], points[c]) == Orientation::CounterClockwise);
+            assert!((0..points.len()).all(|i| !in_circumcircle(points[a], points[b], points[c], points[i])));
+        });
+    }
+
+    #[test]
+    fn robust_predicate() {
+        let ulp = f64::EPSILON / 2.;
+        let (q, r) = (v(12., 12.), v(24., 24.));
+
+        (0..16).for_each(|x| {
+            (0..16).for_each(|y| {
+                let p = v(0.5 + x as f64 * ulp, 0.5 + y as f64 * ulp);
+                let expected = classify((y - x) as f64);
+                assert!(robust_orientation(p, q, r) == expected);
+            });
+        });
+
+        // a point pushed k ulps off the circle through three exactly cocircular ones, inside iff k > 0
+        let (a, b, c) = (v(13.25, 12.5), v(12.5, 13.25), v(11.75, 12.5));
+        (-8..8).for_each(|k| {
+            let point = v(12.5, 11.75 + k as f64 * 16. * ulp);
+            assert!(robust_in_circumcircle(a, b, c, point) == (k > 0));
+            assert!(incircle(a, b, c, point).signum() == (k as f64).signum() || k == 0);
+        });
+        assert!(incircle(a, b, c, v(12.5, 11.75)) == 0.);
+
+        // near cocircular points where the plain determinant rounds to the wrong sign
+        let hard = [
+            (
+                [
+                    0.7442608015705692,
+                    0.7716403530691703,
+                    -0.13485208481208377,
+                    0.5842258045392483,
+                ],
+                1.,
+            ),
+            (
+                [
+                    0.6327940305672639,
+                    0.3971004568857936,
+                    0.5557305161702637,
+                    0.32972725849815715,
+                ],
+                -1.,
+            ),
+            (
+                [
+                    -0.01760374760057498,
+                    1.0187912475430752,
+                    -0.11730973660160937,
+                    0.8683822548177076,
+                ],
+                -1.,
+            ),
+        ];
+        let others = [
+            [
+                0.7175078521620629,
+                0.5321095792398476,
+                -0.030357285634825204,
+                1.0055553367689567,
+            ],
+            [
+                -0.14975203037284862,
+                0.6850630935096909,
+                -0.08145168425986038,
+                0.46126455525976917,
+            ],
+            [
+                -0.1493694289318256,
+                0.676185795384163,
+                0.379992217622202,
+                0.25716679763156614,
+            ],
+        ];
+        hard.iter().zip(others).for_each(|(&([ax, ay, bx, by], sign), [cx, cy, px, py])| {
+            assert!(incircle(v(ax, ay), v(bx, by), v(cx, cy), v(px, py)).signum() == sign);
+        });
+    }
+}
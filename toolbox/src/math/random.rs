@@ -0,0 +1,753 @@
+use super::traits::FloatNumber;
+use super::vector::BasicVectorOps;
+use super::vector::FloatVectorOps;
+use super::vector::Vector2;
+use super::vector::Vector3;
+
+// every sampler is built from integer math, IEEE arithmetic, sqrt and the bundled ln/exp below, so a seed
+// replays bit-for-bit on every platform
+pub trait Rng
+where
+    Self: Sized,
+{
+    fn next_u64(&mut self) -> u64;
+
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1. / (1u64 << 53) as f64)
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 * (1. / (1u32 << 24) as f32)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            return 0;
+        }
+
+        let mut wide = self.next_u64() as u128 * bound as u128;
+        if (wide as u64) < bound {
+            let threshold = bound.wrapping_neg() % bound;
+            while (wide as u64) < threshold {
+                wide = self.next_u64() as u128 * bound as u128;
+            }
+        }
+
+        (wide >> 64) as u64
+    }
+
+    fn range_usize(&mut self, low: usize, high: usize) -> usize {
+        low + self.below(high.saturating_sub(low) as u64) as usize
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
+    fn uniform<T>(&mut self, low: T, high: T) -> T
+    where
+        T: FloatNumber<T>,
+    {
+        low + (high - low) * T::constant(self.next_f64())
+    }
+
+    fn normal<T>(&mut self, mean: T, deviation: T) -> T
+    where
+        T: FloatNumber<T>,
+    {
+        let table = &NORMAL_ZIGGURAT;
+        let z = loop {
+            let bits = self.next_u64();
+            let (layer, magnitude) = ((bits & 0xff) as usize, bits >> 12);
+            let sign = match bits & 0x100 {
+                | 0 => 1.,
+                | _ => -1.,
+            };
+            if magnitude < table.threshold[layer] {
+                break sign * magnitude as f64 * table.width[layer];
+            }
+
+            if layer == 0 {
+                // marsaglia's tail algorithm beyond the base strip
+                loop {
+                    let a = -ln(1. - self.next_f64()) / NORMAL_TAIL;
+                    let b = -ln(1. - self.next_f64());
+                    if b + b > a * a {
+                        return mean + deviation * T::constant(sign * (NORMAL_TAIL + a));
+                    }
+                }
+            }
+
+            let x = magnitude as f64 * table.width[layer];
+            let (low, high) = (table.density[layer], table.density[layer + 1]);
+            if low + (high - low) * self.next_f64() < exp(-0.5 * x * x) {
+                break sign * x;
+            }
+        };
+        mean + deviation * T::constant(z)
+    }
+
+    fn exponential<T>(&mut self, rate: T) -> T
+    where
+        T: FloatNumber<T>,
+    {
+        let table = &EXPONENTIAL_ZIGGURAT;
+        let mut offset = 0.;
+        let x = loop {
+            let bits = self.next_u64();
+            let (layer, magnitude) = ((bits & 0xff) as usize, bits >> 11);
+            if magnitude < table.threshold[layer] {
+                break magnitude as f64 * table.width[layer];
+            }
+
+            // the tail of an exponential is another exponential shifted by the tail start
+            if layer == 0 {
+                offset += EXPONENTIAL_TAIL;
+                continue;
+            }
+
+            let x = magnitude as f64 * table.width[layer];
+            let (low, high) = (table.density[layer], table.density[layer + 1]);
+            if low + (high - low) * self.next_f64() < exp(-x) {
+                break x;
+            }
+        };
+        T::constant(offset + x) / rate
+    }
+
+    fn poisson(&mut self, mean: f64) -> u64 {
+        if mean <= 0. {
+            return 0;
+        }
+
+        if mean < 30. {
+            let limit = exp(-mean);
+            let mut product = self.next_f64();
+            let mut count = 0;
+            while product > limit {
+                product *= self.next_f64();
+                count += 1;
+            }
+
+            return count;
+        }
+
+        // hormann's transformed rejection (PTRS)
+        let sqrt_mean = mean.sqrt();
+        let ln_mean = ln(mean);
+        let b = 0.931 + 2.53 * sqrt_mean;
+        let a = -0.059 + 0.02483 * b;
+        let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+        let vr = 0.9277 - 3.6224 / (b - 2.);
+
+        loop {
+            let u = self.next_f64() - 0.5;
+            let v = self.next_f64();
+            let us = 0.5 - u.abs();
+            let k = ((2. * a / us + b) * u + mean + 0.43).floor();
+
+            if us >= 0.07 && v <= vr {
+                return k as u64;
+            }
+            if k < 0. || (us < 0.013 && v > us) {
+                continue;
+            }
+            if ln(v) + ln(inv_alpha) - ln(a / (us * us) + b) <= -mean + k * ln_mean - ln_factorial(k) {
+                return k as u64;
+            }
+        }
+    }
+
+    fn shuffle<D>(&mut self, data: &mut [D]) {
+        (1..data.len()).rev().for_each(|i| {
+            let j = self.below(i as u64 + 1) as usize;
+            data.swap(i, j);
+        });
+    }
+
+    fn choose<'d, D>(&mut self, data: &'d [D]) -> Option<&'d D> {
+        if data.is_empty() {
+            return None;
+        }
+
+        data.get(self.below(data.len() as u64) as usize)
+    }
+
+    fn weighted_choice<T>(&mut self, weights: &[T]) -> Option<usize>
+    where
+        T: FloatNumber<T>,
+    {
+        let mut total = T::zero();
+        weights.iter().filter(|&&weight| weight > T::zero()).for_each(|&weight| total += weight);
+        if total <= T::zero() {
+            return None;
+        }
+
+        let mut target = total * T::constant(self.next_f64());
+        let mut last = None;
+        for (index, &weight) in weights.iter().enumerate() {
+            if weight <= T::zero() {
+                continue;
+            }
+            if target < weight {
+                return Some(index);
+            }
+
+            target -= weight;
+            last = Some(index);
+        }
+
+        last
+    }
+
+    fn in_unit_disc<T>(&mut self) -> Vector2<T>
+    where
+        T: FloatNumber<T>,
+    {
+        loop {
+            let point = Vector2::build(self.uniform(-T::one(), T::one()), self.uniform(-T::one(), T::one()));
+            if point.squared_length() < T::one() {
+                return point;
+            }
+        }
+    }
+
+    fn on_unit_circle<T>(&mut self) -> Vector2<T>
+    where
+        T: FloatNumber<T>,
+    {
+        loop {
+            let point = self.in_unit_disc::<T>();
+            if point.squared_length() > T::zero() {
+                return point.normalize();
+            }
+        }
+    }
+
+    fn in_unit_sphere<T>(&mut self) -> Vector3<T>
+    where
+        T: FloatNumber<T>,
+    {
+        loop {
+            let point = Vector3::build(
+                self.uniform(-T::one(), T::one()),
+                self.uniform(-T::one(), T::one()),
+                self.uniform(-T::one(), T::one()),
+            );
+            if point.squared_length() < T::one() {
+                return point;
+            }
+        }
+    }
+
+    fn on_unit_sphere<T>(&mut self) -> Vector3<T>
+    where
+        T: FloatNumber<T>,
+    {
+        // marsaglia's disc projection, avoids trig so it stays reproducible
+        let point = self.in_unit_disc::<T>();
+        let s = point.squared_length();
+        let scale = T::constant(2.) * (T::one() - s).sqrt();
+
+        Vector3::build(point.x * scale, point.y * scale, T::one() - T::constant(2.) * s)
+    }
+
+    fn on_hemisphere<T>(&mut self, normal: Vector3<T>) -> Vector3<T>
+    where
+        T: FloatNumber<T>,
+    {
+        let point = self.on_unit_sphere::<T>();
+        match point * normal < T::zero() {
+            | true => -point,
+            | false => point,
+        }
+    }
+
+    fn cosine_hemisphere<T>(&mut self, normal: Vector3<T>) -> Vector3<T>
+    where
+        T: FloatNumber<T>,
+    {
+        loop {
+            let direction = normal.normalize() + self.on_unit_sphere::<T>();
+            if direction.squared_length() > T::constant(1e-12) {
+                return direction.normalize();
+            }
+        }
+    }
+}
+
+fn ln_factorial(k: f64) -> f64 {
+    if k < 10. {
+        let mut out = 0.;
+        (2..=k as u64).for_each(|i| out += ln(i as f64));
+        return out;
+    }
+
+    let x = k + 1.;
+    let inv = 1. / x;
+    let inv2 = inv * inv;
+    let series = inv * (1. / 12. - inv2 * (1. / 360. - inv2 / 1260.));
+
+    (x - 0.5) * ln(x) - x + HALF_LN_TAU + series
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PORTABLE MATH */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// fdlibm's ln and exp, which only use IEEE basic operations and so round identically everywhere
+const LN2_HI: f64 = 6.931_471_803_691_238e-1;
+const LN2_LO: f64 = 1.908_214_929_270_587_7e-10;
+const HALF_LN_TAU: f64 = 0.918_938_533_204_672_8;
+
+pub const fn ln(x: f64) -> f64 {
+    const LG: [f64; 7] = [
+        6.666_666_666_666_735e-1,
+        3.999_999_999_940_942e-1,
+        2.857_142_874_366_239e-1,
+        2.222_219_843_214_978_4e-1,
+        1.818_357_216_161_805e-1,
+        1.531_383_769_920_937_3e-1,
+        1.479_819_860_511_658_6e-1,
+    ];
+
+    let (mut x, mut bits, mut k) = (x, x.to_bits(), 0i64);
+    let mut high = (bits >> 32) as u32;
+    if high < 0x0010_0000 || high >> 31 != 0 {
+        if bits << 1 == 0 {
+            return f64::NEG_INFINITY;
+        }
+        if high >> 31 != 0 {
+            return f64::NAN;
+        }
+
+        k -= 54;
+        x *= 18_014_398_509_481_984.;
+        bits = x.to_bits();
+        high = (bits >> 32) as u32;
+    } else if high >= 0x7ff0_0000 {
+        return x;
+    } else if high == 0x3ff0_0000 && bits << 32 == 0 {
+        return 0.;
+    }
+
+    high += 0x3ff0_0000 - 0x3fe6_a09e;
+    k += (high >> 20) as i64 - 0x3ff;
+    high = (high & 0x000f_ffff) + 0x3fe6_a09e;
+    let x = f64::from_bits(((high as u64) << 32) | (bits & 0xffff_ffff));
+
+    let f = x - 1.;
+    let half_square = 0.5 * f * f;
+    let s = f / (2. + f);
+    let (z, dk) = (s * s, k as f64);
+    let w = z * z;
+    let odd = w * (LG[1] + w * (LG[3] + w * LG[5]));
+    let even = z * (LG[0] + w * (LG[2] + w * (LG[4] + w * LG[6])));
+    s * (half_square + even + odd) + dk * LN2_LO - half_square + f + dk * LN2_HI
+}
+
+pub const fn exp(x: f64) -> f64 {
+    const P: [f64; 5] = [
+        1.666_666_666_666_660_2e-1,
+        -2.777_777_777_701_559_3e-3,
+        6.613_756_321_437_934e-5,
+        -1.653_390_220_546_525_2e-6,
+        4.138_136_797_057_238_5e-8,
+    ];
+
+    if x.is_nan() {
+        return x;
+    }
+    if x > 709.782_712_893_384 {
+        return f64::INFINITY;
+    }
+    if x < -745.133_219_101_941_1 {
+        return 0.;
+    }
+
+    let high = ((x.to_bits() >> 32) as u32) & 0x7fff_ffff;
+    if high <= 0x3e30_0000 {
+        return 1. + x;
+    }
+
+    let sign = match x < 0. {
+        | true => -1.,
+        | false => 1.,
+    };
+    let (k, hi, lo) = match (high > 0x3fd6_2e42, high >= 0x3ff0_a2b2) {
+        | (false, _) => (0, x, 0.),
+        | (true, reduce) => {
+            let k = match reduce {
+                | true => (std::f64::consts::LOG2_E * x + 0.5 * sign) as i64,
+                | false => sign as i64,
+            };
+            (k, x - k as f64 * LN2_HI, k as f64 * LN2_LO)
+        }
+    };
+
+    let x = hi - lo;
+    let square = x * x;
+    let c = x - square * (P[0] + square * (P[1] + square * (P[2] + square * (P[3] + square * P[4]))));
+    let y = 1. + (x * c / (2. - c) - lo + hi);
+    scale_by_power_of_two(y, k)
+}
+
+const fn scale_by_power_of_two(mut y: f64, mut n: i64) -> f64 {
+    if n > 1023 {
+        y *= f64::from_bits(0x7fe0_0000_0000_0000);
+        n -= 1023;
+        if n > 1023 {
+            n = 1023;
+        }
+    } else if n < -1022 {
+        y *= f64::from_bits(0x0360_0000_0000_0000);
+        n += 1022 - 53;
+        if n < -1022 {
+            y *= f64::from_bits(0x0360_0000_0000_0000);
+            n += 1022 - 53;
+            if n < -1022 {
+                n = -1022;
+            }
+        }
+    }
+    y * f64::from_bits(((0x3ff + n) as u64) << 52)
+}
+
+const fn newton_sqrt(x: f64) -> f64 {
+    if x <= 0. {
+        return 0.;
+    }
+
+    let mut y = f64::from_bits((x.to_bits() >> 1) + 0x1ff8_0000_0000_0000);
+    let mut i = 0;
+    while i < 8 {
+        y = 0.5 * (y + x / y);
+        i += 1;
+    }
+    y
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* ZIGGURAT */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// marsaglia and tsang's 256 layer tables, generated at compile time from the tail start and layer area
+const NORMAL_TAIL: f64 = 3.654_152_885_361_009;
+const EXPONENTIAL_TAIL: f64 = 7.697_117_470_131_05;
+const NORMAL_ZIGGURAT: Ziggurat = Ziggurat::build(NORMAL_TAIL, 4.928_673_233_99e-3, 52, true);
+const EXPONENTIAL_ZIGGURAT: Ziggurat = Ziggurat::build(EXPONENTIAL_TAIL, 3.949_659_822_581_572e-3, 53, false);
+
+struct Ziggurat {
+    threshold: [u64; 256],
+    width: [f64; 256],
+    density: [f64; 257],
+}
+
+impl Ziggurat {
+    const fn build(tail: f64, area: f64, bits: u32, normal: bool) -> Self {
+        let scale = (1u64 << bits) as f64;
+
+        let mut edges = [0.; 257];
+        edges[0] = area / Self::density(tail, normal);
+        edges[1] = tail;
+        let mut i = 1;
+        while i < 256 {
+            let y = area / edges[i] + Self::density(edges[i], normal);
+            edges[i + 1] = match (y < 1., normal) {
+                | (false, _) => 0.,
+                | (true, true) => newton_sqrt(-2. * ln(y)),
+                | (true, false) => -ln(y),
+            };
+            i += 1;
+        }
+        edges[256] = 0.;
+
+        let mut out = Self { threshold: [0; 256], width: [0.; 256], density: [1.; 257] };
+        let mut i = 0;
+        while i < 256 {
+            out.threshold[i] = (edges[i + 1] / edges[i] * scale) as u64;
+            out.width[i] = edges[i] / scale;
+            out.density[i] = Self::density(edges[i], normal);
+            i += 1;
+        }
+        out
+    }
+
+    const fn density(x: f64, normal: bool) -> f64 {
+        match normal {
+            | true => exp(-0.5 * x * x),
+            | false => exp(-x),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SPLITMIX64 */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub const fn build(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PCG32 */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    pub fn build(seed: u64, stream: u64) -> Self {
+        let mut out = Self { state: 0, increment: (stream << 1) | 1 };
+        out.step();
+        out.state = out.state.wrapping_add(seed);
+        out.step();
+        out
+    }
+
+    pub fn seed(seed: u64) -> Self {
+        Self::build(seed, 0xda3e_39cb_94b9_5bdb)
+    }
+
+    fn step(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(Self::MULTIPLIER).wrapping_add(self.increment);
+
+        let shifted = (((old >> 18) ^ old) >> 27) as u32;
+        shifted.rotate_right((old >> 59) as u32)
+    }
+}
+
+impl Rng for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        self.step()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let high = self.step() as u64;
+        let low = self.step() as u64;
+        (high << 32) | low
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* XOSHIRO256** */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xoshiro256 {
+    state: [u64; 4],
+}
+
+impl Xoshiro256 {
+    pub fn build(seed: u64) -> Self {
+        let mut seeder = SplitMix64::build(seed);
+        Self::from_state([
+            seeder.next_u64(),
+            seeder.next_u64(),
+            seeder.next_u64(),
+            seeder.next_u64(),
+        ])
+    }
+
+    pub const fn from_state(state: [u64; 4]) -> Self {
+        Self { state }
+    }
+
+    pub fn jump(&mut self) {
+        const JUMP: [u64; 4] = [
+            0x180e_c6d3_3cfd_0aba,
+            0xd5a6_1266_f0c9_392c,
+            0xa958_2618_e03f_c9aa,
+            0x39ab_dc45_29b1_661c,
+        ];
+
+        let mut out = [0; 4];
+        JUMP.iter().for_each(|&word| {
+            (0..64).for_each(|bit| {
+                if word & (1 << bit) != 0 {
+                    (0..4).for_each(|i| out[i] ^= self.state[i]);
+                }
+                self.next_u64();
+            });
+        });
+
+        self.state = out;
+    }
+}
+
+impl Rng for Xoshiro256 {
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let out = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        out
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_streams() {
+        let mut splitmix = SplitMix64::build(0);
+        assert!(splitmix.next_u64() == 0xe220_a839_7b1d_cdaf);
+        assert!(splitmix.next_u64() == 0x6e78_9e6a_a1b9_65f4);
+
+        let mut pcg = Pcg32::build(42, 54);
+        assert!(pcg.next_u32() == 0xa15c_02b7);
+        assert!(pcg.next_u32() == 0x7b47_f409);
+        assert!(pcg.next_u32() == 0xba1d_3330);
+
+        let mut xoshiro = Xoshiro256::from_state([1, 2, 3, 4]);
+        assert!(xoshiro.next_u64() == 11520);
+        assert!(xoshiro.next_u64() == 0);
+        assert!(xoshiro.next_u64() == 1509978240);
+    }
+
+    #[test]
+    fn sampler_streams() {
+        let mut rng = Xoshiro256::build(2024);
+        let normals: Vec<u64> = (0..4).map(|_| rng.normal::<f64>(0., 1.).to_bits()).collect();
+        assert!(
+            normals
+                == [
+                    0xbfbf_9729_5f3e_ec6c,
+                    0x3ff5_944e_ae16_0097,
+                    0x3fd0_d9e7_4439_12f9,
+                    0x3fd1_2f06_c632_2699
+                ]
+        );
+        let exponentials: Vec<u64> = (0..4).map(|_| rng.exponential::<f64>(1.).to_bits()).collect();
+        assert!(
+            exponentials
+                == [
+                    0x3fee_2b01_42fd_748c,
+                    0x3fb3_4c15_ab77_0abe,
+                    0x3fde_7a82_bdd7_1d60,
+                    0x3fdf_d518_0355_fbc2
+                ]
+        );
+        assert!(
+            (0..4).map(|_| rng.poisson(4.5)).eq([5, 2, 5, 2])
+                && (0..4).map(|_| rng.poisson(250.)).eq([250, 250, 229, 268])
+        );
+
+        // long runs reach the wedge, tail and rejection paths as well as the integer fast paths
+        fn digest(mut values: impl FnMut() -> u64) -> u64 {
+            (0..100_000).fold(0, |hash: u64, _| (hash ^ values()).rotate_left(5).wrapping_mul(0x100_0000_01b3))
+        }
+        let mut rng = Xoshiro256::build(2024);
+        assert!(digest(|| rng.normal::<f64>(0., 1.).to_bits()) == 0x7b03_be4b_4faf_d6d4);
+        assert!(digest(|| rng.exponential::<f64>(1.).to_bits()) == 0x823a_027e_9dd2_4852);
+        assert!(digest(|| rng.poisson(4.5)) == 0x87e9_edd5_1ec7_01ea);
+        assert!(digest(|| rng.poisson(250.)) == 0x9a0e_72db_bdc4_1c9a);
+    }
+
+    #[test]
+    fn portable_math() {
+        let ulps = |a: f64, b: f64| (a.to_bits() as i64 - b.to_bits() as i64).unsigned_abs();
+        assert!((0..2000).all(|i| {
+            let x = 1.013f64.powi(i - 1000) * 0.37;
+            ulps(ln(x), x.ln()) <= 1
+        }));
+        assert!((0..2000).all(|i| {
+            let x = (i - 1000) as f64 * 0.7123;
+            ulps(exp(x), x.exp()) <= 1
+        }));
+        assert!(ln(1.) == 0. && exp(0.) == 1. && ln(0.) == f64::NEG_INFINITY && ln(-1.).is_nan());
+        assert!(exp(800.) == f64::INFINITY && exp(-800.) == 0. && ln(f64::MIN_POSITIVE / 4.) < -709.);
+    }
+
+    #[test]
+    fn distribution_moments() {
+        let mut rng = Xoshiro256::build(7);
+        let n = 100_000;
+
+        let (mut sum, mut squares) = (0., 0.);
+        (0..n).for_each(|_| {
+            let x: f64 = rng.normal(3., 2.);
+            sum += x;
+            squares += x * x;
+        });
+        let mean = sum / n as f64;
+        assert!((mean - 3.).abs() < 0.03 && (squares / n as f64 - mean * mean - 4.).abs() < 0.1);
+
+        let mut sum = 0.;
+        (0..n).for_each(|_| sum += rng.exponential(4.));
+        assert!((sum / n as f64 - 0.25).abs() < 0.005);
+
+        for lambda in [3.5, 80.] {
+            let mut sum = 0;
+            (0..n).for_each(|_| sum += rng.poisson(lambda));
+            assert!((sum as f64 / n as f64 - lambda).abs() < lambda * 0.01);
+        }
+    }
+
+    #[test]
+    fn geometry_samples() {
+        let mut rng = Pcg32::seed(3);
+        let normal = Vector3::build(0., 0., 1.);
+
+        (0..1000).for_each(|_| {
+            assert!(rng.in_unit_disc::<f32>().squared_length() < 1.);
+            assert!(rng.in_unit_sphere::<f64>().squared_length() < 1.);
+            assert!((rng.on_unit_circle::<f64>().length() - 1.).abs() < 1e-12);
+            assert!((rng.on_unit_sphere::<f64>().length() - 1.).abs() < 1e-12);
+            assert!(rng.on_hemisphere(normal).z >= 0.);
+            assert!(rng.cosine_hemisphere(normal).z >= 0.);
+        });
+    }
+
+    #[test]
+    fn shuffle_and_choice() {
+        let mut rng = SplitMix64::build(99);
+        let mut data: Vec<usize> = (0..50).collect();
+        rng.shuffle(&mut data);
+        assert!(data != (0..50).collect::<Vec<_>>());
+        data.sort();
+        assert!(data == (0..50).collect::<Vec<_>>());
+
+        let mut counts = [0; 3];
+        (0..30_000).for_each(|_| counts[rng.weighted_choice(&[1., 0., 2.]).unwrap()] += 1);
+        assert!(counts[1] == 0 && (counts[2] as f64 / counts[0] as f64 - 2.).abs() < 0.1);
+        assert!(rng.weighted_choice::<f64>(&[0., 0.]).is_none());
+
+        let replay: Vec<u64> = (0..4).map(|_| Xoshiro256::build(5).below(1000)).collect();
+        assert!(replay.iter().all(|&value| value == replay[0]));
+    }
+}
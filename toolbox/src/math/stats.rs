@@ -0,0 +1,640 @@
+use std::collections::VecDeque;
+
+use crate::containers::rolling_history::RollingHistory;
+
+use super::matrix::MatrixN;
+use super::traits::FloatNumber;
+use super::vector::VectorN;
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* ACCUMULATOR */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Accumulator<T> {
+    count: usize,
+    mean: T,
+    m2: T,
+    m3: T,
+    m4: T,
+    min: Option<T>,
+    max: Option<T>,
+}
+
+impl<T> Accumulator<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            mean: T::zero(),
+            m2: T::zero(),
+            m3: T::zero(),
+            m4: T::zero(),
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        let prev = T::constant(self.count as f64);
+        self.count += 1;
+        let n = T::constant(self.count as f64);
+
+        let delta = value - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term = delta * delta_n * prev;
+
+        self.mean += delta_n;
+        self.m4 += term * delta_n2 * (n * n - T::constant(3.) * n + T::constant(3.))
+            + T::constant(6.) * delta_n2 * self.m2
+            - T::constant(4.) * delta_n * self.m3;
+        self.m3 += term * delta_n * (n - T::constant(2.)) - T::constant(3.) * delta_n * self.m2;
+        self.m2 += term;
+
+        self.min = Some(self.min.map_or(value, |min| if value < min { value } else { min }));
+        self.max = Some(self.max.map_or(value, |max| if value > max { value } else { max }));
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let (na, nb) = (T::constant(self.count as f64), T::constant(other.count as f64));
+        let n = na + nb;
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+
+        let m2 = self.m2 + other.m2 + delta2 * na * nb / n;
+        let m3 = self.m3
+            + other.m3
+            + delta2 * delta * na * nb * (na - nb) / (n * n)
+            + T::constant(3.) * delta * (na * other.m2 - nb * self.m2) / n;
+        let m4 = self.m4
+            + other.m4
+            + delta2 * delta2 * na * nb * (na * na - na * nb + nb * nb) / (n * n * n)
+            + T::constant(6.) * delta2 * (na * na * other.m2 + nb * nb * self.m2) / (n * n)
+            + T::constant(4.) * delta * (na * other.m3 - nb * self.m3) / n;
+
+        self.count += other.count;
+        self.mean += delta * nb / n;
+        (self.m2, self.m3, self.m4) = (m2, m3, m4);
+
+        if let (Some(a), Some(b)) = (self.min, other.min) {
+            self.min = Some(if b < a { b } else { a });
+        }
+        if let (Some(a), Some(b)) = (self.max, other.max) {
+            self.max = Some(if b > a { b } else { a });
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<T> {
+        match self.count {
+            | 0 => None,
+            | _ => Some(self.mean),
+        }
+    }
+
+    pub fn variance(&self) -> Option<T> {
+        match self.count {
+            | 0 => None,
+            | n => Some(self.m2 / T::constant(n as f64)),
+        }
+    }
+
+    pub fn sample_variance(&self) -> Option<T> {
+        match self.count {
+            | 0 | 1 => None,
+            | n => Some(self.m2 / T::constant((n - 1) as f64)),
+        }
+    }
+
+    pub fn deviation(&self) -> Option<T> {
+        self.variance().map(|variance| variance.sqrt())
+    }
+
+    pub fn skewness(&self) -> Option<T> {
+        if self.count < 2 || self.m2 == T::zero() {
+            return None;
+        }
+
+        let n = T::constant(self.count as f64);
+        Some(n.sqrt() * self.m3 / self.m2.powf(T::constant(1.5)))
+    }
+
+    pub fn kurtosis(&self) -> Option<T> {
+        if self.count < 2 || self.m2 == T::zero() {
+            return None;
+        }
+
+        let n = T::constant(self.count as f64);
+        Some(n * self.m4 / (self.m2 * self.m2) - T::constant(3.))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.max
+    }
+}
+
+impl<T> Default for Accumulator<T>
+where
+    T: FloatNumber<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for Accumulator<T>
+where
+    T: FloatNumber<T>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| self.push(value));
+    }
+}
+
+impl<T> FromIterator<T> for Accumulator<T>
+where
+    T: FloatNumber<T>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* ROLLING STATS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct RollingStats<T> {
+    history: RollingHistory<T>,
+    mean: T,
+    m2: T,
+    minima: VecDeque<(usize, T)>,
+    maxima: VecDeque<(usize, T)>,
+    pushed: usize,
+}
+
+impl<T> RollingStats<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(length: usize) -> Self {
+        Self {
+            history: RollingHistory::build(length.max(1)),
+            mean: T::zero(),
+            m2: T::zero(),
+            minima: VecDeque::new(),
+            maxima: VecDeque::new(),
+            pushed: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if let Ok(Some(old)) = self.history.push_back(value) {
+            let n = T::constant((self.history.len() - 1) as f64);
+            let mean = match n > T::zero() {
+                | true => self.mean - (old - self.mean) / n,
+                | false => T::zero(),
+            };
+            self.m2 -= (old - self.mean) * (old - mean);
+            self.mean = mean;
+        }
+
+        let n = T::constant(self.history.len() as f64);
+        let delta = value - self.mean;
+        self.mean += delta / n;
+        self.m2 += delta * (value - self.mean);
+        if self.m2 < T::zero() {
+            self.m2 = T::zero();
+        }
+
+        let index = self.pushed;
+        self.pushed += 1;
+        let start = self.pushed - self.history.len();
+
+        while self.minima.back().is_some_and(|&(_, back)| back >= value) {
+            self.minima.pop_back();
+        }
+        while self.maxima.back().is_some_and(|&(_, back)| back <= value) {
+            self.maxima.pop_back();
+        }
+        self.minima.push_back((index, value));
+        self.maxima.push_back((index, value));
+
+        while self.minima.front().is_some_and(|&(i, _)| i < start) {
+            self.minima.pop_front();
+        }
+        while self.maxima.front().is_some_and(|&(i, _)| i < start) {
+            self.maxima.pop_front();
+        }
+    }
+
+    pub fn history(&self) -> &RollingHistory<T> {
+        &self.history
+    }
+
+    pub fn count(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn mean(&self) -> Option<T> {
+        match self.count() {
+            | 0 => None,
+            | _ => Some(self.mean),
+        }
+    }
+
+    pub fn variance(&self) -> Option<T> {
+        match self.count() {
+            | 0 => None,
+            | n => Some(self.m2 / T::constant(n as f64)),
+        }
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.minima.front().map(|&(_, value)| value)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.maxima.front().map(|&(_, value)| value)
+    }
+
+    pub fn accumulator(&self) -> Accumulator<T> {
+        self.history.iter().copied().collect()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* P2 QUANTILE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct P2Quantile<T> {
+    quantile: T,
+    heights: [T; 5],
+    positions: [T; 5],
+    desired: [T; 5],
+    increments: [T; 5],
+    count: usize,
+}
+
+impl<T> P2Quantile<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(quantile: T) -> Self {
+        let p = quantile;
+        let (zero, one, two) = (T::zero(), T::one(), T::constant(2.));
+
+        Self {
+            quantile,
+            heights: [zero; 5],
+            positions: [one, two, T::constant(3.), T::constant(4.), T::constant(5.)],
+            desired: [
+                one,
+                one + two * p,
+                one + T::constant(4.) * p,
+                T::constant(3.) + two * p,
+                T::constant(5.),
+            ],
+            increments: [zero, p / two, p, (one + p) / two, one],
+            count: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.count < 5 {
+            self.heights[self.count] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            }
+            return;
+        }
+        self.count += 1;
+
+        let h = &mut self.heights;
+        let cell = match value {
+            | v if v < h[0] => {
+                h[0] = v;
+                0
+            }
+            | v if v >= h[4] => {
+                h[4] = v;
+                3
+            }
+            | v => (1..5).find(|&i| v < h[i]).unwrap_or(4) - 1,
+        };
+
+        (cell + 1..5).for_each(|i| self.positions[i] += T::one());
+        (0..5).for_each(|i| self.desired[i] += self.increments[i]);
+
+        (1..4).for_each(|i| {
+            let offset = self.desired[i] - self.positions[i];
+            let forward = self.positions[i + 1] - self.positions[i];
+            let backward = self.positions[i - 1] - self.positions[i];
+
+            if (offset >= T::one() && forward > T::one()) || (offset <= -T::one() && backward < -T::one()) {
+                let step = match offset > T::zero() {
+                    | true => T::one(),
+                    | false => -T::one(),
+                };
+
+                let candidate = self.parabolic(i, step);
+                self.heights[i] = match self.heights[i - 1] < candidate && candidate < self.heights[i + 1] {
+                    | true => candidate,
+                    | false => self.linear(i, step),
+                };
+                self.positions[i] += step;
+            }
+        });
+    }
+
+    pub fn value(&self) -> Option<T> {
+        if self.count == 0 {
+            return None;
+        }
+        if self.count < 5 {
+            let mut sorted = self.heights;
+            let seen = &mut sorted[..self.count];
+            seen.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+            let rank = self.quantile * T::constant((self.count - 1) as f64) + T::constant(0.5);
+            let index = (0..self.count).rev().find(|&i| T::constant(i as f64) <= rank).unwrap_or(0);
+            return Some(seen[index]);
+        }
+
+        Some(self.heights[2])
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    fn parabolic(&self, i: usize, step: T) -> T {
+        let (q, n) = (self.heights, self.positions);
+        let outer = step / (n[i + 1] - n[i - 1]);
+        let upper = (n[i] - n[i - 1] + step) * (q[i + 1] - q[i]) / (n[i + 1] - n[i]);
+        let lower = (n[i + 1] - n[i] - step) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]);
+
+        q[i] + outer * (upper + lower)
+    }
+
+    fn linear(&self, i: usize, step: T) -> T {
+        let (q, n) = (self.heights, self.positions);
+        let j = match step > T::zero() {
+            | true => i + 1,
+            | false => i - 1,
+        };
+
+        q[i] + step * (q[j] - q[i]) / (n[j] - n[i])
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* COVARIANCE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CovarianceAccumulator<T, const N: usize> {
+    count: usize,
+    mean: VectorN<T, N>,
+    comoment: MatrixN<T, N>,
+}
+
+impl<T, const N: usize> CovarianceAccumulator<T, N>
+where
+    T: FloatNumber<T>,
+{
+    pub fn new() -> Self {
+        Self { count: 0, mean: VectorN::zeros(), comoment: MatrixN::zeros() }
+    }
+
+    pub fn push(&mut self, value: VectorN<T, N>) {
+        self.count += 1;
+
+        let before = value - self.mean;
+        self.mean += before / T::constant(self.count as f64);
+        let after = value - self.mean;
+
+        (0..N).for_each(|i| {
+            (0..N).for_each(|j| {
+                self.comoment.inner[i][j] += before.inner[i] * after.inner[j];
+            });
+        });
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<VectorN<T, N>> {
+        match self.count {
+            | 0 => None,
+            | _ => Some(self.mean),
+        }
+    }
+
+    pub fn covariance(&self) -> Option<MatrixN<T, N>> {
+        match self.count {
+            | 0 => None,
+            | n => Some(self.comoment * (T::one() / T::constant(n as f64))),
+        }
+    }
+
+    pub fn sample_covariance(&self) -> Option<MatrixN<T, N>> {
+        match self.count {
+            | 0 | 1 => None,
+            | n => Some(self.comoment * (T::one() / T::constant((n - 1) as f64))),
+        }
+    }
+}
+
+impl<T, const N: usize> Default for CovarianceAccumulator<T, N>
+where
+    T: FloatNumber<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* HISTOGRAM */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram<T> {
+    pub low: T,
+    pub high: T,
+    pub counts: Vec<usize>,
+    pub underflow: usize,
+    pub overflow: usize,
+}
+
+impl<T> Histogram<T>
+where
+    T: FloatNumber<T>,
+{
+    pub fn build(low: T, high: T, bins: usize) -> Self {
+        Self {
+            low,
+            high,
+            counts: vec![0; bins.max(1)],
+            underflow: 0,
+            overflow: 0,
+        }
+    }
+
+    pub fn bin(&self, value: T) -> Option<usize> {
+        if !(value >= self.low && value < self.high) {
+            return None;
+        }
+
+        // rounding can push values just below high onto the last edge
+        let bins = self.counts.len();
+        let position = (value - self.low) / (self.high - self.low) * T::constant(bins as f64);
+        Some((position.to_f64() as usize).min(bins - 1))
+    }
+
+    pub fn push(&mut self, value: T) {
+        match self.bin(value) {
+            | Some(index) => self.counts[index] += 1,
+            | None if value < self.low => self.underflow += 1,
+            | None => self.overflow += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum::<usize>() + self.underflow + self.overflow
+    }
+
+    pub fn edges(&self) -> Vec<T> {
+        let bins = self.counts.len();
+        let width = (self.high - self.low) / T::constant(bins as f64);
+
+        (0..=bins).map(|i| self.low + width * T::constant(i as f64)).collect()
+    }
+
+    pub fn density(&self) -> Vec<T> {
+        let binned: usize = self.counts.iter().sum();
+        if binned == 0 {
+            return vec![T::zero(); self.counts.len()];
+        }
+
+        let width = (self.high - self.low) / T::constant(self.counts.len() as f64);
+        let scale = T::one() / (T::constant(binned as f64) * width);
+
+        self.counts.iter().map(|&count| T::constant(count as f64) * scale).collect()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::random::Rng;
+    use crate::math::random::Xoshiro256;
+
+    const DATA: [f64; 8] = [2., 4., 4., 4., 5., 5., 7., 9.];
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn moments() {
+        let acc: Accumulator<f64> = DATA.into_iter().collect();
+        assert!(close(acc.mean().unwrap(), 5.) && close(acc.variance().unwrap(), 4.));
+        assert!(close(acc.sample_variance().unwrap(), 32. / 7.));
+        assert!(close(acc.skewness().unwrap(), 0.65625));
+        assert!(close(acc.kurtosis().unwrap(), -0.21875));
+        assert!(acc.min() == Some(2.) && acc.max() == Some(9.));
+
+        let mut left: Accumulator<f64> = DATA[..3].iter().copied().collect();
+        let right: Accumulator<f64> = DATA[3..].iter().copied().collect();
+        left.merge(&right);
+        assert!(left.count() == 8 && close(left.mean().unwrap(), 5.) && close(left.variance().unwrap(), 4.));
+        assert!(close(left.skewness().unwrap(), 0.65625) && close(left.kurtosis().unwrap(), -0.21875));
+    }
+
+    #[test]
+    fn rolling_window() {
+        let mut rolling = RollingStats::build(3);
+        let stream = [5., 1., 8., 3., 9., 2., 2., 7.];
+
+        stream.iter().enumerate().for_each(|(i, &value)| {
+            rolling.push(value);
+
+            let window: Accumulator<f64> = rolling.history().iter().copied().collect();
+            assert!(window.count() == (i + 1).min(rolling.count()));
+            assert!((rolling.mean().unwrap() - window.mean().unwrap()).abs() < 1e-9);
+            assert!((rolling.variance().unwrap() - window.variance().unwrap()).abs() < 1e-9);
+            assert!(rolling.min() == window.min() && rolling.max() == window.max());
+        });
+    }
+
+    #[test]
+    fn rolling_zero_length() {
+        let mut rolling = RollingStats::<f64>::build(0);
+        rolling.push(1.);
+        rolling.push(4.);
+        assert!(rolling.count() == 1 && rolling.mean() == Some(4.) && rolling.variance() == Some(0.));
+        assert!(rolling.min() == Some(4.) && rolling.max() == Some(4.));
+    }
+
+    #[test]
+    fn streaming_quantile() {
+        let mut rng = Xoshiro256::build(11);
+        let mut median = P2Quantile::build(0.5);
+        let mut tail = P2Quantile::build(0.9);
+
+        (0..20_000).for_each(|_| {
+            let value: f64 = rng.uniform(0., 10.);
+            median.push(value);
+            tail.push(value);
+        });
+        assert!((median.value().unwrap() - 5.).abs() < 0.1);
+        assert!((tail.value().unwrap() - 9.).abs() < 0.1);
+
+        let mut small = P2Quantile::build(0.5);
+        [3., 1., 2.].iter().for_each(|&value| small.push(value));
+        assert!(small.value() == Some(2.));
+    }
+
+    #[test]
+    fn covariance_and_histogram() {
+        let mut cov = CovarianceAccumulator::<f64, 2>::new();
+        DATA.iter().for_each(|&x| cov.push(VectorN::build([x, 2. * x + 1.])));
+        let matrix = cov.covariance().unwrap();
+        assert!(close(matrix.inner[0][0], 4.) && close(matrix.inner[0][1], 8.) && close(matrix.inner[1][1], 16.));
+
+        let mut histogram = Histogram::build(0., 10., 5);
+        DATA.iter().for_each(|&x| histogram.push(x));
+        histogram.push(-1.);
+        histogram.push(10.);
+        assert!(histogram.counts == vec![0, 1, 5, 1, 1]);
+        assert!(histogram.underflow == 1 && histogram.overflow == 1 && histogram.total() == 10);
+        assert!(histogram.edges() == vec![0., 2., 4., 6., 8., 10.]);
+        assert!(histogram.bin(2.) == Some(1) && histogram.bin(f64::NAN).is_none());
+
+        let fine = Histogram::build(0., 1., 1_000_000);
+        assert!(fine.bin(0.5) == Some(500_000) && fine.bin(1. - f64::EPSILON) == Some(999_999));
+    }
+}
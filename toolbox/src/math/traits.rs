@@ -8,6 +8,10 @@ use std::ops::Neg;
 use std::ops::Sub;
 use std::ops::SubAssign;
 
+use super::vector::Vector2;
+use super::vector::Vector3;
+use super::vector::Vector4;
+
 pub trait Scalar
 where
     Self: Default + Clone + Copy + Sized,
@@ -50,6 +54,28 @@ where
     fn sqrt(self) -> Self;
 
     fn constant(value: f64) -> Self;
+
+    fn epsilon() -> Self;
+
+    fn abs(self) -> Self;
+
+    fn sin(self) -> Self;
+
+    fn cos(self) -> Self;
+
+    fn atan2(self, other: Self) -> Self;
+
+    fn exp(self) -> Self;
+
+    fn ln(self) -> Self;
+
+    fn powf(self, exponent: Self) -> Self;
+
+    fn hypot(self, other: Self) -> Self;
+
+    fn floor(self) -> Self;
+
+    fn to_f64(self) -> f64;
 }
 
 macro_rules! impl_floatnumber {
@@ -62,6 +88,50 @@ macro_rules! impl_floatnumber {
             fn constant(value: f64) -> $type {
                 return value as $type;
             }
+
+            fn epsilon() -> $type {
+                return <$type>::EPSILON;
+            }
+
+            fn abs(self) -> $type {
+                return self.abs();
+            }
+
+            fn sin(self) -> $type {
+                return self.sin();
+            }
+
+            fn cos(self) -> $type {
+                return self.cos();
+            }
+
+            fn atan2(self, other: $type) -> $type {
+                return self.atan2(other);
+            }
+
+            fn exp(self) -> $type {
+                return self.exp();
+            }
+
+            fn ln(self) -> $type {
+                return self.ln();
+            }
+
+            fn powf(self, exponent: $type) -> $type {
+                return self.powf(exponent);
+            }
+
+            fn hypot(self, other: $type) -> $type {
+                return self.hypot(other);
+            }
+
+            fn floor(self) -> $type {
+                return self.floor();
+            }
+
+            fn to_f64(self) -> f64 {
+                return self as f64;
+            }
         }
     };
 }
@@ -126,3 +196,94 @@ impl_one!(usize);
 impl_one!(isize);
 impl_one!(f32);
 impl_one!(f64);
+
+pub trait Linear
+where
+    Self: Copy,
+{
+    fn scale(self, factor: f32) -> Self;
+
+    fn add(self, other: Self) -> Self;
+
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self.scale(1. - t).add(other.scale(t))
+    }
+}
+
+impl Linear for () {
+    fn scale(self, _: f32) -> Self {}
+
+    fn add(self, _: Self) -> Self {}
+}
+
+impl Linear for f32 {
+    fn scale(self, factor: f32) -> Self {
+        self * factor
+    }
+
+    fn add(self, other: Self) -> Self {
+        self + other
+    }
+}
+
+macro_rules! impl_linear_vector {
+    ($type:ident) => {
+        impl Linear for $type<f32> {
+            fn scale(self, factor: f32) -> Self {
+                self * factor
+            }
+
+            fn add(self, other: Self) -> Self {
+                self + other
+            }
+        }
+    };
+}
+
+impl_linear_vector!(Vector2);
+impl_linear_vector!(Vector3);
+impl_linear_vector!(Vector4);
+
+impl<V, const N: usize> Linear for [V; N]
+where
+    V: Linear,
+{
+    fn scale(self, factor: f32) -> Self {
+        self.map(|value| value.scale(factor))
+    }
+
+    fn add(self, other: Self) -> Self {
+        let mut out = self;
+        out.iter_mut().zip(other).for_each(|(out, other)| *out = out.add(other));
+        out
+    }
+}
+
+impl<A, B> Linear for (A, B)
+where
+    A: Linear,
+    B: Linear,
+{
+    fn scale(self, factor: f32) -> Self {
+        (self.0.scale(factor), self.1.scale(factor))
+    }
+
+    fn add(self, other: Self) -> Self {
+        (self.0.add(other.0), self.1.add(other.1))
+    }
+}
+
+impl<A, B, C> Linear for (A, B, C)
+where
+    A: Linear,
+    B: Linear,
+    C: Linear,
+{
+    fn scale(self, factor: f32) -> Self {
+        (self.0.scale(factor), self.1.scale(factor), self.2.scale(factor))
+    }
+
+    fn add(self, other: Self) -> Self {
+        (self.0.add(other.0), self.1.add(other.1), self.2.add(other.2))
+    }
+}
@@ -582,7 +582,7 @@ where
     T: Numeric<T>,
 {
     fn inner_product(self, other: Self) -> T {
-        self.x * other.x + self.y * other.y + self.z * other.z
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
     }
 
     fn squared_length(self) -> T {
@@ -1157,6 +1157,15 @@ mod tests {
         assert!(product == Vector3::build(0., -100., 0.));
     }
 
+    #[test]
+    fn vector4_products() {
+        let v1 = Vector4::build(1, 2, 3, 4);
+        let v2 = Vector4::build(5, 6, 7, 8);
+        assert!(v1.inner_product(v2) == 70);
+        assert!(Vector4::build(0, 0, 0, 3).squared_length() == 9);
+        assert!(Vector4::build(0., 0., 3., 4.).length() == 5.);
+    }
+
     #[test]
     fn swizzle_testing() {
         let vector = Vector3::build(99, 1, 1);
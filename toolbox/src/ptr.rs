@@ -1,63 +1,246 @@
-use std::ops::Deref;
-use std::ops::DerefMut;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::ptr::NonNull;
 use std::sync::Arc;
 
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////
-/* THREAD POINTER */
+/* SEND POINTER */
 /////////////////////////////////////////////////////////////////////////////////////////////////////////////
-pub struct RawPtr<T> {
+pub struct SendPtr<T> {
     ptr: *mut T,
 }
 
-impl<T> RawPtr<T> {
+unsafe impl<T> Send for SendPtr<T> where T: Send {}
+
+unsafe impl<T> Sync for SendPtr<T> where T: Send {}
+
+impl<T> SendPtr<T> {
     pub fn new(data: &mut T) -> Self {
         Self { ptr: data as *mut T }
     }
 
-    pub fn read(&self) -> &T {
-        unsafe { &(*self.ptr) }
+    pub fn from_raw(ptr: *mut T) -> Self {
+        Self { ptr }
+    }
+
+    pub fn as_ptr(self) -> *mut T {
+        self.ptr
+    }
+
+    /// # Safety
+    /// The offset must stay inside the allocation the pointer was derived from.
+    pub unsafe fn add(self, count: usize) -> Self {
+        // SAFETY: upheld by the caller
+        Self { ptr: unsafe { self.ptr.add(count) } }
+    }
+
+    /// # Safety
+    /// The pointee must be alive and not mutated through any other path while the reference exists.
+    pub unsafe fn get<'d>(self) -> &'d T {
+        // SAFETY: upheld by the caller
+        unsafe { &*self.ptr }
+    }
+
+    /// # Safety
+    /// The pointee must be alive and no other reference to it may exist while the returned one does.
+    pub unsafe fn get_mut<'d>(self) -> &'d mut T {
+        // SAFETY: upheld by the caller
+        unsafe { &mut *self.ptr }
     }
 
-    pub fn drop_not(&mut self) {
-        unsafe { _ = Box::from_raw(self.ptr) }
+    /// # Safety
+    /// The pointee must be valid for writes and not accessed concurrently from another thread.
+    pub unsafe fn write(self, value: T) {
+        // SAFETY: upheld by the caller
+        unsafe { *self.ptr = value };
     }
 }
 
-unsafe impl<T> Send for RawPtr<T> {}
+impl<T> Copy for SendPtr<T> {}
 
-unsafe impl<T> Sync for RawPtr<T> {}
+impl<T> Clone for SendPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SHARED SLICE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct SharedSlice<'d, T> {
+    ptr: *mut T,
+    len: usize,
+    marker: PhantomData<&'d mut [T]>,
+}
 
-impl<T> Copy for RawPtr<T> {}
+unsafe impl<T> Send for SharedSlice<'_, T> where T: Send {}
 
-impl<T> Clone for RawPtr<T> {
+unsafe impl<T> Sync for SharedSlice<'_, T> where T: Send {}
+
+impl<'d, T> SharedSlice<'d, T> {
+    pub fn new(data: &'d mut [T]) -> Self {
+        Self { ptr: data.as_mut_ptr(), len: data.len(), marker: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// # Safety
+    /// No thread may write the element at `index` while the returned reference exists.
+    pub unsafe fn get(&self, index: usize) -> &'d T {
+        assert!(index < self.len, "shared slice index {index} out of bounds for length {}", self.len);
+        // SAFETY: in bounds, aliasing upheld by the caller
+        unsafe { &*self.ptr.add(index) }
+    }
+
+    /// # Safety
+    /// No other reference to the element at `index` may exist while the returned one does.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut(&self, index: usize) -> &'d mut T {
+        assert!(index < self.len, "shared slice index {index} out of bounds for length {}", self.len);
+        // SAFETY: in bounds, exclusivity upheld by the caller
+        unsafe { &mut *self.ptr.add(index) }
+    }
+
+    /// # Safety
+    /// No other thread may access the element at `index` during the write.
+    pub unsafe fn write(&self, index: usize, value: T) {
+        // SAFETY: upheld by the caller
+        unsafe { *self.get_mut(index) = value };
+    }
+
+    /// # Safety
+    /// Ranges handed out concurrently must not overlap each other or any element accessed by index.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn slice_mut(&self, range: Range<usize>) -> &'d mut [T] {
+        assert!(range.start <= range.end && range.end <= self.len, "shared slice range {range:?} out of bounds");
+        // SAFETY: in bounds, exclusivity upheld by the caller
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.add(range.start), range.len()) }
+    }
+}
+
+impl<T> Copy for SharedSlice<'_, T> {}
+
+impl<T> Clone for SharedSlice<'_, T> {
     fn clone(&self) -> Self {
         *self
     }
 }
 
-impl<T> Deref for RawPtr<T> {
-    type Target = T;
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* RAW POINTER */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    Borrowed,
+    Boxed,
+    Shared,
+}
+
+pub struct RawPtr<'d, T> {
+    ptr: NonNull<T>,
+    origin: Origin,
+    marker: PhantomData<&'d mut T>,
+}
+
+unsafe impl<T> Send for RawPtr<'_, T> where T: Send + Sync {}
+
+unsafe impl<T> Sync for RawPtr<'_, T> where T: Send + Sync {}
+
+impl<'d, T> RawPtr<'d, T> {
+    pub fn new(data: &'d mut T) -> Self {
+        Self {
+            ptr: NonNull::from(data),
+            origin: Origin::Borrowed,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn origin(&self) -> Origin {
+        self.origin
+    }
+
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    pub fn send(&self) -> SendPtr<T> {
+        SendPtr::from_raw(self.ptr.as_ptr())
+    }
+
+    /// # Safety
+    /// Nothing may mutate the pointee through another path while the reference exists.
+    pub unsafe fn as_ref(&self) -> &T {
+        // SAFETY: the pointer is valid for the lifetime of self, aliasing upheld by the caller
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// # Safety
+    /// No other reference to the pointee may exist while the returned one does, which for a shared origin
+    /// includes every other clone of the source `Arc`.
+    pub unsafe fn as_mut(&mut self) -> &mut T {
+        // SAFETY: the pointer is valid for the lifetime of self, exclusivity upheld by the caller
+        unsafe { self.ptr.as_mut() }
+    }
 
-    fn deref(&self) -> &Self::Target {
-        self.read()
+    pub fn into_box(self) -> Result<Box<T>, Self> {
+        if self.origin != Origin::Boxed {
+            return Err(self);
+        }
+
+        let ptr = std::mem::ManuallyDrop::new(self).ptr;
+        // SAFETY: the pointer came from Box::into_raw and ownership is moved back exactly once
+        Ok(unsafe { Box::from_raw(ptr.as_ptr()) })
+    }
+
+    pub fn into_arc(self) -> Result<Arc<T>, Self> {
+        if self.origin != Origin::Shared {
+            return Err(self);
+        }
+
+        let ptr = std::mem::ManuallyDrop::new(self).ptr;
+        // SAFETY: the pointer came from Arc::into_raw and its strong count is returned exactly once
+        Ok(unsafe { Arc::from_raw(ptr.as_ptr()) })
     }
 }
 
-impl<T> DerefMut for RawPtr<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { &mut (*self.ptr) }
+impl<T> Drop for RawPtr<'_, T> {
+    fn drop(&mut self) {
+        match self.origin {
+            | Origin::Borrowed => {}
+            // SAFETY: the pointer came from Box::into_raw and is released exactly once
+            | Origin::Boxed => drop(unsafe { Box::from_raw(self.ptr.as_ptr()) }),
+            // SAFETY: the pointer came from Arc::into_raw and its strong count is released exactly once
+            | Origin::Shared => drop(unsafe { Arc::from_raw(self.ptr.as_ptr()) }),
+        }
     }
 }
 
-impl<T> From<Box<T>> for RawPtr<T> {
+impl<T> fmt::Debug for RawPtr<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawPtr").field("ptr", &self.ptr).field("origin", &self.origin).finish()
+    }
+}
+
+impl<T> From<Box<T>> for RawPtr<'static, T> {
     fn from(value: Box<T>) -> Self {
-        Self { ptr: Box::into_raw(value) }
+        // SAFETY: Box::into_raw never returns null
+        let ptr = unsafe { NonNull::new_unchecked(Box::into_raw(value)) };
+        Self { ptr, origin: Origin::Boxed, marker: PhantomData }
     }
 }
 
-impl<T> From<Arc<T>> for RawPtr<T> {
+impl<T> From<Arc<T>> for RawPtr<'static, T> {
     fn from(value: Arc<T>) -> Self {
-        Self { ptr: Arc::into_raw(value) as *mut T }
+        // SAFETY: Arc::into_raw never returns null
+        let ptr = unsafe { NonNull::new_unchecked(Arc::into_raw(value) as *mut T) };
+        Self { ptr, origin: Origin::Shared, marker: PhantomData }
     }
 }
 
@@ -67,14 +250,85 @@ impl<T> From<Arc<T>> for RawPtr<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::thread;
+
+    #[test]
+    fn send_ptr() {
+        let mut data = [0u32; 8];
+        let base = SendPtr::from_raw(data.as_mut_ptr());
+        thread::scope(|scope| {
+            (0..4).for_each(|part| {
+                scope.spawn(move || {
+                    (0..2).for_each(|i| {
+                        let index = part * 2 + i;
+                        // SAFETY: every thread writes its own two elements of the array
+                        unsafe { base.add(index).write(index as u32 * 10) };
+                    });
+                });
+            });
+        });
+        assert!(data == [0, 10, 20, 30, 40, 50, 60, 70]);
+    }
+
+    #[test]
+    fn shared_slice() {
+        let mut data: Vec<String> = (0..6).map(|i| i.to_string()).collect();
+        let shared = SharedSlice::new(&mut data);
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                // SAFETY: this thread owns the even indices
+                (0..shared.len()).step_by(2).for_each(|i| unsafe { shared.get_mut(i).push('e') });
+            });
+            scope.spawn(move || {
+                // SAFETY: this thread owns the odd indices
+                (1..shared.len()).step_by(2).for_each(|i| unsafe { shared.write(i, format!("{i}o")) });
+            });
+        });
+        assert!(data == ["0e", "1o", "2e", "3o", "4e", "5o"]);
+
+        let shared = SharedSlice::new(&mut data);
+        // SAFETY: the two ranges are disjoint
+        let (head, tail) = unsafe { (shared.slice_mut(0..2), shared.slice_mut(2..6)) };
+        head[0].clear();
+        tail[3].clear();
+        assert!(data[0].is_empty() && data[5].is_empty() && data[1] == "1o");
+    }
 
     #[test]
-    fn ptr() {
-        let mut data = 120;
-        let mut mut1 = RawPtr::new(&mut data);
-        let mut mut2 = RawPtr::new(&mut data);
-        *mut1 += 1;
-        *mut2 += 10;
-        assert!(data == 131);
+    fn ownership_tracking() {
+        let tracker = Arc::new(5);
+
+        let mut value = 1;
+        let mut borrowed = RawPtr::new(&mut value);
+        // SAFETY: the only reference to value is held through this pointer
+        unsafe { *borrowed.as_mut() += 1 };
+        let borrowed = borrowed.into_box().unwrap_err();
+        assert!(borrowed.origin() == Origin::Borrowed);
+        drop(borrowed);
+        assert!(value == 2);
+
+        let boxed = RawPtr::from(Box::new(Arc::clone(&tracker)));
+        assert!(Arc::strong_count(&tracker) == 2);
+        drop(boxed);
+        assert!(Arc::strong_count(&tracker) == 1);
+
+        let shared = RawPtr::from(Arc::clone(&tracker));
+        // SAFETY: no one mutates the shared value
+        assert!(unsafe { *shared.as_ref() } == 5 && Arc::strong_count(&tracker) == 2);
+        let shared = shared.into_box().unwrap_err();
+        let restored = shared.into_arc().unwrap();
+        assert!(Arc::strong_count(&tracker) == 2);
+        drop(restored);
+
+        let shared = RawPtr::from(Arc::clone(&tracker));
+        let send = shared.send();
+        thread::spawn(move || {
+            // SAFETY: the arc is kept alive by the pointer on the spawning thread until join
+            assert!(unsafe { *send.get() } == 5);
+        })
+        .join()
+        .unwrap();
+        drop(shared);
+        assert!(Arc::strong_count(&tracker) == 1);
     }
 }
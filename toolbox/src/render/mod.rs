@@ -0,0 +1,3 @@
+pub mod pipeline;
+pub mod raster;
+pub mod texture;
@@ -0,0 +1,269 @@
+use super::raster::Fragment;
+use super::raster::Rasterizer;
+use super::raster::Varying;
+use super::raster::Vertex;
+use super::raster::Viewport;
+use super::raster::clip_polygon;
+use crate::containers::buffer::Bitmap;
+use crate::containers::buffer::BitmapPayload;
+use crate::containers::buffer::Buffer2;
+use crate::containers::buffer::Color;
+use crate::containers::buffer::PackedColor;
+use crate::containers::buffer::unpack_color;
+use crate::math::matrix::Matrix4;
+use crate::math::traits::Scalar;
+use crate::math::vector::BasicVectorOps;
+use crate::math::vector::FloatVectorOps;
+use crate::math::vector::Vector2;
+use crate::math::vector::Vector3;
+use crate::math::vector::Vector4;
+
+const FRUSTUM: [Vector4<f32>; 6] = [
+    Vector4::build(1., 0., 0., 1.),
+    Vector4::build(-1., 0., 0., 1.),
+    Vector4::build(0., 1., 0., 1.),
+    Vector4::build(0., -1., 0., 1.),
+    Vector4::build(0., 0., 1., 1.),
+    Vector4::build(0., 0., -1., 1.),
+];
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* SAMPLER */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub trait Sampler {
+    fn sample(&self, uv: Vector2<f32>) -> Color;
+
+    fn sample_grad(&self, uv: Vector2<f32>, _ddx: Vector2<f32>, _ddy: Vector2<f32>) -> Color {
+        self.sample(uv)
+    }
+}
+
+fn texel<T>(buffer: &Buffer2<T>, uv: Vector2<f32>) -> Option<T>
+where
+    T: Clone + Copy,
+{
+    let x = (uv.x.rem_euclid(1.) * buffer.width as f32) as usize;
+    let y = (uv.y.rem_euclid(1.) * buffer.height as f32) as usize;
+    buffer.get(x.min(buffer.width.saturating_sub(1)), y.min(buffer.height.saturating_sub(1)))
+}
+
+impl Sampler for Buffer2<Color> {
+    fn sample(&self, uv: Vector2<f32>) -> Color {
+        texel(self, uv).unwrap_or(self.clear_value)
+    }
+}
+
+impl Sampler for Buffer2<PackedColor> {
+    fn sample(&self, uv: Vector2<f32>) -> Color {
+        unpack_color(texel(self, uv).unwrap_or(self.clear_value))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* PIPELINE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Pipeline<S> {
+    pub rasterizer: Rasterizer,
+    pub viewport: Option<Viewport>,
+    pub sampler: S,
+}
+
+impl<S> Pipeline<S> {
+    pub fn build(sampler: S) -> Self {
+        Self { rasterizer: Rasterizer::new(), viewport: None, sampler }
+    }
+
+    pub fn draw<T, D, I, V>(
+        &self,
+        bitmap: &mut Bitmap<T, D>,
+        vertices: &[I],
+        vertex: impl Fn(&I) -> Vertex<V>,
+        fragment: impl FnMut(&Fragment<V>, &S) -> Option<T>,
+    ) -> usize
+    where
+        T: Scalar,
+        V: Varying,
+    {
+        let indices: Vec<[usize; 3]> = (0..vertices.len() / 3).map(|i| [i * 3, i * 3 + 1, i * 3 + 2]).collect();
+        self.draw_indexed(bitmap, vertices, &indices, vertex, fragment)
+    }
+
+    pub fn draw_indexed<T, D, I, V>(
+        &self,
+        bitmap: &mut Bitmap<T, D>,
+        vertices: &[I],
+        indices: &[[usize; 3]],
+        vertex: impl Fn(&I) -> Vertex<V>,
+        mut fragment: impl FnMut(&Fragment<V>, &S) -> Option<T>,
+    ) -> usize
+    where
+        T: Scalar,
+        V: Varying,
+    {
+        let viewport = self.viewport.unwrap_or(Viewport::build(0., 0., bitmap.dim.x as f32, bitmap.dim.y as f32));
+        let transformed: Vec<Vertex<V>> = vertices.iter().map(vertex).collect();
+        let mut shade = |input: &Fragment<V>| {
+            let data = fragment(input, &self.sampler)?;
+            Some(BitmapPayload { position: input.position, data, depth: input.depth })
+        };
+
+        let mut written = 0;
+        for triangle in indices {
+            let corners = triangle.map(|index| transformed[index]);
+            let outside = |plane: &Vector4<f32>| corners.map(|corner| plane.inner_product(corner.position) < 0.);
+            if FRUSTUM.iter().any(|plane| outside(plane).iter().all(|&out| out)) {
+                continue;
+            }
+
+            let polygon = match FRUSTUM.iter().any(|plane| outside(plane).iter().any(|&out| out)) {
+                | true => FRUSTUM.iter().fold(corners.to_vec(), |polygon, &plane| clip_polygon(&polygon, plane)),
+                | false => corners.to_vec(),
+            };
+            if polygon.iter().any(|corner| corner.position.w <= f32::EPSILON) {
+                continue;
+            }
+
+            let screen: Vec<_> = polygon.into_iter().map(|corner| viewport.project(corner)).collect();
+            written += (1..screen.len().saturating_sub(1))
+                .map(|i| self.rasterizer.draw_screen(bitmap, [screen[0], screen[i], screen[i + 1]], &mut shade))
+                .sum::<usize>();
+        }
+        written
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TRANSFORMS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Matrix4<f32> {
+    let focal = 1. / (fov_y / 2.).tan();
+    Matrix4::build([
+        [focal / aspect, 0., 0., 0.],
+        [0., focal, 0., 0.],
+        [0., 0., (far + near) / (near - far), 2. * far * near / (near - far)],
+        [0., 0., -1., 0.],
+    ])
+}
+
+pub fn look_at(eye: Vector3<f32>, target: Vector3<f32>, up: Vector3<f32>) -> Matrix4<f32> {
+    let forward = (target - eye).normalize();
+    let side = forward.cross_product(up).normalize();
+    let up = side.cross_product(forward);
+    Matrix4::build([
+        [side.x, side.y, side.z, -side.inner_product(eye)],
+        [up.x, up.y, up.z, -up.inner_product(eye)],
+        [-forward.x, -forward.y, -forward.z, forward.inner_product(eye)],
+        [0., 0., 0., 1.],
+    ])
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::containers::buffer::pack_color;
+    use crate::vec2;
+    use crate::vec3;
+    use crate::vec4;
+
+    fn quad(z: f32, size: f32) -> [(Vector3<f32>, Vector2<f32>); 6] {
+        let corner = |u: f32, v: f32| (vec3!((u * 2. - 1.) * size, (1. - v * 2.) * size, z), vec2!(u, v));
+        [
+            corner(0., 0.),
+            corner(0., 1.),
+            corner(1., 1.),
+            corner(0., 0.),
+            corner(1., 1.),
+            corner(1., 0.),
+        ]
+    }
+
+    #[test]
+    fn textured_quad() {
+        let mut checker = Buffer2::new(2, 2, 0xff00_0000);
+        checker.set(1, 0, 0xffff_ffff);
+        checker.set(0, 1, 0xffff_ffff);
+
+        let mut bitmap = Bitmap::<PackedColor, ()>::new(8, 8, 0, f32::INFINITY);
+        let pipeline = Pipeline::build(checker);
+        let drawn = pipeline.draw(
+            &mut bitmap,
+            &quad(0., 1.),
+            |&(position, uv)| Vertex::build(vec4!(position.x, position.y, position.z, 1.), uv),
+            |fragment, sampler| Some(pack_color(sampler.sample(fragment.varying))),
+        );
+        assert!(drawn == 64);
+        assert!(bitmap.main.get(1, 1) == Some(0xff00_0000) && bitmap.main.get(6, 1) == Some(0xffff_ffff));
+        assert!(bitmap.main.get(1, 6) == Some(0xffff_ffff) && bitmap.main.get(6, 6) == Some(0xff00_0000));
+    }
+
+    #[test]
+    fn frustum_and_viewport() {
+        let mut bitmap = Bitmap::<u32, ()>::new(16, 16, 0, f32::INFINITY);
+        let mut pipeline = Pipeline::build(());
+        let transform = perspective(std::f32::consts::FRAC_PI_2, 1., 0.5, 50.);
+
+        let mut hits = vec![0; 256];
+        let drawn = pipeline.draw(
+            &mut bitmap,
+            &quad(-2., 40.),
+            |&(position, uv)| Vertex::build(transform * vec4!(position.x, position.y, position.z, 1.), uv),
+            |fragment, _| {
+                hits[fragment.position.y * 16 + fragment.position.x] += 1;
+                assert!((0. ..=1.).contains(&fragment.varying.x) && (0. ..=1.).contains(&fragment.depth));
+                Some(1)
+            },
+        );
+        assert!(drawn == 256 && hits.iter().all(|&count| count == 1));
+
+        pipeline.viewport = Some(Viewport::build(4., 2., 8., 6.));
+        bitmap.clear();
+        let drawn = pipeline.draw(
+            &mut bitmap,
+            &quad(0., 1.),
+            |&(position, _)| Vertex::build(vec4!(position.x, position.y, position.z, 1.), 0.),
+            |_, _| Some(2),
+        );
+        let inside = |x: usize, y: usize| (4..12).contains(&x) && (2..8).contains(&y);
+        assert!(drawn == 48);
+        assert!((0..16).all(|y| (0..16).all(|x| (bitmap.main.get(x, y) == Some(2)) == inside(x, y))));
+
+        let behind = quad(3., 1.).map(|(position, _)| position);
+        let drawn = pipeline.draw(
+            &mut bitmap,
+            &behind,
+            |&position| Vertex::build(transform * vec4!(position.x, position.y, position.z, 1.), ()),
+            |_, _| Some(3),
+        );
+        assert!(drawn == 0);
+    }
+
+    #[test]
+    fn camera_depth_order() {
+        let camera = perspective(1., 1., 0.1, 100.) * look_at(vec3!(0., 0., 5.), vec3!(0., 0., 0.), vec3!(0., 1., 0.));
+        let near = quad(1., 0.5).map(|(position, _)| (position, 1u32));
+        let far = quad(-1., 1.5).map(|(position, _)| (position, 2u32));
+        let mut triangles = near.to_vec();
+        triangles.extend(far);
+
+        let mut pipeline = Pipeline::build(());
+        pipeline.rasterizer.cull = super::super::raster::Cull::None;
+        let render = |order: &[(Vector3<f32>, u32)]| {
+            let mut bitmap = Bitmap::<u32, ()>::new(32, 32, 0, f32::INFINITY);
+            pipeline.draw(
+                &mut bitmap,
+                order,
+                |&(position, id)| Vertex::build(camera * vec4!(position.x, position.y, position.z, 1.), id as f32),
+                |fragment, _| Some(fragment.varying.round() as u32),
+            );
+            bitmap.main.data
+        };
+
+        let forward = render(&triangles);
+        triangles.reverse();
+        assert!(forward == render(&triangles));
+        assert!(forward[16 * 32 + 16] == 1 && forward[16 * 32 + 10] == 2 && forward[0] == 0);
+    }
+}
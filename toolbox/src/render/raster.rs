@@ -0,0 +1,505 @@
+use crate::containers::buffer::Bitmap;
+use crate::containers::buffer::BitmapPayload;
+use crate::math::traits::Linear;
+use crate::math::traits::Scalar;
+use crate::math::vector::BasicVectorOps;
+use crate::math::vector::Vector2;
+use crate::math::vector::Vector3;
+use crate::math::vector::Vector4;
+use crate::vec2;
+use crate::vec3;
+
+const SUBPIXEL_BITS: u32 = 8;
+const SUBPIXEL: f32 = (1 << SUBPIXEL_BITS) as f32;
+const FIXED_LIMIT: i64 = 1 << 30;
+const GUARD_BAND: f32 = 16.;
+const NEAR_PLANE: Vector4<f32> = Vector4::build(0., 0., 1., 1.);
+const CLIP_PLANES: [Vector4<f32>; 5] = [
+    NEAR_PLANE,
+    Vector4::build(1., 0., 0., GUARD_BAND),
+    Vector4::build(-1., 0., 0., GUARD_BAND),
+    Vector4::build(0., 1., 0., GUARD_BAND),
+    Vector4::build(0., -1., 0., GUARD_BAND),
+];
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* VARYING */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub trait Varying
+where
+    Self: Linear,
+{
+}
+
+impl<T> Varying for T where T: Linear {}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* RASTERIZER */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vertex<V> {
+    pub position: Vector4<f32>,
+    pub varying: V,
+}
+
+impl<V> Vertex<V> {
+    pub fn build(position: Vector4<f32>, varying: V) -> Self {
+        Self { position, varying }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fragment<V> {
+    pub position: Vector2<usize>,
+    pub depth: f32,
+    pub varying: V,
+    pub ddx: V,
+    pub ddy: V,
+    pub barycentric: [f32; 3],
+    pub front_facing: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthTest {
+    Less,
+    LessEqual,
+    Always,
+}
+
+impl DepthTest {
+    pub fn passes(self, depth: f32, stored: f32) -> bool {
+        match self {
+            | Self::Less => depth < stored,
+            | Self::LessEqual => depth <= stored,
+            | Self::Always => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cull {
+    None,
+    Back,
+    Front,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rasterizer {
+    pub depth_test: DepthTest,
+    pub depth_write: bool,
+    pub cull: Cull,
+}
+
+impl Default for Rasterizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenVertex<V> {
+    pub position: Vector3<f32>,
+    pub inverse_w: f32,
+    pub varying: V,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Viewport {
+    pub fn build(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height, near: 0., far: 1. }
+    }
+
+    pub fn project<V>(&self, vertex: Vertex<V>) -> ScreenVertex<V>
+    where
+        V: Varying,
+    {
+        let inverse_w = 1. / vertex.position.w;
+        let [x, y, z] = [vertex.position.x, vertex.position.y, vertex.position.z].map(|value| value * inverse_w);
+        ScreenVertex {
+            position: vec3!(
+                self.x + (x + 1.) * 0.5 * self.width,
+                self.y + (1. - y) * 0.5 * self.height,
+                self.near + (z * 0.5 + 0.5) * (self.far - self.near)
+            ),
+            inverse_w,
+            varying: vertex.varying.scale(inverse_w),
+        }
+    }
+}
+
+impl Rasterizer {
+    pub fn new() -> Self {
+        Self {
+            depth_test: DepthTest::Less,
+            depth_write: true,
+            cull: Cull::Back,
+        }
+    }
+
+    pub fn draw_triangle<T, D, V>(
+        &self,
+        bitmap: &mut Bitmap<T, D>,
+        triangle: [Vertex<V>; 3],
+        mut fragment: impl FnMut(&Fragment<V>) -> Option<BitmapPayload<T>>,
+    ) -> usize
+    where
+        T: Scalar,
+        V: Varying,
+    {
+        let viewport = Viewport::build(0., 0., bitmap.dim.x as f32, bitmap.dim.y as f32);
+        let clipped = CLIP_PLANES.iter().fold(triangle.to_vec(), |polygon, &plane| clip_polygon(&polygon, plane));
+        (1..clipped.len().saturating_sub(1))
+            .filter(|&i| [0, i, i + 1].iter().all(|&j| clipped[j].position.w > f32::EPSILON))
+            .map(|i| {
+                let screen = [clipped[0], clipped[i], clipped[i + 1]].map(|vertex| viewport.project(vertex));
+                self.draw_screen(bitmap, screen, &mut fragment)
+            })
+            .sum()
+    }
+
+    pub fn draw_screen<T, D, V>(
+        &self,
+        bitmap: &mut Bitmap<T, D>,
+        triangle: [ScreenVertex<V>; 3],
+        mut fragment: impl FnMut(&Fragment<V>) -> Option<BitmapPayload<T>>,
+    ) -> usize
+    where
+        T: Scalar,
+        V: Varying,
+    {
+        let (width, height) = (bitmap.dim.x, bitmap.dim.y);
+        let fixed = |vertex: &ScreenVertex<V>| {
+            [vertex.position.x, vertex.position.y].map(|value| (value * SUBPIXEL).round() as i64)
+        };
+        let [a, mut b, mut c] = triangle;
+        let (fa, mut fb, mut fc) = (fixed(&a), fixed(&b), fixed(&c));
+        // callers clip to the guard band first, anything still past it would overflow the edge functions
+        if [fa, fb, fc].iter().flatten().any(|value| value.abs() > FIXED_LIMIT) {
+            return 0;
+        }
+
+        let area = edge(&fa, &fb, fc);
+        let front_facing = area < 0;
+        let culled = match self.cull {
+            | Cull::None => false,
+            | Cull::Back => !front_facing,
+            | Cull::Front => front_facing,
+        };
+        if area == 0 || culled {
+            return 0;
+        }
+        if area < 0 {
+            (b, c, fb, fc) = (c, b, fc, fb);
+        }
+        let area = area.abs() as f32;
+
+        let bounds = |axis: usize, limit: usize| {
+            let low = fa[axis].min(fb[axis]).min(fc[axis]);
+            let high = fa[axis].max(fb[axis]).max(fc[axis]);
+            let first = (low >> SUBPIXEL_BITS).clamp(0, limit as i64) as usize;
+            let last = ((high >> SUBPIXEL_BITS) + 1).clamp(0, limit as i64) as usize;
+            first..last
+        };
+        let (columns, rows) = (bounds(0, width), bounds(1, height));
+        if columns.is_empty() || rows.is_empty() {
+            return 0;
+        }
+
+        let edges = [(fb, fc), (fc, fa), (fa, fb)].map(|(from, to)| {
+            let bias = match top_left(&from, &to) {
+                | true => 0,
+                | false => -1,
+            };
+            (from, to, bias)
+        });
+
+        let steps = edges.map(|(from, to, _)| {
+            [
+                (from[1] - to[1]) as f32 * SUBPIXEL / area,
+                (to[0] - from[0]) as f32 * SUBPIXEL / area,
+            ]
+        });
+        let resolve = |linear: [f32; 3]| {
+            let inverse_w = linear[0] * a.inverse_w + linear[1] * b.inverse_w + linear[2] * c.inverse_w;
+            let varying = a
+                .varying
+                .scale(linear[0])
+                .add(b.varying.scale(linear[1]))
+                .add(c.varying.scale(linear[2]))
+                .scale(1. / inverse_w);
+            (inverse_w, varying)
+        };
+
+        // screen space plane gradients of the weighted varyings, constant across the triangle
+        let gradient = |axis: usize| {
+            let inverse_w = steps[0][axis] * a.inverse_w + steps[1][axis] * b.inverse_w + steps[2][axis] * c.inverse_w;
+            let varying = a
+                .varying
+                .scale(steps[0][axis])
+                .add(b.varying.scale(steps[1][axis]))
+                .add(c.varying.scale(steps[2][axis]));
+            (inverse_w, varying)
+        };
+        let (gradient_x, gradient_y) = (gradient(0), gradient(1));
+
+        let half = 1 << (SUBPIXEL_BITS - 1);
+        let mut written = 0;
+        for y in rows {
+            let py = ((y as i64) << SUBPIXEL_BITS) + half;
+            for x in columns.clone() {
+                let point = [((x as i64) << SUBPIXEL_BITS) + half, py];
+                let weights = edges.map(|(from, to, _)| edge(&from, &to, point));
+                if weights.iter().zip(&edges).any(|(&weight, &(_, _, bias))| weight + bias < 0) {
+                    continue;
+                }
+
+                let linear = weights.map(|weight| weight as f32 / area);
+                let (inverse_w, varying) = resolve(linear);
+                let barycentric = [
+                    linear[0] * a.inverse_w / inverse_w,
+                    linear[1] * b.inverse_w / inverse_w,
+                    linear[2] * c.inverse_w / inverse_w,
+                ];
+                // quotient rule on varying / inverse_w
+                let derivative =
+                    |(slope, weighted): (f32, V)| weighted.add(varying.scale(-slope)).scale(1. / inverse_w);
+
+                let input = Fragment {
+                    position: vec2!(usize; x, y),
+                    depth: linear[0] * a.position.z + linear[1] * b.position.z + linear[2] * c.position.z,
+                    varying,
+                    ddx: derivative(gradient_x),
+                    ddy: derivative(gradient_y),
+                    barycentric,
+                    front_facing,
+                };
+                let Some(payload) = fragment(&input) else {
+                    continue;
+                };
+
+                let [px, py] = payload.position.array();
+                let Some(stored) = bitmap.depth.get(px, py) else {
+                    continue;
+                };
+                if !self.depth_test.passes(payload.depth, stored) {
+                    continue;
+                }
+
+                bitmap.main.set_unchecked(px, py, payload.data);
+                if self.depth_write {
+                    bitmap.depth.set_unchecked(px, py, payload.depth);
+                }
+                written += 1;
+            }
+        }
+        written
+    }
+}
+
+fn edge(from: &[i64; 2], to: &[i64; 2], point: [i64; 2]) -> i64 {
+    (to[0] - from[0]) * (point[1] - from[1]) - (to[1] - from[1]) * (point[0] - from[0])
+}
+
+fn top_left(from: &[i64; 2], to: &[i64; 2]) -> bool {
+    let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+    (dy == 0 && dx > 0) || dy < 0
+}
+
+pub fn clip_polygon<V>(polygon: &[Vertex<V>], plane: Vector4<f32>) -> Vec<Vertex<V>>
+where
+    V: Varying,
+{
+    let distance = |vertex: &Vertex<V>| plane.inner_product(vertex.position);
+    let mut out = Vec::with_capacity(polygon.len() + 1);
+    (0..polygon.len()).for_each(|i| {
+        let (current, next) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        let (dc, dn) = (distance(&current), distance(&next));
+        if dc >= 0. {
+            out.push(current);
+        }
+        if (dc >= 0.) != (dn >= 0.) {
+            let t = dc / (dc - dn);
+            out.push(Vertex {
+                position: current.position + (next.position - current.position) * t,
+                varying: current.varying.lerp(next.varying, t),
+            });
+        }
+    });
+    out
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec4;
+
+    fn vertex<V>(x: f32, y: f32, z: f32, w: f32, varying: V) -> Vertex<V> {
+        Vertex::build(vec4!(x, y, z, w), varying)
+    }
+
+    fn payload<V>(value: u32) -> impl FnMut(&Fragment<V>) -> Option<BitmapPayload<u32>> {
+        move |fragment| {
+            Some(BitmapPayload {
+                position: fragment.position,
+                data: value,
+                depth: fragment.depth,
+            })
+        }
+    }
+
+    #[test]
+    fn fill_rule() {
+        let mut bitmap = Bitmap::<u32, ()>::new(13, 11, 0, f32::INFINITY);
+        let raster = Rasterizer {
+            depth_test: DepthTest::Always,
+            depth_write: false,
+            cull: Cull::None,
+        };
+
+        let center = vertex(0.13, -0.07, 0., 1., ());
+        let ring: Vec<Vertex<()>> = (0..9)
+            .map(|i| {
+                let angle = i as f32 / 9. * std::f32::consts::TAU;
+                vertex(1.6 * angle.cos(), 1.6 * angle.sin(), 0., 1., ())
+            })
+            .collect();
+        let mut hits = vec![0; 13 * 11];
+        (0..9).for_each(|i| {
+            raster.draw_triangle(&mut bitmap, [center, ring[i], ring[(i + 1) % 9]], |fragment| {
+                hits[fragment.position.y * 13 + fragment.position.x] += 1;
+                None
+            });
+        });
+        assert!(hits.iter().all(|&count| count == 1));
+
+        let quad = [
+            vertex(-1., -1., 0., 1., ()),
+            vertex(1., -1., 0., 1., ()),
+            vertex(1., 1., 0., 1., ()),
+        ];
+        let other = [
+            vertex(-1., -1., 0., 1., ()),
+            vertex(1., 1., 0., 1., ()),
+            vertex(-1., 1., 0., 1., ()),
+        ];
+        let drawn =
+            raster.draw_triangle(&mut bitmap, quad, payload(1)) + raster.draw_triangle(&mut bitmap, other, payload(2));
+        assert!(drawn == 13 * 11 && bitmap.main.data.iter().all(|&value| value != 0));
+    }
+
+    #[test]
+    fn depth_and_culling() {
+        let mut bitmap = Bitmap::<u32, ()>::new(8, 8, 0, 1.);
+        let triangle = |z: f32| {
+            [
+                vertex(-1., -1., z, 1., ()),
+                vertex(3., -1., z, 1., ()),
+                vertex(-1., 3., z, 1., ()),
+            ]
+        };
+        let mut raster = Rasterizer::new();
+
+        assert!(raster.draw_triangle(&mut bitmap, triangle(0.), payload(1)) == 64);
+        assert!(raster.draw_triangle(&mut bitmap, triangle(0.5), payload(2)) == 0);
+        assert!(raster.draw_triangle(&mut bitmap, triangle(0.), payload(3)) == 0);
+        raster.depth_test = DepthTest::LessEqual;
+        assert!(raster.draw_triangle(&mut bitmap, triangle(0.), payload(4)) == 64);
+
+        raster.depth_test = DepthTest::Always;
+        raster.depth_write = false;
+        assert!(raster.draw_triangle(&mut bitmap, triangle(0.5), payload(5)) == 64);
+        assert!(bitmap.main.data.iter().all(|&v| v == 5) && bitmap.depth.data.iter().all(|&d| d == 0.5));
+
+        let [a, b, c] = triangle(0.);
+        assert!(raster.draw_triangle(&mut bitmap, [a, c, b], payload(6)) == 0);
+        raster.cull = Cull::Front;
+        assert!(raster.draw_triangle(&mut bitmap, [a, b, c], payload(6)) == 0);
+        let mut facing = Vec::new();
+        raster.draw_triangle(&mut bitmap, [a, c, b], |fragment| {
+            facing.push(fragment.front_facing);
+            None
+        });
+        assert!(facing.len() == 64 && facing.iter().all(|&front| !front));
+    }
+
+    #[test]
+    fn perspective_correct() {
+        let mut bitmap = Bitmap::<u32, ()>::new(64, 64, 0, f32::INFINITY);
+        let view = |x: f32, y: f32, z: f32| vertex(x, y, z - 1., z, x);
+        let triangle = [view(-1., -1., 2.), view(1., -1., 4.), view(-1., 1., 2.)];
+
+        let mut checked = 0;
+        Rasterizer::new().draw_triangle(&mut bitmap, triangle, |fragment| {
+            let ndc = (fragment.position.x as f32 + 0.5) / 32. - 1.;
+            assert!((fragment.varying - 3. * ndc / (1. - ndc)).abs() < 1e-3);
+            assert!((fragment.ddx - 3. / (32. * (1. - ndc) * (1. - ndc))).abs() < 1e-3 && fragment.ddy.abs() < 1e-3);
+            assert!((fragment.barycentric.iter().sum::<f32>() - 1.).abs() < 1e-5);
+            checked += 1;
+            None
+        });
+        assert!(checked > 200);
+    }
+
+    #[test]
+    fn guard_band_clipping() {
+        let mut bitmap = Bitmap::<u32, ()>::new(16, 16, 0, f32::INFINITY);
+        let triangle = [
+            vertex(-1., -1., 0., 1., -1.),
+            vertex(1e7, -1., 0., 1., 1e7),
+            vertex(-1., 1e7, 0., 1., -1.),
+        ];
+        assert!(CLIP_PLANES.iter().fold(triangle.to_vec(), |polygon, &plane| clip_polygon(&polygon, plane)).len() == 4);
+
+        let raster = Rasterizer { cull: Cull::None, ..Rasterizer::new() };
+        let drawn = raster.draw_triangle(&mut bitmap, triangle, |fragment| {
+            let ndc = (fragment.position.x as f32 + 0.5) / 8. - 1.;
+            assert!((fragment.varying - ndc).abs() < 1e-3);
+            None
+        });
+        assert!(drawn == 0);
+        assert!(raster.draw_triangle(&mut bitmap, triangle, payload(1)) == 16 * 16);
+    }
+
+    #[test]
+    fn near_clipping() {
+        let mut bitmap = Bitmap::<u32, ()>::new(32, 32, 0, f32::INFINITY);
+        let triangle = [
+            vertex(-0.5, -0.5, 0.5, 1., 0.),
+            vertex(0.5, -0.5, 0.5, 1., 0.),
+            vertex(0., 0.5, -3., -0.5, 1.),
+        ];
+        assert!(clip_polygon(&triangle, NEAR_PLANE).len() == 4);
+
+        let (mut depths, mut varyings) = (Vec::new(), Vec::new());
+        let raster = Rasterizer { cull: Cull::None, ..Rasterizer::new() };
+        let drawn = raster.draw_triangle(&mut bitmap, triangle, |fragment| {
+            depths.push(fragment.depth);
+            varyings.push(fragment.varying);
+            Some(BitmapPayload { position: fragment.position, data: 1, depth: fragment.depth })
+        });
+        assert!(drawn > 0 && drawn == depths.len());
+        assert!(depths.iter().all(|depth| (-1e-4..=1.).contains(depth)));
+        assert!(varyings.iter().all(|varying| (-1e-4..=1.0001).contains(varying)));
+
+        let behind = [
+            vertex(-0.5, -0.5, -2., 1., 0.),
+            vertex(0.5, -0.5, -2., 1., 0.),
+            vertex(0., 0.5, -2., 1., 0.),
+        ];
+        assert!(
+            clip_polygon(&behind, NEAR_PLANE).is_empty() && raster.draw_triangle(&mut bitmap, behind, payload(2)) == 0
+        );
+    }
+}
@@ -0,0 +1,413 @@
+use super::pipeline::Sampler;
+use crate::containers::buffer::Buffer2;
+use crate::containers::buffer::Color;
+use crate::containers::buffer::PackedColor;
+use crate::containers::buffer::unpack_color;
+use crate::math::vector::FloatVectorOps;
+use crate::math::vector::Vector2;
+use crate::math::vector::Vector3;
+use crate::vec2;
+
+const KAISER_RADIUS: isize = 3;
+const KAISER_ALPHA: f32 = 4.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+    Trilinear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Address {
+    Wrap,
+    Clamp,
+    Mirror,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MipFilter {
+    Box,
+    Kaiser,
+}
+
+impl Address {
+    pub fn resolve(self, coordinate: isize, size: usize) -> usize {
+        let size = size as isize;
+        let resolved = match self {
+            | Self::Wrap => coordinate.rem_euclid(size),
+            | Self::Clamp => coordinate.clamp(0, size - 1),
+            | Self::Mirror => {
+                let folded = coordinate.rem_euclid(2 * size);
+                match folded < size {
+                    | true => folded,
+                    | false => 2 * size - 1 - folded,
+                }
+            }
+        };
+        resolved as usize
+    }
+
+    // fold a texel coordinate into one period while still in f32, so huge or infinite uvs can't overflow
+    fn reduce(self, coordinate: f32, size: usize) -> f32 {
+        let size = size as f32;
+        match self {
+            | _ if !coordinate.is_finite() => 0.,
+            | Self::Wrap => coordinate.rem_euclid(size),
+            | Self::Clamp => coordinate.clamp(-1., size),
+            | Self::Mirror => coordinate.rem_euclid(2. * size),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEXTURE */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+pub struct Texture {
+    levels: Vec<Buffer2<Color>>,
+    pub filter: Filter,
+    pub address: Address,
+    pub anisotropy: usize,
+}
+
+impl Texture {
+    pub fn build(image: Buffer2<Color>) -> Self {
+        assert!(image.width > 0 && image.height > 0, "texture must not be empty");
+        Self {
+            levels: vec![image],
+            filter: Filter::Bilinear,
+            address: Address::Wrap,
+            anisotropy: 1,
+        }
+    }
+
+    pub fn from_packed(image: &Buffer2<PackedColor>) -> Self {
+        let mut colors = Buffer2::new(image.width, image.height, unpack_color(image.clear_value));
+        colors.data.iter_mut().zip(&image.data).for_each(|(color, &packed)| *color = unpack_color(packed));
+        Self::build(colors)
+    }
+
+    pub fn levels(&self) -> &[Buffer2<Color>] {
+        &self.levels
+    }
+
+    pub fn width(&self) -> usize {
+        self.levels[0].width
+    }
+
+    pub fn height(&self) -> usize {
+        self.levels[0].height
+    }
+
+    pub fn generate_mipmaps(&mut self, filter: MipFilter) {
+        self.levels.truncate(1);
+        while let Some(last) = self.levels.last().filter(|last| last.width > 1 || last.height > 1) {
+            let next = match filter {
+                | MipFilter::Box => downsample_box(last),
+                | MipFilter::Kaiser => downsample_kaiser(last),
+            };
+            self.levels.push(next);
+        }
+    }
+
+    pub fn lod(&self, ddx: Vector2<f32>, ddy: Vector2<f32>) -> f32 {
+        let (x, y) = self.footprint(ddx, ddy);
+        x.max(y).max(f32::MIN_POSITIVE).log2()
+    }
+
+    pub fn sample_level(&self, uv: Vector2<f32>, lod: f32) -> Color {
+        let lod = lod.clamp(0., (self.levels.len() - 1) as f32);
+        match self.filter {
+            | Filter::Nearest => self.nearest(&self.levels[lod.round() as usize], uv),
+            | Filter::Bilinear => self.bilinear(&self.levels[lod.round() as usize], uv),
+            | Filter::Trilinear => {
+                let (low, t) = (lod.floor() as usize, lod.fract());
+                let high = (low + 1).min(self.levels.len() - 1);
+                let a = self.bilinear(&self.levels[low], uv);
+                match t > 0. && high != low {
+                    | true => a * (1. - t) + self.bilinear(&self.levels[high], uv) * t,
+                    | false => a,
+                }
+            }
+        }
+    }
+
+    fn footprint(&self, ddx: Vector2<f32>, ddy: Vector2<f32>) -> (f32, f32) {
+        let size = vec2!(self.width(), self.height());
+        (vec2!(ddx.x * size.x, ddx.y * size.y).length(), vec2!(ddy.x * size.x, ddy.y * size.y).length())
+    }
+
+    fn nearest(&self, level: &Buffer2<Color>, uv: Vector2<f32>) -> Color {
+        let (u, v) = (uv.x * level.width as f32, uv.y * level.height as f32);
+        let x = self.address.resolve(self.address.reduce(u, level.width).floor() as isize, level.width);
+        let y = self.address.resolve(self.address.reduce(v, level.height).floor() as isize, level.height);
+        level.get_unchecked(x, y)
+    }
+
+    fn bilinear(&self, level: &Buffer2<Color>, uv: Vector2<f32>) -> Color {
+        let u = self.address.reduce(uv.x * level.width as f32 - 0.5, level.width);
+        let v = self.address.reduce(uv.y * level.height as f32 - 0.5, level.height);
+        let (x0, y0) = (u.floor(), v.floor());
+        let (tx, ty) = (u - x0, v - y0);
+        let texel = |dx: isize, dy: isize| {
+            let x = self.address.resolve(x0 as isize + dx, level.width);
+            let y = self.address.resolve(y0 as isize + dy, level.height);
+            level.get_unchecked(x, y)
+        };
+        let top = texel(0, 0) * (1. - tx) + texel(1, 0) * tx;
+        let bottom = texel(0, 1) * (1. - tx) + texel(1, 1) * tx;
+        top * (1. - ty) + bottom * ty
+    }
+}
+
+impl Sampler for Texture {
+    fn sample(&self, uv: Vector2<f32>) -> Color {
+        self.sample_level(uv, 0.)
+    }
+
+    fn sample_grad(&self, uv: Vector2<f32>, ddx: Vector2<f32>, ddy: Vector2<f32>) -> Color {
+        let (x, y) = self.footprint(ddx, ddy);
+        let (major, minor, axis) = match x >= y {
+            | true => (x, y, ddx),
+            | false => (y, x, ddy),
+        };
+        let ratio = match minor > 0. {
+            | true => (major / minor).min(self.anisotropy.max(1) as f32),
+            | false => self.anisotropy.max(1) as f32,
+        };
+        let taps = ratio.ceil().max(1.) as usize;
+        let lod = (major / ratio).max(f32::MIN_POSITIVE).log2();
+        if taps == 1 {
+            return self.sample_level(uv, lod);
+        }
+
+        let total = (0..taps).fold(Vector3::zeros(), |total, i| {
+            let offset = (i as f32 + 0.5) / taps as f32 - 0.5;
+            total + self.sample_level(uv + axis * offset, lod)
+        });
+        total / taps as f32
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* MIPMAPS */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+fn downsample_box(source: &Buffer2<Color>) -> Buffer2<Color> {
+    let (width, height) = ((source.width / 2).max(1), (source.height / 2).max(1));
+    let mut out = Buffer2::new(width, height, source.clear_value);
+    (0..height).for_each(|y| {
+        (0..width).for_each(|x| {
+            let texel = |dx: usize, dy: usize| {
+                source.get_unchecked((x * 2 + dx).min(source.width - 1), (y * 2 + dy).min(source.height - 1))
+            };
+            out.set_unchecked(x, y, (texel(0, 0) + texel(1, 0) + texel(0, 1) + texel(1, 1)) * 0.25);
+        });
+    });
+    out
+}
+
+fn bessel_i0(x: f32) -> f32 {
+    let (mut sum, mut term, mut k) = (1., 1., 1.);
+    while term > sum * 1e-7 {
+        term *= (x / (2. * k)) * (x / (2. * k));
+        sum += term;
+        k += 1.;
+    }
+    sum
+}
+
+fn kaiser_weights() -> Vec<f32> {
+    let radius = KAISER_RADIUS as f32;
+    let taps: Vec<f32> = (-KAISER_RADIUS * 2 + 1..=KAISER_RADIUS * 2)
+        .map(|i| {
+            let distance = (i as f32 - 0.5) / 2.;
+            let sinc = match distance == 0. {
+                | true => 1.,
+                | false => (std::f32::consts::PI * distance).sin() / (std::f32::consts::PI * distance),
+            };
+            let window = bessel_i0(KAISER_ALPHA * (1. - (distance / radius).powi(2)).max(0.).sqrt());
+            sinc * window / bessel_i0(KAISER_ALPHA)
+        })
+        .collect();
+    let total: f32 = taps.iter().sum();
+    taps.iter().map(|weight| weight / total).collect()
+}
+
+fn downsample_kaiser(source: &Buffer2<Color>) -> Buffer2<Color> {
+    let weights = kaiser_weights();
+    let reach = KAISER_RADIUS * 2 - 1;
+    let reduce = |length: usize, fetch: &dyn Fn(usize) -> Color, out: usize| match length > 1 {
+        | true => weights.iter().enumerate().fold(Vector3::zeros(), |total, (i, &weight)| {
+            let index = (out as isize * 2 - reach + i as isize).clamp(0, length as isize - 1);
+            total + fetch(index as usize) * weight
+        }),
+        | false => fetch(0),
+    };
+
+    let (width, height) = ((source.width / 2).max(1), (source.height / 2).max(1));
+    let mut horizontal = Buffer2::new(width, source.height, source.clear_value);
+    (0..source.height).for_each(|y| {
+        (0..width).for_each(|x| {
+            horizontal.set_unchecked(x, y, reduce(source.width, &|i| source.get_unchecked(i, y), x));
+        });
+    });
+
+    let mut out = Buffer2::new(width, height, source.clear_value);
+    (0..height).for_each(|y| {
+        (0..width).for_each(|x| out.set_unchecked(x, y, reduce(source.height, &|i| horizontal.get_unchecked(x, i), y)));
+    });
+    out
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+/* TEST */
+/////////////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::containers::buffer::Bitmap;
+    use crate::containers::buffer::pack_color;
+    use crate::math::vector::Vector4;
+    use crate::render::pipeline::Pipeline;
+    use crate::render::raster::Cull;
+    use crate::render::raster::Vertex;
+    use crate::vec3;
+    use crate::vec4;
+
+    fn checker(width: usize, height: usize) -> Buffer2<Color> {
+        let mut out = Buffer2::new(width, height, Vector3::zeros());
+        (0..height)
+            .for_each(|y| (0..width).for_each(|x| out.set_unchecked(x, y, Vector3::splat(((x + y) % 2) as f32))));
+        out
+    }
+
+    fn close(a: Color, b: Color) -> bool {
+        (a - b).array().iter().all(|delta| delta.abs() < 1e-4)
+    }
+
+    #[test]
+    fn mipmap_chain() {
+        let mut texture = Texture::build(checker(8, 4));
+        texture.generate_mipmaps(MipFilter::Box);
+        let sizes: Vec<_> = texture.levels().iter().map(|level| (level.width, level.height)).collect();
+        assert!(sizes == [(8, 4), (4, 2), (2, 1), (1, 1)]);
+        assert!(texture.levels()[1..].iter().all(|level| level.data.iter().all(|&c| close(c, Vector3::splat(0.5)))));
+
+        let mut flat = Texture::build(Buffer2::new(13, 7, vec3!(0.2, 0.4, 0.8)));
+        flat.generate_mipmaps(MipFilter::Kaiser);
+        assert!(flat.levels().len() == 4 && flat.levels()[3].width == 1 && flat.levels()[2].height == 1);
+        assert!(flat.levels().iter().all(|level| level.data.iter().all(|&c| close(c, vec3!(0.2, 0.4, 0.8)))));
+
+        let mut sharp = Texture::build(checker(16, 16));
+        sharp.generate_mipmaps(MipFilter::Kaiser);
+        let level = &sharp.levels()[1];
+        let mean = level.data.iter().map(|color| color.x).sum::<f32>() / level.data.len() as f32;
+        assert!((mean - 0.5).abs() < 0.02 && level.data.iter().all(|color| (color.x - 0.5).abs() < 0.1));
+    }
+
+    #[test]
+    fn addressing_and_filtering() {
+        let mut ramp = Buffer2::new(4, 1, Vector3::zeros());
+        (0..4).for_each(|x| ramp.set_unchecked(x, 0, Vector3::splat(x as f32)));
+        let mut texture = Texture::build(ramp);
+        texture.filter = Filter::Nearest;
+
+        let at = |texture: &Texture, u: f32| texture.sample(vec2!(u, 0.5)).x;
+        assert!(at(&texture, 1.1) == 0. && at(&texture, -0.1) == 3.);
+        texture.address = Address::Clamp;
+        assert!(at(&texture, 1.1) == 3. && at(&texture, -0.1) == 0.);
+        texture.address = Address::Mirror;
+        assert!(at(&texture, 1.1) == 3. && at(&texture, -0.1) == 0. && at(&texture, 1.6) == 1.);
+        assert!((-9..9).all(|i| Address::Mirror.resolve(i, 3) == [0, 1, 2, 2, 1, 0][i.rem_euclid(6) as usize]));
+
+        texture.filter = Filter::Bilinear;
+        texture.address = Address::Clamp;
+        assert!((at(&texture, 0.5) - 1.5).abs() < 1e-5 && (at(&texture, 0.3125) - 0.75).abs() < 1e-5);
+        texture.address = Address::Wrap;
+        assert!((at(&texture, 0.) - 1.5).abs() < 1e-5);
+        assert!((at(&texture, 1e6 + 0.5) - 1.5).abs() < 1e-5 && (at(&texture, -2.) - 1.5).abs() < 1e-5);
+
+        // out of range uvs must not overflow the texel arithmetic in any mode
+        [Filter::Nearest, Filter::Bilinear].into_iter().for_each(|filter| {
+            [Address::Wrap, Address::Clamp, Address::Mirror].into_iter().for_each(|address| {
+                (texture.filter, texture.address) = (filter, address);
+                [f32::INFINITY, f32::NEG_INFINITY, f32::NAN, 1e30, -1e30, f32::MAX].into_iter().for_each(|u| {
+                    assert!((0. ..=3.).contains(&at(&texture, u)));
+                });
+            });
+        });
+
+        let mut mipped = Texture::build(checker(8, 8));
+        mipped.generate_mipmaps(MipFilter::Box);
+        mipped.filter = Filter::Trilinear;
+        assert!(mipped.lod(vec2!(1. / 8., 0.), vec2!(0., 1. / 8.)).abs() < 1e-5);
+        assert!((mipped.lod(vec2!(0.5, 0.), vec2!(0., 0.125)) - 2.).abs() < 1e-5);
+        let texel = vec2!(1.5 / 8., 0.5 / 8.);
+        assert!(close(mipped.sample_level(texel, 0.), Vector3::splat(1.)));
+        assert!(
+            close(mipped.sample_level(texel, 0.5), Vector3::splat(0.75))
+                && close(mipped.sample_level(texel, 9.), Vector3::splat(0.5))
+        );
+    }
+
+    #[test]
+    fn anisotropic_sampling() {
+        let mut stripes = Buffer2::new(16, 16, Vector3::zeros());
+        (0..16).for_each(|y| (0..16).for_each(|x| stripes.set_unchecked(x, y, Vector3::splat((x % 2) as f32))));
+        let mut texture = Texture::build(stripes);
+        texture.generate_mipmaps(MipFilter::Box);
+        texture.filter = Filter::Trilinear;
+
+        let (uv, ddx, ddy) = (vec2!(0.5, 0.5), vec2!(0.5, 0.), vec2!(0., 1. / 16.));
+        assert!(close(texture.sample_grad(uv, ddx, ddy), Vector3::splat(0.5)));
+        texture.anisotropy = 8;
+        assert!(close(texture.sample_grad(uv, ddx, ddy), Vector3::splat(0.5)));
+
+        let vertical = vec2!(0., 1. / 16.);
+        let isotropic = texture.sample_grad(vec2!(1.5 / 16., 0.5), vec2!(1. / 16., 0.), vertical);
+        assert!(close(isotropic, Vector3::splat(1.)));
+    }
+
+    #[test]
+    fn receding_plane() {
+        let mut texture = Texture::build(checker(64, 64));
+        texture.generate_mipmaps(MipFilter::Box);
+        texture.filter = Filter::Trilinear;
+        texture.anisotropy = 4;
+
+        let mut pipeline = Pipeline::build(texture);
+        pipeline.rasterizer.cull = Cull::None;
+        let transform = crate::render::pipeline::perspective(1.2, 1., 0.1, 100.);
+        let floor = [
+            (-4., -1., -1., 0., 1.),
+            (4., -1., -1., 1., 1.),
+            (4., -1., -60., 1., 0.),
+            (-4., -1., -60., 0., 0.),
+        ];
+
+        let mut bitmap = Bitmap::<PackedColor, ()>::new(32, 32, 0, f32::INFINITY);
+        let mut derivatives = Vec::new();
+        pipeline.draw_indexed(
+            &mut bitmap,
+            &floor,
+            &[[0, 1, 2], [0, 2, 3]],
+            |&(x, y, z, u, v)| Vertex::build(transform * vec4!(x, y, z, 1.), vec2!(u, v)),
+            |fragment, sampler| {
+                derivatives.push((fragment.position.y, fragment.ddx, fragment.ddy));
+                Some(pack_color(sampler.sample_grad(fragment.varying, fragment.ddx, fragment.ddy)))
+            },
+        );
+
+        let horizon = derivatives.iter().map(|&(y, _, _)| y).min().unwrap();
+        let far: Vec<_> = derivatives.iter().filter(|&&(y, _, _)| y <= horizon + 1).collect();
+        let near: Vec<_> = derivatives.iter().filter(|&&(y, _, _)| y == 31).collect();
+        assert!(far.iter().all(|(_, _, ddy)| ddy.y.abs() > near[0].2.y.abs() * 4.));
+        assert!(near.iter().all(|(_, ddx, _)| (ddx.x - near[0].1.x).abs() < 1e-4 && ddx.y.abs() < 1e-4));
+        assert!(far.iter().all(|(_, ddx, _)| ddx.x > near[0].1.x * 4.));
+
+        let row = |y: usize| (0..32).filter_map(|x| bitmap.main.get(x, y)).filter(|&c| c != 0).collect::<Vec<_>>();
+        let far_row = row(horizon + 1);
+        let grey = far_row.iter().filter(|&&color| (0x60..=0xa0).contains(&(color & 0xff))).count();
+        assert!(!far_row.is_empty() && grey * 10 >= far_row.len() * 9);
+    }
+}
@@ -0,0 +1,115 @@
+#!/usr/bin/env python3
+# Regenerates the image fixtures used by containers::buffer::image tests with an independent writer.
+import struct
+import zlib
+from pathlib import Path
+
+W, H = 5, 3
+HERE = Path(__file__).parent
+
+
+def rgb(x, y):
+    return (x * 60, y * 100 + 20, (x + y) * 30)
+
+
+def alpha(x, y):
+    return 255 - x * 50 - y * 10
+
+
+def grey(x, y):
+    return x * 50 + y * 5
+
+
+def rows(pixel):
+    return [[pixel(x, y) for x in range(W)] for y in range(H)]
+
+
+def write(name, data):
+    (HERE / name).write_bytes(data)
+
+
+# PNM
+write("pattern.ppm", b"P6\n# golden\n5 3\n255\n" + bytes(c for row in rows(rgb) for p in row for c in p))
+ascii_rgb = "\n".join(" ".join("%d %d %d" % p for p in row) for row in rows(rgb))
+write("pattern_ascii.ppm", ("P3\n5 3 # width height\n255\n" + ascii_rgb + "\n").encode())
+write("grey.pgm", b"P5 5 3 255\n" + bytes(v for row in rows(grey) for v in row))
+ascii_grey = "\n".join(" ".join("%d" % (x * 3 + y) for x in range(W)) for y in range(H))
+write("grey_ascii.pgm", ("P2\n5 3\n15\n" + ascii_grey + "\n").encode())
+
+
+# BMP
+def bmp(bits):
+    stride = (W * bits // 8 + 3) & ~3
+    pixels = b""
+    for y in reversed(range(H)):
+        row = b""
+        for x in range(W):
+            r, g, b = rgb(x, y)
+            row += bytes((b, g, r)) + (bytes((alpha(x, y),)) if bits == 32 else b"")
+        pixels += row + b"\0" * (stride - len(row))
+    header = struct.pack("<IiiHHIIiiII", 40, W, H, 1, bits, 0, len(pixels), 2835, 2835, 0, 0)
+    return b"BM" + struct.pack("<IHHI", 54 + len(pixels), 0, 0, 54) + header + pixels
+
+
+write("pattern.bmp", bmp(24))
+write("alpha.bmp", bmp(32))
+
+
+# TGA
+def tga(kind, bits, descriptor, order, pixel):
+    header = struct.pack("<BBBHHBHHHHBB", 0, 0, kind, 0, 0, 0, 0, 0, W, H, bits, descriptor)
+    return header + b"".join(bytes(pixel(x, y)) for y in order for x in range(W))
+
+
+write("pattern.tga", tga(2, 24, 0x20, range(H), lambda x, y: rgb(x, y)[::-1]))
+write("alpha.tga", tga(2, 32, 0x28, range(H), lambda x, y: rgb(x, y)[::-1] + (alpha(x, y),)))
+write("grey.tga", tga(3, 8, 0x00, reversed(range(H)), lambda x, y: (grey(x, y),)))
+
+
+# PNG
+def chunk(kind, data):
+    return struct.pack(">I", len(data)) + kind + data + struct.pack(">I", zlib.crc32(kind + data))
+
+
+def paeth(a, b, c):
+    p = a + b - c
+    pa, pb, pc = abs(p - a), abs(p - b), abs(p - c)
+    return a if pa <= pb and pa <= pc else b if pb <= pc else c
+
+
+def filtered(lines, bpp):
+    out, previous = b"", bytes(len(lines[0]))
+    for index, line in enumerate(lines):
+        kind = index % 5
+        row = bytearray()
+        for i, value in enumerate(line):
+            a = line[i - bpp] if i >= bpp else 0
+            b = previous[i]
+            c = previous[i - bpp] if i >= bpp else 0
+            predictor = [0, a, b, (a + b) // 2, paeth(a, b, c)][kind]
+            row.append((value - predictor) & 0xFF)
+        out += bytes((kind,)) + bytes(row)
+        previous = line
+    return out
+
+
+def png(color_type, depth, lines, bpp, extra=b""):
+    header = struct.pack(">IIBBBBB", W, H, depth, color_type, 0, 0, 0)
+    body = zlib.compress(filtered(lines, bpp), 9)
+    return b"\x89PNG\r\n\x1a\n" + chunk(b"IHDR", header) + extra + chunk(b"IDAT", body) + chunk(b"IEND", b"")
+
+
+write("pattern.png", png(2, 8, [bytes(c for p in row for c in p) for row in rows(rgb)], 3))
+rgba = [bytes(c for x in range(W) for c in rgb(x, y) + (alpha(x, y),)) for y in range(H)]
+write("alpha.png", png(6, 8, rgba, 4))
+write("grey16.png", png(0, 16, [b"".join(struct.pack(">H", v * 257) for v in row) for row in rows(grey)], 2))
+
+palette = bytes((255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255))
+indices = []
+for y in range(H):
+    bits = [(x + y) % 4 for x in range(W)] + [0] * 3
+    indices.append(bytes(bits[i] << 6 | bits[i + 1] << 4 | bits[i + 2] << 2 | bits[i + 3] for i in range(0, W, 4)))
+write("palette.png", png(3, 2, indices, 1, chunk(b"PLTE", palette) + chunk(b"tRNS", bytes((255, 128)))))
+
+# zlib stream with a dynamic huffman block for containers::buffer::deflate
+write("squares.zlib", zlib.compress(b"".join(b"%d squared is %d; " % (i, i * i) for i in range(40)), 9))